@@ -5,6 +5,7 @@ use once_cell::sync::Lazy;
 use rl::{
     algo::dqn::{DQNAgent, DQNAgentConfig},
     gym::CartPole,
+    trainer::{Trainer, TrainerConfig},
     viz,
 };
 
@@ -23,17 +24,14 @@ fn main() {
     let agent_config = DQNAgentConfig::default();
     let mut agent = DQNAgent::new(model, agent_config, &*DEVICE);
 
-    let (handle, tx) = viz::init(env.report.keys(), NUM_EPISODES);
-
-    for i in 0..NUM_EPISODES {
-        agent.go(&mut env);
-        let report = env.report.take();
-        tx.send(viz::Update {
-            episode: i,
-            data: report.values().copied().collect(),
-        })
-        .unwrap();
-    }
+    let (handle, tx) = viz::init(NUM_EPISODES);
+
+    Trainer::new(TrainerConfig {
+        max_episodes: Some(NUM_EPISODES.into()),
+        ..Default::default()
+    })
+    .with_sink(tx)
+    .train(&mut agent, &mut env);
 
     let _ = handle.join();
 }
@@ -1,18 +1,14 @@
 use std::{collections::HashMap, ops::Range};
 
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use rand_distr::Distribution;
-use rl::env::{DiscreteActionSpace, DiscreteStateSpace, Environment};
+use rl::env::{
+    DiscreteActionSpace, DiscreteStateSpace, Environment, Outcome, StepResult, TransitionModel,
+};
 use statrs::distribution::{Discrete, Poisson};
 
 type PMFCache = HashMap<(i32, i32), f64>;
 
-pub struct Outcome {
-    pub next_state: [i32; 2],
-    pub reward: f32,
-    pub prob: f32,
-}
-
 struct Location {
     inventory: i32,
     requests: Poisson,
@@ -32,6 +28,7 @@ impl Location {
 pub struct CarRental {
     locations: [Location; 2],
     pmf_cache: PMFCache,
+    rng: StdRng,
 }
 
 impl CarRental {
@@ -39,6 +36,7 @@ impl CarRental {
         Self {
             locations: [Location::new(10, 3.0, 3.0), Location::new(10, 4.0, 2.0)],
             pmf_cache: generate_pmf_cache(&[2, 3, 4], 0..10),
+            rng: StdRng::from_entropy(),
         }
     }
 
@@ -50,8 +48,10 @@ impl CarRental {
     fn get_state(&self) -> [i32; 2] {
         [self.locations[0].inventory, self.locations[1].inventory]
     }
+}
 
-    pub fn dynamics(&mut self, state: [i32; 2], action: i32) -> Vec<Outcome> {
+impl TransitionModel for CarRental {
+    fn outcomes(&self, state: Self::State, action: Self::Action) -> Vec<Outcome<Self::State>> {
         let mut outcomes = Vec::with_capacity(10000);
         let (loc1_req_lambda, loc2_req_lambda, loc1_ret_lambda, loc2_ret_lambda) = (3, 4, 3, 2);
         for loc1_req in 0..10 {
@@ -65,7 +65,7 @@ impl CarRental {
                         let (next_state, reward) =
                             transition(&state, action, loc1_req, loc2_req, loc1_ret, loc2_ret);
                         outcomes.push(Outcome {
-                            next_state,
+                            next_state: Some(next_state),
                             reward,
                             prob: (loc1_req_p * loc2_req_p * loc1_ret_p * loc2_ret_p) as f32,
                         });
@@ -76,6 +76,15 @@ impl CarRental {
 
         outcomes
     }
+
+    /// Cannot move more cars than are present at a location
+    fn available_actions(&self, state: Self::State) -> Vec<Self::Action> {
+        let [i1, i2] = state;
+        self.actions()
+            .into_iter()
+            .filter(|action| (-i2..i1).contains(action))
+            .collect()
+    }
 }
 
 impl Environment for CarRental {
@@ -84,17 +93,17 @@ impl Environment for CarRental {
     /// Number of cars moved from location 1 to location 2
     type Action = i32;
 
-    fn random_action(&self) -> Self::Action {
-        rand::thread_rng().gen_range(-5..=5)
+    fn random_action(&mut self) -> Self::Action {
+        self.rng.gen_range(-5..=5)
     }
 
-    fn step(&mut self, action: Self::Action) -> (Option<Self::State>, f32) {
-        let mut rng = rand::thread_rng();
+    fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
+        let rng = &mut self.rng;
         let (loc1_req, loc2_req, loc1_ret, loc2_ret) = (
-            self.locations[0].requests.sample(&mut rng).round() as i32,
-            self.locations[1].requests.sample(&mut rng).round() as i32,
-            self.locations[0].returns.sample(&mut rng).round() as i32,
-            self.locations[1].returns.sample(&mut rng).round() as i32,
+            self.locations[0].requests.sample(rng).round() as i32,
+            self.locations[1].requests.sample(rng).round() as i32,
+            self.locations[0].returns.sample(rng).round() as i32,
+            self.locations[1].returns.sample(rng).round() as i32,
         );
 
         let (next_state, reward) = transition(
@@ -107,7 +116,7 @@ impl Environment for CarRental {
         );
         self.set_state(next_state);
 
-        (Some(next_state), reward as f32)
+        StepResult::new(Some(next_state), reward as f32)
     }
 
     fn reset(&mut self) -> Self::State {
@@ -117,6 +126,10 @@ impl Environment for CarRental {
 
         self.get_state()
     }
+
+    fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
 }
 
 impl DiscreteStateSpace for CarRental {
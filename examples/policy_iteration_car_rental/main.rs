@@ -1,20 +1,31 @@
 use std::{error::Error, fs};
 
-use agent::PolicyIterationAgent;
 use env::CarRental;
+use rl::{
+    algo::dp::{DPAgent, DPConfig},
+    env::DiscreteStateSpace,
+};
 
-mod agent;
 mod env;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let mut env = CarRental::new();
-    let mut agent = PolicyIterationAgent::new(0.9);
+    let env = CarRental::new();
+    let config = DPConfig {
+        gamma: 0.9,
+        ..Default::default()
+    };
+    // Start from the policy of never moving any cars
+    let initial_policy = env.states().into_iter().map(|s| (s, 0)).collect();
+    let mut agent = DPAgent::new(config).with_policy(initial_policy);
 
     let mut policies = Vec::with_capacity(5);
 
     for i in 0..5 {
         println!("Iteration {}", i + 1);
-        agent.learn(&mut env, 1);
+        println!("Evaluating...");
+        agent.evaluate_policy(&env);
+        println!("Improving...");
+        agent.improve_policy(&env);
         let mut policy = agent.policy().clone().into_iter().collect::<Vec<_>>();
         policy.sort_unstable_by_key(|(k, _)| *k);
 
@@ -0,0 +1,41 @@
+use burn::backend::{wgpu::WgpuDevice, Autodiff, Wgpu};
+use gym_rs::utils::renderer::RenderMode;
+use model::ModelConfig;
+use once_cell::sync::Lazy;
+use rl::{
+    algo::ppo::{PPOAgent, PPOAgentConfig},
+    gym::CartPole,
+    trainer::{Trainer, TrainerConfig},
+    viz,
+};
+
+mod model;
+
+type PPOBackend = Autodiff<Wgpu>;
+
+static DEVICE: Lazy<WgpuDevice> = Lazy::new(WgpuDevice::default);
+
+const NUM_EPISODES: u16 = 512;
+
+fn main() {
+    let mut env = CartPole::new(RenderMode::Human);
+
+    let model = ModelConfig::new(64).init::<PPOBackend>(&*DEVICE);
+    let agent_config = PPOAgentConfig {
+        n_steps: 512,
+        entropy_coef: 0.01,
+        ..Default::default()
+    };
+    let mut agent = PPOAgent::new(model, agent_config, &*DEVICE);
+
+    let (handle, tx) = viz::init(NUM_EPISODES);
+
+    Trainer::new(TrainerConfig {
+        max_episodes: Some(NUM_EPISODES.into()),
+        ..Default::default()
+    })
+    .with_sink(tx)
+    .train(&mut agent, &mut env);
+
+    let _ = handle.join();
+}
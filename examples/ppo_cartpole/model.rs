@@ -0,0 +1,41 @@
+use burn::{
+    prelude::*,
+    tensor::{activation::tanh, backend::AutodiffBackend},
+};
+use nn::{Linear, LinearConfig};
+use rl::algo::policy_gradient::ActorCriticModel;
+
+/// Separate two-layer perceptrons for the policy and the value function
+#[derive(Module, Debug)]
+pub struct Model<B: Backend> {
+    actor_fc: Linear<B>,
+    actor_out: Linear<B>,
+    critic_fc: Linear<B>,
+    critic_out: Linear<B>,
+}
+
+#[derive(Config, Debug)]
+pub struct ModelConfig {
+    hidden: usize,
+}
+
+impl ModelConfig {
+    pub fn init<B: Backend>(&self, device: &B::Device) -> Model<B> {
+        Model {
+            actor_fc: LinearConfig::new(4, self.hidden).init(device),
+            actor_out: LinearConfig::new(self.hidden, 2).init(device),
+            critic_fc: LinearConfig::new(4, self.hidden).init(device),
+            critic_out: LinearConfig::new(self.hidden, 1).init(device),
+        }
+    }
+}
+
+impl<B: AutodiffBackend> ActorCriticModel<B, 2> for Model<B> {
+    fn forward(&self, input: Tensor<B, 2>) -> (Tensor<B, 2>, Tensor<B, 2>) {
+        let logits = self
+            .actor_out
+            .forward(tanh(self.actor_fc.forward(input.clone())));
+        let values = self.critic_out.forward(tanh(self.critic_fc.forward(input)));
+        (logits, values)
+    }
+}
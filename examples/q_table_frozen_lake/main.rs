@@ -3,6 +3,7 @@ use rl::{
     decay,
     exploration::EpsilonGreedy,
     gym::FrozenLake,
+    trainer::{Trainer, TrainerConfig},
     viz,
 };
 
@@ -16,17 +17,14 @@ fn main() {
     };
     let mut agent = QTableAgent::new(config);
 
-    let (handle, tx) = viz::init(env.report.keys(), NUM_EPISODES);
+    let (handle, tx) = viz::init(NUM_EPISODES);
 
-    for i in 0..NUM_EPISODES {
-        agent.go(&mut env);
-        let report = env.report.take();
-        tx.send(viz::Update {
-            episode: i,
-            data: report.values().copied().collect(),
-        })
-        .unwrap();
-    }
+    Trainer::new(TrainerConfig {
+        max_episodes: Some(NUM_EPISODES.into()),
+        ..Default::default()
+    })
+    .with_sink(tx)
+    .train(&mut agent, &mut env);
 
     let _ = handle.join();
 }
@@ -0,0 +1,38 @@
+use burn::backend::{wgpu::WgpuDevice, Autodiff, Wgpu};
+use gym_rs::utils::renderer::RenderMode;
+use model::ModelConfig;
+use once_cell::sync::Lazy;
+use rl::{
+    algo::reinforce::{ReinforceAgent, ReinforceAgentConfig},
+    gym::CartPole,
+    trainer::{Trainer, TrainerConfig},
+    viz,
+};
+
+mod model;
+
+type ReinforceBackend = Autodiff<Wgpu>;
+
+static DEVICE: Lazy<WgpuDevice> = Lazy::new(WgpuDevice::default);
+
+const NUM_EPISODES: u16 = 512;
+
+fn main() {
+    let mut env = CartPole::new(RenderMode::Human);
+
+    let policy = ModelConfig::new(128, 2).init::<ReinforceBackend>(&*DEVICE);
+    let baseline = ModelConfig::new(128, 1).init::<ReinforceBackend>(&*DEVICE);
+    let agent_config = ReinforceAgentConfig::default();
+    let mut agent = ReinforceAgent::new(policy, Some(baseline), agent_config, &*DEVICE);
+
+    let (handle, tx) = viz::init(NUM_EPISODES);
+
+    Trainer::new(TrainerConfig {
+        max_episodes: Some(NUM_EPISODES.into()),
+        ..Default::default()
+    })
+    .with_sink(tx)
+    .train(&mut agent, &mut env);
+
+    let _ = handle.join();
+}
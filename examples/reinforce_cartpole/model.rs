@@ -0,0 +1,47 @@
+use burn::{
+    prelude::*,
+    tensor::{activation::relu, backend::AutodiffBackend},
+};
+use nn::{Linear, LinearConfig};
+use rl::algo::policy_gradient::{PolicyModel, ValueModel};
+
+/// A two-layer perceptron used for both the policy (2 outputs) and the baseline (1 output)
+#[derive(Module, Debug)]
+pub struct Model<B: Backend> {
+    fc1: Linear<B>,
+    fc2: Linear<B>,
+}
+
+#[derive(Config, Debug)]
+pub struct ModelConfig {
+    hidden: usize,
+    outputs: usize,
+}
+
+impl ModelConfig {
+    pub fn init<B: Backend>(&self, device: &B::Device) -> Model<B> {
+        Model {
+            fc1: LinearConfig::new(4, self.hidden).init(device),
+            fc2: LinearConfig::new(self.hidden, self.outputs).init(device),
+        }
+    }
+}
+
+impl<B: Backend> Model<B> {
+    fn forward(&self, input: Tensor<B, 2>) -> Tensor<B, 2> {
+        let x = relu(self.fc1.forward(input));
+        self.fc2.forward(x)
+    }
+}
+
+impl<B: AutodiffBackend> PolicyModel<B, 2> for Model<B> {
+    fn forward(&self, input: Tensor<B, 2>) -> Tensor<B, 2> {
+        Model::forward(self, input)
+    }
+}
+
+impl<B: AutodiffBackend> ValueModel<B, 2> for Model<B> {
+    fn forward(&self, input: Tensor<B, 2>) -> Tensor<B, 2> {
+        Model::forward(self, input)
+    }
+}
@@ -0,0 +1,40 @@
+use burn::backend::{wgpu::WgpuDevice, Autodiff, Wgpu};
+use model::{ActorConfig, CriticConfig};
+use once_cell::sync::Lazy;
+use rl::{
+    algo::sac::{SACAgent, SACAgentConfig},
+    gym::Pendulum,
+    trainer::{Trainer, TrainerConfig},
+    viz,
+};
+
+mod model;
+
+type SACBackend = Autodiff<Wgpu>;
+
+static DEVICE: Lazy<WgpuDevice> = Lazy::new(WgpuDevice::default);
+
+const NUM_EPISODES: u16 = 128;
+
+fn main() {
+    let mut env = Pendulum::default();
+
+    let actor = ActorConfig::new(256).init::<SACBackend>(&*DEVICE);
+    let critics = [
+        CriticConfig::new(256).init::<SACBackend>(&*DEVICE),
+        CriticConfig::new(256).init::<SACBackend>(&*DEVICE),
+    ];
+    let agent_config = SACAgentConfig::default();
+    let mut agent = SACAgent::new(actor, critics, agent_config, &*DEVICE);
+
+    let (handle, tx) = viz::init(NUM_EPISODES);
+
+    Trainer::new(TrainerConfig {
+        max_episodes: Some(NUM_EPISODES.into()),
+        ..Default::default()
+    })
+    .with_sink(tx)
+    .train(&mut agent, &mut env);
+
+    let _ = handle.join();
+}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use rand::{rngs::StdRng, SeedableRng};
 use rl::{
     decay,
     env::{DiscreteActionSpace, Environment},
@@ -16,6 +17,7 @@ pub struct SarsaAgent {
     alpha: f32,
     gamma: f32,
     episode: u32,
+    rng: StdRng,
 }
 
 impl SarsaAgent {
@@ -26,12 +28,13 @@ impl SarsaAgent {
             alpha,
             gamma,
             episode: 0,
+            rng: StdRng::from_entropy(),
         }
     }
 
     /// Choose an action based on the current state and exploration policy
-    fn act(&self, env: &E, state: State) -> Action {
-        match self.exploration.choose(self.episode) {
+    fn act(&mut self, env: &mut E, state: State) -> Action {
+        match self.exploration.choose(self.episode, &mut self.rng) {
             Choice::Explore => env.random_action(),
             Choice::Exploit => *env
                 .actions()
@@ -61,15 +64,17 @@ impl SarsaAgent {
 
     pub fn go(&mut self, env: &mut E) {
         let mut next_state = env.reset();
-        let mut next_action = self.act(&env, next_state);
+        let mut next_action = self.act(env, next_state);
 
         loop {
             let (state, action) = (next_state, next_action);
-            let (Some(next), reward) = env.step(next_action) else {
+            let step = env.step(next_action);
+            let Some(next) = step.next_state.filter(|_| !step.truncated) else {
                 break;
             };
+            let reward = step.reward;
             next_state = next;
-            next_action = self.act(&env, state);
+            next_action = self.act(env, state);
 
             self.learn(state, action, reward, next_state, next_action);
         }
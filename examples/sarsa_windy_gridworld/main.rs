@@ -1,7 +1,7 @@
 use std::{error::Error, fs, path::Path};
 
 use agent::SarsaAgent;
-use rl::gym::WindyGridworld;
+use rl::{env::Environment, gym::WindyGridworld};
 
 mod agent;
 
@@ -20,7 +20,10 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     for i in 0..NUM_EPISODES {
         agent.go(&mut env);
-        wtr.write_record(&[&env.report["steps"].to_string(), &i.to_string()])?;
+        wtr.write_record(&[
+            &env.report().unwrap()["steps"].value().to_string(),
+            &i.to_string(),
+        ])?;
     }
 
     wtr.flush()?;
@@ -0,0 +1,38 @@
+use burn::backend::{wgpu::WgpuDevice, Autodiff, Wgpu};
+use model::{ActorConfig, CriticConfig};
+use once_cell::sync::Lazy;
+use rl::{
+    algo::ddpg::{DDPGAgent, DDPGAgentConfig},
+    gym::Pendulum,
+    trainer::{Trainer, TrainerConfig},
+    viz,
+};
+
+mod model;
+
+type TD3Backend = Autodiff<Wgpu>;
+
+static DEVICE: Lazy<WgpuDevice> = Lazy::new(WgpuDevice::default);
+
+const NUM_EPISODES: u16 = 128;
+
+fn main() {
+    let mut env = Pendulum::default();
+
+    let actor = ActorConfig::new(256).init::<TD3Backend>(&*DEVICE);
+    let critic = CriticConfig::new(256).init::<TD3Backend>(&*DEVICE);
+    let twin_critic = CriticConfig::new(256).init::<TD3Backend>(&*DEVICE);
+    let agent_config = DDPGAgentConfig::td3();
+    let mut agent = DDPGAgent::new(actor, critic, Some(twin_critic), agent_config, &*DEVICE);
+
+    let (handle, tx) = viz::init(NUM_EPISODES);
+
+    Trainer::new(TrainerConfig {
+        max_episodes: Some(NUM_EPISODES.into()),
+        ..Default::default()
+    })
+    .with_sink(tx)
+    .train(&mut agent, &mut env);
+
+    let _ = handle.join();
+}
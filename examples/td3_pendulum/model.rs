@@ -0,0 +1,71 @@
+use burn::{
+    prelude::*,
+    tensor::{
+        activation::{relu, tanh},
+        backend::AutodiffBackend,
+    },
+};
+use nn::{Linear, LinearConfig};
+use rl::algo::continuous::{CriticModel, DeterministicPolicyModel};
+
+/// A two-layer perceptron producing the pendulum's torque, squashed into `[-1, 1]`
+#[derive(Module, Debug)]
+pub struct Actor<B: Backend> {
+    fc1: Linear<B>,
+    fc2: Linear<B>,
+    out: Linear<B>,
+}
+
+#[derive(Config, Debug)]
+pub struct ActorConfig {
+    hidden: usize,
+}
+
+impl ActorConfig {
+    pub fn init<B: Backend>(&self, device: &B::Device) -> Actor<B> {
+        Actor {
+            fc1: LinearConfig::new(3, self.hidden).init(device),
+            fc2: LinearConfig::new(self.hidden, self.hidden).init(device),
+            out: LinearConfig::new(self.hidden, 1).init(device),
+        }
+    }
+}
+
+impl<B: AutodiffBackend> DeterministicPolicyModel<B, 2> for Actor<B> {
+    fn forward(&self, input: Tensor<B, 2>) -> Tensor<B, 2> {
+        let x = relu(self.fc1.forward(input));
+        let x = relu(self.fc2.forward(x));
+        tanh(self.out.forward(x))
+    }
+}
+
+/// A two-layer perceptron estimating the Q value of a state and torque
+#[derive(Module, Debug)]
+pub struct Critic<B: Backend> {
+    fc1: Linear<B>,
+    fc2: Linear<B>,
+    out: Linear<B>,
+}
+
+#[derive(Config, Debug)]
+pub struct CriticConfig {
+    hidden: usize,
+}
+
+impl CriticConfig {
+    pub fn init<B: Backend>(&self, device: &B::Device) -> Critic<B> {
+        Critic {
+            fc1: LinearConfig::new(3 + 1, self.hidden).init(device),
+            fc2: LinearConfig::new(self.hidden, self.hidden).init(device),
+            out: LinearConfig::new(self.hidden, 1).init(device),
+        }
+    }
+}
+
+impl<B: AutodiffBackend> CriticModel<B, 2> for Critic<B> {
+    fn forward(&self, state: Tensor<B, 2>, action: Tensor<B, 2>) -> Tensor<B, 2> {
+        let x = relu(self.fc1.forward(Tensor::cat(vec![state, action], 1)));
+        let x = relu(self.fc2.forward(x));
+        self.out.forward(x)
+    }
+}
@@ -6,7 +6,9 @@ use rl::{
         ucb::{UCBAgent, UCBAgentConfig},
     },
     decay,
+    env::Environment,
     gym::KArmedBandit,
+    traits::Agent,
 };
 
 const STEP_LIMIT: usize = 2000;
@@ -32,8 +34,8 @@ fn main() -> Result<(), Box<dyn Error>> {
             agent.go(&mut env);
         }
 
-        let report = env.report.take();
-        let avg_reward = report["reward"] / (NUM_EPISODES * STEP_LIMIT) as f64;
+        let report = env.report().unwrap().take();
+        let avg_reward = report["reward"].value() / (NUM_EPISODES * STEP_LIMIT) as f64;
         e_greedy_data.push((x, avg_reward));
     }
 
@@ -49,8 +51,8 @@ fn main() -> Result<(), Box<dyn Error>> {
             agent.go(&mut env);
         }
 
-        let report = env.report.take();
-        let avg_reward = report["reward"] / (NUM_EPISODES * STEP_LIMIT) as f64;
+        let report = env.report().unwrap().take();
+        let avg_reward = report["reward"].value() / (NUM_EPISODES * STEP_LIMIT) as f64;
         ucb_data.push((x, avg_reward));
     }
 
@@ -67,8 +69,8 @@ fn main() -> Result<(), Box<dyn Error>> {
             agent.go(&mut env);
         }
 
-        let report = env.report.take();
-        let avg_reward = report["reward"] / (NUM_EPISODES * STEP_LIMIT) as f64;
+        let report = env.report().unwrap().take();
+        let avg_reward = report["reward"].value() / (NUM_EPISODES * STEP_LIMIT) as f64;
         goi_data.push((x, avg_reward));
     }
 
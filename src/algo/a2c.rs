@@ -0,0 +1,397 @@
+use std::fmt::Debug;
+
+use burn::{
+    module::AutodiffModule, optim::GradientsParams, prelude::*, tensor::backend::AutodiffBackend,
+};
+use rand::rngs::StdRng;
+
+use crate::{
+    algo::policy_gradient::{
+        gae, next_state_values, normalize, ActorCriticModel, Categorical, Rollout,
+    },
+    decay::{self, Decay},
+    env::{Aggregation, Environment, MaskedActionSpace, Report},
+    memory::Exp,
+    nn::{legal_mask, GradientClipping, ModelOptimizer, OptimizerConfig},
+    traits::{Agent, ToTensor},
+    util::rng_from_seed,
+};
+
+/// Configuration for the [`A2CAgent`]
+///
+/// ### Generics
+/// - `LR` - The learning rate schedule
+/// - `ENT` - The entropy coefficient schedule
+#[derive(Debug, Clone)]
+pub struct A2CAgentConfig<LR = decay::Constant, ENT = decay::Constant> {
+    /// The number of steps in each rollout, after which the agent learns
+    ///
+    /// Rollouts continue across episode boundaries.
+    ///
+    /// **Default:** `5`
+    pub n_steps: usize,
+    /// The discount factor
+    ///
+    /// **Default:** `0.99`
+    pub gamma: f32,
+    /// The GAE parameter λ, trading off bias (`0.0`) against variance (`1.0`) in the advantage estimates
+    /// (see [`gae`])
+    ///
+    /// **Default:** `1.0`
+    pub gae_lambda: f32,
+    /// The weight of the value loss relative to the policy loss
+    ///
+    /// **Default:** `0.5`
+    pub value_coef: f32,
+    /// The schedule for the weight of the entropy bonus, evaluated at the agent's total number of steps
+    ///
+    /// **Default:** [`Constant`](decay::Constant) coefficient `0.0`
+    pub entropy_coef: ENT,
+    /// Standardize the advantages of each rollout to zero mean and unit variance before the policy update
+    ///
+    /// **Default:** `false`
+    pub normalize_advantages: bool,
+    /// The optimizer to train the model with
+    ///
+    /// **Default:** [`OptimizerConfig::rms_prop`]
+    pub optimizer: OptimizerConfig,
+    /// The gradient clipping applied before each optimizer step, or `None` to leave gradients unclipped
+    ///
+    /// **Default:** `Some(GradientClipping::Norm(0.5))`
+    pub grad_clipping: Option<GradientClipping>,
+    /// The learning rate schedule for the optimizer, evaluated at the agent's total number of steps
+    ///
+    /// **Default:** [`Constant`](decay::Constant) learning rate `7e-4`
+    pub lr_schedule: LR,
+    /// Seed for the agent's random number generator, which drives action sampling, or `None` to seed from system
+    /// entropy
+    ///
+    /// **Default:** `None`
+    pub seed: Option<u64>,
+}
+
+impl Default for A2CAgentConfig {
+    fn default() -> Self {
+        Self {
+            n_steps: 5,
+            gamma: 0.99,
+            gae_lambda: 1.0,
+            value_coef: 0.5,
+            entropy_coef: decay::Constant::new(0.0),
+            normalize_advantages: false,
+            optimizer: OptimizerConfig::rms_prop(),
+            grad_clipping: Some(GradientClipping::Norm(0.5)),
+            lr_schedule: decay::Constant::new(7e-4),
+            seed: None,
+        }
+    }
+}
+
+/// An advantage actor-critic agent ([Mnih et al. 2016](https://arxiv.org/abs/1602.01783)), the synchronous variant
+/// of A3C
+///
+/// The agent collects rollouts of `n_steps` steps, estimates the advantage of each action with [`gae`], and takes
+/// one gradient step on the combined policy, value, and entropy losses. Truncated episodes bootstrap from the value
+/// of their final state.
+///
+/// The agent's [report](Agent::report) records the mean `policy_loss`, `value_loss`, and `entropy` of each
+/// episode's updates.
+///
+/// ### Generics
+/// - `B` - A burn backend
+/// - `M` - The [`ActorCriticModel`] with the policy and value heads
+/// - `E` - The [`Environment`] in which the agent will learn
+///     - The environment's action space must be discrete. Actions that are illegal according to its
+///       [`MaskedActionSpace`] are never chosen.
+/// - `D` - The dimension of the input
+/// - `LR` - The learning rate schedule
+/// - `ENT` - The entropy coefficient schedule
+#[derive(Clone)]
+pub struct A2CAgent<B, M, E, const D: usize, LR = decay::Constant, ENT = decay::Constant>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+    E: Environment,
+    LR: Decay,
+    ENT: Decay,
+{
+    model: Option<M>,
+    optimizer: ModelOptimizer<M, B>,
+    device: &'static B::Device,
+    rollout: Rollout<E>,
+    n_steps: usize,
+    gamma: f32,
+    gae_lambda: f32,
+    value_coef: f32,
+    entropy_coef: ENT,
+    normalize_advantages: bool,
+    lr_schedule: LR,
+    report: Report,
+    total_steps: u32,
+    rng: StdRng,
+}
+
+impl<B, M, E, const D: usize, LR, ENT> A2CAgent<B, M, E, D, LR, ENT>
+where
+    B: AutodiffBackend<FloatElem = f32, IntElem = i32>,
+    M: ActorCriticModel<B, D>,
+    E: MaskedActionSpace,
+    LR: Decay,
+    ENT: Decay,
+    Vec<E::State>: ToTensor<B, D, Float>,
+    Vec<E::Action>: ToTensor<B, 2, Int>,
+    E::Action: From<usize> + Into<usize>,
+{
+    /// Initialize a new `A2CAgent`
+    ///
+    /// ### Arguments
+    /// - `model` An [`ActorCriticModel`] to be trained
+    /// - `config` An [`A2CAgentConfig`] containing hyperparameters for the agent
+    /// - `device` A static reference to the device used for the `model`
+    pub fn new(model: M, config: A2CAgentConfig<LR, ENT>, device: &'static B::Device) -> Self {
+        Self {
+            model: Some(model),
+            optimizer: ModelOptimizer::new(&config.optimizer, config.grad_clipping),
+            device,
+            rollout: Rollout::default(),
+            n_steps: config.n_steps,
+            gamma: config.gamma,
+            gae_lambda: config.gae_lambda,
+            value_coef: config.value_coef,
+            entropy_coef: config.entropy_coef,
+            normalize_advantages: config.normalize_advantages,
+            lr_schedule: config.lr_schedule,
+            report: Report::default()
+                .with_metric("policy_loss", Aggregation::Mean)
+                .with_metric("value_loss", Aggregation::Mean)
+                .with_metric("entropy", Aggregation::Mean),
+            total_steps: 0,
+            rng: rng_from_seed(config.seed),
+        }
+    }
+
+    /// Compute the action distribution of the policy in a single state
+    fn policy(&self, state: E::State, mask: Option<Vec<bool>>) -> Categorical<B> {
+        let (logits, _) = self
+            .model
+            .as_ref()
+            .unwrap()
+            .forward(vec![state].to_tensor(self.device));
+        Categorical::new(logits, &[mask], self.device)
+    }
+
+    /// Update the model from the collected rollout
+    fn learn(&mut self) {
+        let Rollout {
+            states,
+            actions,
+            rewards,
+            masks,
+            next_states,
+            dones,
+        } = std::mem::take(&mut self.rollout);
+        let model = self.model.take().unwrap();
+
+        let next_values = next_state_values(&model, &next_states, self.device);
+
+        // Compute advantages and value targets
+        let (logits, values) = model.forward(states.to_tensor(self.device));
+        let values: Tensor<B, 1> = values.squeeze(1);
+        let value_data = values.clone().into_data().convert::<f32>().value;
+        let advantages = gae(
+            &rewards,
+            &value_data,
+            &next_values,
+            &dones,
+            self.gamma,
+            self.gae_lambda,
+        );
+        let targets = advantages
+            .iter()
+            .zip(&value_data)
+            .map(|(advantage, value)| advantage + value)
+            .collect::<Vec<_>>();
+        let advantages: Tensor<B, 1> = advantages.to_tensor(self.device);
+        let advantages = match self.normalize_advantages && rewards.len() > 1 {
+            true => normalize(advantages),
+            false => advantages,
+        };
+        let targets: Tensor<B, 1> = targets.to_tensor(self.device);
+
+        // Combine the policy, value, and entropy losses
+        let dist = Categorical::new(logits, &masks, self.device);
+        let policy_loss = (dist.log_prob(actions.to_tensor(self.device)) * advantages)
+            .mean()
+            .neg();
+        let value_loss = (values - targets).powf_scalar(2.0).mean();
+        let entropy = dist.entropy().mean();
+        let entropy_coef = self.entropy_coef.evaluate(self.total_steps as f32);
+        let loss = policy_loss.clone() + value_loss.clone() * self.value_coef
+            - entropy.clone() * entropy_coef;
+
+        let grads = GradientsParams::from_grads(loss.backward(), &model);
+        let lr = self.lr_schedule.evaluate(self.total_steps as f32).into();
+        self.model = Some(self.optimizer.step(lr, model, grads));
+
+        for (key, metric) in [
+            ("policy_loss", policy_loss),
+            ("value_loss", value_loss),
+            ("entropy", entropy),
+        ] {
+            let value = metric.into_data().convert::<f32>().value[0];
+            self.report.record(key, value as f64);
+        }
+    }
+}
+
+impl<B, M, E, const D: usize, LR, ENT> Agent<E> for A2CAgent<B, M, E, D, LR, ENT>
+where
+    B: AutodiffBackend<FloatElem = f32, IntElem = i32>,
+    M: ActorCriticModel<B, D>,
+    E: MaskedActionSpace,
+    LR: Decay,
+    ENT: Decay,
+    Vec<E::State>: ToTensor<B, D, Float>,
+    Vec<E::Action>: ToTensor<B, 2, Int>,
+    E::Action: From<usize> + Into<usize>,
+{
+    /// Sample a legal action from the policy
+    fn act(&mut self, env: &mut E, state: E::State) -> E::Action {
+        let mask = legal_mask(env);
+        let action = self.policy(state, mask.clone()).sample(&mut self.rng)[0];
+        self.rollout.masks.push(mask);
+        E::Action::from(action)
+    }
+
+    /// Choose the most likely legal action under the policy
+    fn act_greedy(&mut self, env: &mut E, state: E::State) -> E::Action {
+        E::Action::from(self.policy(state, legal_mask(env)).mode()[0])
+    }
+
+    /// Add the transition to the rollout, and learn once the rollout is full
+    fn observe(&mut self, _env: &E, exp: Exp<E>) {
+        self.rollout.push(exp);
+        self.total_steps += 1;
+
+        if self.rollout.len() >= self.n_steps {
+            self.learn();
+        }
+    }
+
+    /// Mark the last transition as the end of an episode, so advantages are not carried across it
+    fn end_episode(&mut self) {
+        self.rollout.end_episode();
+    }
+
+    fn report(&mut self) -> Option<&mut Report> {
+        Some(&mut self.report)
+    }
+}
+
+impl<B, M, E, const D: usize, LR, ENT> Debug for A2CAgent<B, M, E, D, LR, ENT>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+    E: Environment,
+    LR: Decay,
+    ENT: Decay,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("A2CAgent")
+            .field("n_steps", &self.n_steps)
+            .field("gamma", &self.gamma)
+            .field("gae_lambda", &self.gae_lambda)
+            .field("value_coef", &self.value_coef)
+            .field("normalize_advantages", &self.normalize_advantages)
+            .field("total_steps", &self.total_steps)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algo::tests::{ActorCritic, Signal, B, DEVICE};
+
+    use super::*;
+
+    type TestAgent = A2CAgent<B, ActorCritic<B>, Signal, 2>;
+
+    fn agent(n_steps: usize) -> TestAgent {
+        let config = A2CAgentConfig {
+            n_steps,
+            lr_schedule: decay::Constant::new(1e-2),
+            seed: Some(0),
+            ..Default::default()
+        };
+        TestAgent::new(ActorCritic::new(2, 2), config, &DEVICE)
+    }
+
+    #[test]
+    fn a2c_learns_signal() {
+        let mut agent = agent(4);
+        let mut env = Signal::new(0);
+
+        for _ in 0..2000 {
+            agent.go(&mut env);
+        }
+
+        let report = agent.report().unwrap().take();
+        assert!(
+            report["policy_loss"].count() > 0,
+            "Agent learned from full rollouts"
+        );
+        assert_eq!(
+            agent.evaluate(&mut env, 20).mean_reward,
+            1.0,
+            "Agent follows the signal"
+        );
+    }
+
+    #[test]
+    fn a2c_rollout_spans_episodes() {
+        let mut agent = agent(3);
+        let mut env = Signal::new(0);
+        let state = env.reset();
+        let mut step = |agent: &mut TestAgent, next_state| {
+            let action = agent.act(&mut env, state);
+            agent.observe(
+                &env,
+                Exp {
+                    state,
+                    action,
+                    reward: 1.0,
+                    next_state,
+                    next_mask: None,
+                    steps: 1,
+                },
+            );
+        };
+
+        // A truncated episode followed by the start of the next one
+        step(&mut agent, Some(state));
+        agent.end_episode();
+        step(&mut agent, Some(state));
+        assert_eq!(
+            agent.rollout.dones,
+            [true, false],
+            "Ending the episode marks the last transition as done"
+        );
+        assert_eq!(
+            agent.rollout.next_states,
+            [Some(state), Some(state)],
+            "Truncated episode keeps its final state to bootstrap from"
+        );
+
+        step(&mut agent, None);
+        assert_eq!(
+            agent.rollout.len(),
+            0,
+            "Agent learned from the full rollout"
+        );
+        assert_eq!(
+            agent.report().unwrap().take()["policy_loss"].count(),
+            1,
+            "Agent learned once from a rollout spanning two episodes"
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use std::f32::consts::PI;
+
+use burn::{
+    module::AutodiffModule,
+    prelude::*,
+    tensor::{activation::tanh, backend::AutodiffBackend},
+};
+use rand::Rng;
+use rand_distr::StandardNormal;
+
+use crate::{space::Space, traits::ToTensor};
+
+/// A burn module that outputs the mean and log standard deviation of a Gaussian distribution over actions, used as
+/// the actor of stochastic continuous control agents
+///
+/// Actions are squashed into `[-1, 1]` by `tanh` after sampling, and then rescaled to the bounds of the
+/// environment's action space.
+///
+/// ### Generics
+/// - `B` - A burn backend
+/// - `D` - The dimension of the input tensor
+pub trait GaussianPolicyModel<B: AutodiffBackend, const D: usize>: AutodiffModule<B> {
+    /// Forward pass through the model, producing the mean and log standard deviation of each action dimension, both
+    /// of shape `[batch_size, action_dim]`
+    fn forward(&self, input: Tensor<B, D>) -> (Tensor<B, 2>, Tensor<B, 2>);
+}
+
+/// A burn module that maps states to actions, used as the actor of deterministic continuous control agents
+///
+/// Actions are rescaled from `[-1, 1]` to the bounds of the environment's action space, so the output layer should
+/// squash its values, e.g. with `tanh`.
+///
+/// ### Generics
+/// - `B` - A burn backend
+/// - `D` - The dimension of the input tensor
+pub trait DeterministicPolicyModel<B: AutodiffBackend, const D: usize>: AutodiffModule<B> {
+    /// Forward pass through the model, producing actions in `[-1, 1]` of shape `[batch_size, action_dim]`
+    fn forward(&self, input: Tensor<B, D>) -> Tensor<B, 2>;
+}
+
+/// A burn module that estimates the value of taking a continuous action in a state, used as the critic of
+/// continuous control agents
+///
+/// ### Generics
+/// - `B` - A burn backend
+/// - `D` - The dimension of the state tensor
+pub trait CriticModel<B: AutodiffBackend, const D: usize>: AutodiffModule<B> {
+    /// Forward pass through the model, producing Q values of shape `[batch_size, 1]` from states and actions of shape
+    /// `[batch_size, action_dim]`, where actions are normalized to `[-1, 1]`
+    fn forward(&self, state: Tensor<B, D>, action: Tensor<B, 2>) -> Tensor<B, 2>;
+}
+
+/// Take the elementwise minimum of two tensors
+pub(crate) fn minimum<B: Backend, const D: usize>(
+    a: Tensor<B, D>,
+    b: Tensor<B, D>,
+) -> Tensor<B, D> {
+    a.clone().mask_where(b.clone().lower(a), b)
+}
+
+/// The bounds of a continuous action space, which map actions between the environment's range and `[-1, 1]`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ActionBounds {
+    low: Vec<f32>,
+    high: Vec<f32>,
+}
+
+impl ActionBounds {
+    /// **Panics** if `space` is not a [`Space::Box`] with finite bounds
+    pub(crate) fn new(space: &Space) -> Self {
+        let Space::Box { low, high, .. } = space else {
+            panic!("Continuous control requires a box action space, got {space:?}");
+        };
+        assert!(
+            low.iter().chain(high).all(|x| x.is_finite()),
+            "Continuous control requires a bounded action space"
+        );
+
+        Self {
+            low: low.clone(),
+            high: high.clone(),
+        }
+    }
+
+    /// The number of action dimensions
+    pub(crate) fn dim(&self) -> usize {
+        self.low.len()
+    }
+
+    /// Convert a normalized action in `[-1, 1]` to an environment action
+    pub(crate) fn action<A: TryFrom<Vec<f32>>>(&self, normalized: &[f32]) -> A {
+        let action = normalized
+            .iter()
+            .zip(self.low.iter().zip(&self.high))
+            .map(|(x, (low, high))| low + (x + 1.0) * 0.5 * (high - low))
+            .collect::<Vec<_>>();
+        A::try_from(action)
+            .unwrap_or_else(|_| panic!("Action type has the dimension of the action space"))
+    }
+
+    /// Convert a batch of environment actions to a tensor of normalized actions of shape `[batch_size, action_dim]`
+    pub(crate) fn normalize<B: Backend, A: Into<Vec<f32>>>(
+        &self,
+        actions: Vec<A>,
+        device: &B::Device,
+    ) -> Tensor<B, 2> {
+        let batch_size = actions.len();
+        let normalized = actions
+            .into_iter()
+            .flat_map(|action| {
+                action
+                    .into()
+                    .into_iter()
+                    .zip(self.low.iter().zip(&self.high))
+                    .map(|(x, (low, high))| 2.0 * (x - low) / (high - low) - 1.0)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let tensor: Tensor<B, 1> = normalized.to_tensor(device);
+        tensor.reshape([batch_size, self.dim()])
+    }
+}
+
+/// The bounds of the log standard deviation of a [`SquashedGaussian`], for numerical stability
+const LOG_STD_MIN: f32 = -20.0;
+const LOG_STD_MAX: f32 = 2.0;
+
+/// A batch of diagonal Gaussian distributions over actions whose samples are squashed into `[-1, 1]` by `tanh`
+pub(crate) struct SquashedGaussian<B: Backend> {
+    mean: Tensor<B, 2>,
+    log_std: Tensor<B, 2>,
+}
+
+impl<B: Backend> SquashedGaussian<B> {
+    /// Build the distributions from means and log standard deviations of shape `[batch_size, action_dim]`
+    pub(crate) fn new(mean: Tensor<B, 2>, log_std: Tensor<B, 2>) -> Self {
+        Self {
+            mean,
+            log_std: log_std.clamp(LOG_STD_MIN, LOG_STD_MAX),
+        }
+    }
+
+    /// Sample an action from each distribution with the reparameterization trick, so that gradients flow through
+    /// the sample
+    ///
+    /// **Returns** the actions and the log probability of each one
+    pub(crate) fn rsample(&self, rng: &mut impl Rng) -> (Tensor<B, 2>, Tensor<B, 1>) {
+        let [batch_size, action_dim] = self.mean.dims();
+        let noise = (0..batch_size * action_dim)
+            .map(|_| rng.sample::<f32, _>(StandardNormal))
+            .collect::<Vec<_>>();
+        let noise: Tensor<B, 1> = noise.to_tensor(&self.mean.device());
+        let noise = noise.reshape([batch_size, action_dim]);
+
+        let actions = tanh(self.mean.clone() + self.log_std.clone().exp() * noise.clone());
+        let log_probs = (noise.powf_scalar(2.0) * -0.5
+            - self.log_std.clone()
+            - 0.5 * (2.0 * PI).ln()
+            - (actions.clone().powf_scalar(2.0).neg() + 1.0 + 1e-6).log())
+        .sum_dim(1)
+        .squeeze(1);
+
+        (actions, log_probs)
+    }
+
+    /// The squashed mean of each distribution
+    pub(crate) fn mode(&self) -> Tensor<B, 2> {
+        tanh(self.mean.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn::backend::{ndarray::NdArrayDevice, NdArray as B};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn squashed_gaussian_log_prob() {
+        let device = NdArrayDevice::Cpu;
+        let mean = Tensor::<B, 2>::from_floats([[0.5]], &device);
+        let log_std = Tensor::<B, 2>::from_floats([[-1.0]], &device);
+        let dist = SquashedGaussian::new(mean, log_std);
+
+        let (action, log_prob) = dist.rsample(&mut StdRng::seed_from_u64(0));
+        let action = action.into_scalar();
+        let log_prob = log_prob.into_scalar();
+
+        // Change of variables from the Gaussian sample u = atanh(a)
+        let std = (-1.0f32).exp();
+        let u = action.atanh();
+        let gaussian = -0.5 * ((u - 0.5) / std).powi(2) - std.ln() - 0.5 * (2.0 * PI).ln();
+        let expected = gaussian - (1.0 - action.powi(2) + 1e-6).ln();
+
+        assert!(action.abs() < 1.0, "Action is squashed");
+        assert!(
+            (log_prob - expected).abs() < 1e-3,
+            "Log probability accounts for the squashing"
+        );
+    }
+}
@@ -0,0 +1,642 @@
+use std::fmt::Debug;
+
+use burn::{
+    module::AutodiffModule, optim::GradientsParams, prelude::*, tensor::backend::AutodiffBackend,
+};
+use rand::{rngs::StdRng, Rng};
+use rand_distr::StandardNormal;
+
+use crate::{
+    algo::continuous::{minimum, ActionBounds, CriticModel, DeterministicPolicyModel},
+    decay::{self, Decay},
+    env::{Aggregation, Environment, Report, Spaces},
+    exploration::{ActionNoise, NoiseProcess},
+    memory::{bellman_targets, Exp, ReplayMemory},
+    nn::{soft_update, GradientClipping, ModelOptimizer, OptimizerConfig},
+    traits::{Agent, ToTensor},
+    util::rng_from_seed,
+};
+
+/// Clipped Gaussian noise added to the target actor's actions when computing critic targets, so that the critics
+/// are fit to a smoothed estimate of the value of nearby actions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TargetPolicyNoise {
+    /// The standard deviation of the noise, relative to the normalized action range `[-1, 1]`
+    pub std: f32,
+    /// The noise is clipped to `[-clip, clip]`
+    pub clip: f32,
+}
+
+/// Configuration for the [`DDPGAgent`]
+///
+/// Use [`DDPGAgentConfig::td3`] for the TD3 variant.
+///
+/// ### Generics
+/// - `LR` - The learning rate schedule
+#[derive(Debug, Clone)]
+pub struct DDPGAgentConfig<LR = decay::Constant> {
+    /// The maximum number of experiences held in replay memory
+    ///
+    /// **Default:** `100000`
+    pub memory_capacity: usize,
+    /// The number of experiences sampled from replay memory for each gradient step
+    ///
+    /// **Default:** `256`
+    pub memory_batch_size: usize,
+    /// The discount factor
+    ///
+    /// **Default:** `0.99`
+    pub gamma: f32,
+    /// The Polyak averaging coefficient τ of the target actor and critics (see [`soft_update`])
+    ///
+    /// **Default:** `0.005`
+    pub tau: f32,
+    /// The number of steps taken with uniformly random actions before learning begins
+    ///
+    /// **Default:** `100`
+    pub learning_starts: usize,
+    /// The number of steps between training rounds
+    ///
+    /// **Default:** `1`
+    pub train_freq: usize,
+    /// The number of gradient steps in each training round
+    ///
+    /// **Default:** `1`
+    pub gradient_steps: usize,
+    /// The exploration noise added to the actor's actions, or `None` to act deterministically
+    ///
+    /// **Default:** `Some(ActionNoise::gaussian(0.1))`
+    pub action_noise: Option<ActionNoise>,
+    /// The number of critic updates per update of the actor and the target networks
+    ///
+    /// **Default:** `1`
+    pub policy_delay: usize,
+    /// The smoothing noise added to target actions, or `None` to use the target actor's actions as is
+    ///
+    /// **Default:** `None`
+    pub target_policy_noise: Option<TargetPolicyNoise>,
+    /// The optimizer to train the actor and critics with
+    ///
+    /// **Default:** [`OptimizerConfig::adam`]
+    pub optimizer: OptimizerConfig,
+    /// The gradient clipping applied before each optimizer step, or `None` to leave gradients unclipped
+    ///
+    /// **Default:** `None`
+    pub grad_clipping: Option<GradientClipping>,
+    /// The learning rate schedule for the optimizers, evaluated at the agent's total number of steps
+    ///
+    /// **Default:** [`Constant`](decay::Constant) learning rate `1e-3`
+    pub lr_schedule: LR,
+    /// Seed for the agent's random number generator, which drives exploration noise and replay memory sampling, or
+    /// `None` to seed from system entropy
+    ///
+    /// **Default:** `None`
+    pub seed: Option<u64>,
+}
+
+impl Default for DDPGAgentConfig {
+    fn default() -> Self {
+        Self {
+            memory_capacity: 100000,
+            memory_batch_size: 256,
+            gamma: 0.99,
+            tau: 0.005,
+            learning_starts: 100,
+            train_freq: 1,
+            gradient_steps: 1,
+            action_noise: Some(ActionNoise::gaussian(0.1)),
+            policy_delay: 1,
+            target_policy_noise: None,
+            optimizer: OptimizerConfig::adam(),
+            grad_clipping: None,
+            lr_schedule: decay::Constant::new(1e-3),
+            seed: None,
+        }
+    }
+}
+
+impl DDPGAgentConfig {
+    /// The configuration of TD3 ([Fujimoto et al. 2018](https://arxiv.org/abs/1802.09477)), which delays actor
+    /// updates to every second critic update and smooths target actions with noise of standard deviation `0.2`
+    /// clipped to `0.5`
+    ///
+    /// Pass a twin critic to [`DDPGAgent::new`] for TD3's clipped double-Q learning.
+    pub fn td3() -> Self {
+        Self {
+            policy_delay: 2,
+            target_policy_noise: Some(TargetPolicyNoise {
+                std: 0.2,
+                clip: 0.5,
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+/// A Deep Deterministic Policy Gradient agent ([Lillicrap et al. 2015](https://arxiv.org/abs/1509.02971)) for
+/// continuous action spaces, which also implements TD3
+///
+/// The agent learns a deterministic actor that maximizes a critic's estimate of the value of its actions, and
+/// explores by adding [`ActionNoise`] to them. The critic is trained from a [`ReplayMemory`] toward the estimates of
+/// a target critic and a target actor, which follow the critic and actor by Polyak averaging.
+///
+/// TD3 ([`DDPGAgentConfig::td3`]) adds three changes to counter overestimation:
+/// - **Clipped double-Q learning** - with a twin critic, targets use the smaller of the two target critics' estimates
+/// - **Delayed policy updates** - the actor and the target networks are updated less often than the critics
+/// - **Target policy smoothing** - noise is added to target actions (see [`TargetPolicyNoise`])
+///
+/// The environment's [action space](Spaces::action_space) must be a [`Space::Box`](crate::space::Space::Box) with
+/// finite bounds, and its actions must convert to and from vectors of the same length, e.g. `[f32; N]`.
+///
+/// The agent's [report](Agent::report) records the mean `actor_loss` and `critic_loss` of each episode's gradient
+/// steps.
+///
+/// ### Generics
+/// - `B` - A burn backend
+/// - `A` - The [`DeterministicPolicyModel`] used as the actor
+/// - `C` - The [`CriticModel`] used for the critic and the optional twin critic
+/// - `E` - The [`Environment`] in which the agent will learn
+/// - `D` - The dimension of the input
+/// - `LR` - The learning rate schedule
+#[derive(Clone)]
+pub struct DDPGAgent<B, A, C, E, const D: usize, LR = decay::Constant>
+where
+    B: AutodiffBackend,
+    A: AutodiffModule<B>,
+    C: AutodiffModule<B>,
+    E: Environment,
+    LR: Decay,
+{
+    actor: Option<A>,
+    target_actor: Option<A>,
+    actor_optimizer: ModelOptimizer<A, B>,
+    critics: Vec<C>,
+    target_critics: Vec<C>,
+    critic_optimizers: Vec<ModelOptimizer<C, B>>,
+    device: &'static B::Device,
+    memory: ReplayMemory<E>,
+    bounds: Option<ActionBounds>,
+    noise: Option<NoiseProcess>,
+    gamma: f32,
+    tau: f32,
+    learning_starts: usize,
+    train_freq: usize,
+    gradient_steps: usize,
+    policy_delay: usize,
+    target_policy_noise: Option<TargetPolicyNoise>,
+    lr_schedule: LR,
+    report: Report,
+    total_steps: usize,
+    total_gradient_steps: usize,
+    rng: StdRng,
+}
+
+impl<B, A, C, E, const D: usize, LR> DDPGAgent<B, A, C, E, D, LR>
+where
+    B: AutodiffBackend<FloatElem = f32>,
+    A: DeterministicPolicyModel<B, D>,
+    C: CriticModel<B, D>,
+    E: Spaces,
+    LR: Decay,
+    Vec<E::State>: ToTensor<B, D, Float>,
+    E::Action: Into<Vec<f32>> + TryFrom<Vec<f32>>,
+{
+    /// Initialize a new `DDPGAgent`
+    ///
+    /// ### Arguments
+    /// - `actor` A [`DeterministicPolicyModel`] to be trained, which is also cloned as the target actor
+    /// - `critic` A [`CriticModel`] to be trained, which is also cloned as the target critic
+    /// - `twin_critic` An optional second, independently initialized [`CriticModel`] for clipped double-Q learning
+    /// - `config` A [`DDPGAgentConfig`] containing hyperparameters for the agent
+    /// - `device` A static reference to the device used for the models
+    ///
+    /// **Panics** if `policy_delay` or `train_freq` is zero
+    pub fn new(
+        actor: A,
+        critic: C,
+        twin_critic: Option<C>,
+        config: DDPGAgentConfig<LR>,
+        device: &'static B::Device,
+    ) -> Self {
+        assert!(config.policy_delay > 0, "policy_delay must be at least 1");
+        assert!(config.train_freq > 0, "train_freq must be at least 1");
+        let mut rng = rng_from_seed(config.seed);
+        let mut memory = ReplayMemory::new(config.memory_capacity, config.memory_batch_size);
+        memory.seed(rng.gen());
+        let critics = std::iter::once(critic)
+            .chain(twin_critic)
+            .collect::<Vec<_>>();
+
+        Self {
+            target_actor: Some(actor.clone()),
+            actor: Some(actor),
+            actor_optimizer: ModelOptimizer::new(&config.optimizer, config.grad_clipping),
+            critic_optimizers: critics
+                .iter()
+                .map(|_| ModelOptimizer::new(&config.optimizer, config.grad_clipping))
+                .collect(),
+            target_critics: critics.clone(),
+            critics,
+            device,
+            memory,
+            bounds: None,
+            noise: config.action_noise.map(NoiseProcess::new),
+            gamma: config.gamma,
+            tau: config.tau,
+            learning_starts: config.learning_starts,
+            train_freq: config.train_freq,
+            gradient_steps: config.gradient_steps,
+            policy_delay: config.policy_delay,
+            target_policy_noise: config.target_policy_noise,
+            lr_schedule: config.lr_schedule,
+            report: Report::default()
+                .with_metric("actor_loss", Aggregation::Mean)
+                .with_metric("critic_loss", Aggregation::Mean),
+            total_steps: 0,
+            total_gradient_steps: 0,
+            rng,
+        }
+    }
+
+    /// Compute the normalized action of the actor in a single state
+    fn policy(&self, state: E::State) -> Vec<f32> {
+        self.actor
+            .as_ref()
+            .unwrap()
+            .forward(vec![state].to_tensor(self.device))
+            .into_data()
+            .convert::<f32>()
+            .value
+    }
+
+    /// Compute the target actor's action in each state, smoothed with clipped noise if there is target policy noise
+    fn target_actions(&mut self, states: Tensor<B, D>) -> Tensor<B, 2> {
+        let actions = self.target_actor.as_ref().unwrap().forward(states);
+        match self.target_policy_noise {
+            Some(TargetPolicyNoise { std, clip }) => {
+                let [batch_size, action_dim] = actions.dims();
+                let noise = (0..batch_size * action_dim)
+                    .map(|_| (std * self.rng.sample::<f32, _>(StandardNormal)).clamp(-clip, clip))
+                    .collect::<Vec<_>>();
+                let noise: Tensor<B, 1> = noise.to_tensor(self.device);
+                (actions + noise.reshape([batch_size, action_dim])).clamp(-1.0, 1.0)
+            }
+            None => actions,
+        }
+    }
+
+    /// Estimate the value of each next state with the target networks, taking the smaller estimate of twin critics
+    fn next_q_values(&mut self, next_states: Tensor<B, D>) -> Tensor<B, 2> {
+        let next_actions = self.target_actions(next_states.clone());
+        self.target_critics
+            .iter()
+            .map(|critic| critic.forward(next_states.clone(), next_actions.clone()))
+            .reduce(minimum)
+            .unwrap()
+    }
+
+    /// Take a gradient step on the critics, and on the actor and target networks if the policy delay has elapsed
+    ///
+    /// **Returns** `false` if there are not yet enough experiences in memory
+    fn learn(&mut self) -> bool {
+        let Some(batch) = self.memory.sample_zipped() else {
+            return false;
+        };
+        let bounds = self
+            .bounds
+            .clone()
+            .expect("Action bounds are known once the agent has acted");
+        let lr = self.lr_schedule.evaluate(self.total_steps as f32).into();
+
+        // Compute the value of each next state from the target networks
+        let targets = bellman_targets(
+            &batch,
+            self.gamma,
+            self.device,
+            |next_states: Tensor<B, D>, _| self.next_q_values(next_states),
+        )
+        .detach();
+
+        // Tensor conversions
+        let states: Tensor<B, D> = batch.states.to_tensor(self.device);
+        let actions = bounds.normalize(batch.actions, self.device);
+
+        // Fit the critics to the targets
+        let critics = std::mem::take(&mut self.critics);
+        let critic_loss = critics
+            .iter()
+            .map(|critic| {
+                (critic.forward(states.clone(), actions.clone()) - targets.clone())
+                    .powf_scalar(2.0)
+                    .mean()
+            })
+            .reduce(|a, b| a + b)
+            .unwrap();
+        let mut grads = critic_loss.backward();
+        self.critics = critics
+            .into_iter()
+            .zip(&mut self.critic_optimizers)
+            .map(|(critic, optimizer)| {
+                let critic_grads = GradientsParams::from_module(&mut grads, &critic);
+                optimizer.step(lr, critic, critic_grads)
+            })
+            .collect();
+        self.report.record(
+            "critic_loss",
+            critic_loss.into_data().convert::<f32>().value[0] as f64,
+        );
+
+        self.total_gradient_steps += 1;
+        if self.total_gradient_steps % self.policy_delay != 0 {
+            return true;
+        }
+
+        // Update the actor to maximize the first critic's estimate of its actions
+        let actor = self.actor.take().unwrap();
+        let actor_loss = self.critics[0]
+            .forward(states.clone(), actor.forward(states))
+            .mean()
+            .neg();
+        let actor_grads = GradientsParams::from_grads(actor_loss.backward(), &actor);
+        self.actor = Some(self.actor_optimizer.step(lr, actor, actor_grads));
+        self.report.record(
+            "actor_loss",
+            actor_loss.into_data().convert::<f32>().value[0] as f64,
+        );
+
+        // Update the target networks
+        let target_actor = self.target_actor.take().unwrap();
+        self.target_actor = Some(soft_update(
+            target_actor,
+            self.actor.as_ref().unwrap(),
+            self.tau,
+        ));
+        self.target_critics = std::mem::take(&mut self.target_critics)
+            .into_iter()
+            .zip(&self.critics)
+            .map(|(target, critic)| soft_update(target, critic, self.tau))
+            .collect();
+
+        true
+    }
+}
+
+impl<B, A, C, E, const D: usize, LR> Agent<E> for DDPGAgent<B, A, C, E, D, LR>
+where
+    B: AutodiffBackend<FloatElem = f32>,
+    A: DeterministicPolicyModel<B, D>,
+    C: CriticModel<B, D>,
+    E: Spaces,
+    LR: Decay,
+    Vec<E::State>: ToTensor<B, D, Float>,
+    E::Action: Into<Vec<f32>> + TryFrom<Vec<f32>>,
+{
+    /// Add exploration noise to the actor's action, or take a uniformly random action before learning starts
+    fn act(&mut self, env: &mut E, state: E::State) -> E::Action {
+        self.bounds
+            .get_or_insert_with(|| ActionBounds::new(&env.action_space()));
+        if self.total_steps < self.learning_starts {
+            return env.random_action();
+        }
+
+        let mut action = self.policy(state);
+        if let Some(noise) = &mut self.noise {
+            let noise = noise.sample(action.len(), &mut self.rng);
+            for (a, n) in action.iter_mut().zip(noise) {
+                *a = (*a + n).clamp(-1.0, 1.0);
+            }
+        }
+        self.bounds.as_ref().unwrap().action(&action)
+    }
+
+    /// Choose the actor's action without noise
+    fn act_greedy(&mut self, env: &mut E, state: E::State) -> E::Action {
+        let bounds = self
+            .bounds
+            .get_or_insert_with(|| ActionBounds::new(&env.action_space()))
+            .clone();
+        bounds.action(&self.policy(state))
+    }
+
+    /// Store the experience in replay memory and train on schedule
+    fn observe(&mut self, _env: &E, exp: Exp<E>) {
+        self.memory.push(exp);
+        self.total_steps += 1;
+
+        if self.total_steps > self.learning_starts && self.total_steps % self.train_freq == 0 {
+            for _ in 0..self.gradient_steps {
+                if !self.learn() {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Restart correlated exploration noise
+    fn end_episode(&mut self) {
+        if let Some(noise) = &mut self.noise {
+            noise.reset();
+        }
+    }
+
+    fn report(&mut self) -> Option<&mut Report> {
+        Some(&mut self.report)
+    }
+}
+
+impl<B, A, C, E, const D: usize, LR> Debug for DDPGAgent<B, A, C, E, D, LR>
+where
+    B: AutodiffBackend,
+    A: AutodiffModule<B>,
+    C: AutodiffModule<B>,
+    E: Environment,
+    LR: Decay,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DDPGAgent")
+            .field("twin_critic", &(self.critics.len() > 1))
+            .field("noise", &self.noise)
+            .field("gamma", &self.gamma)
+            .field("tau", &self.tau)
+            .field("policy_delay", &self.policy_delay)
+            .field("target_policy_noise", &self.target_policy_noise)
+            .field("total_steps", &self.total_steps)
+            .field("total_gradient_steps", &self.total_gradient_steps)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "gym"))]
+mod tests {
+    use burn::{
+        nn::{Linear, LinearConfig},
+        tensor::activation::tanh,
+    };
+
+    use crate::{
+        algo::tests::{Critic, B, DEVICE},
+        gym::Pendulum,
+    };
+
+    use super::*;
+
+    #[derive(Module, Debug)]
+    struct Actor<B: Backend> {
+        fc: Linear<B>,
+    }
+
+    impl DeterministicPolicyModel<B, 2> for Actor<B> {
+        fn forward(&self, input: Tensor<B, 2>) -> Tensor<B, 2> {
+            tanh(self.fc.forward(input))
+        }
+    }
+
+    type TestAgent = DDPGAgent<B, Actor<B>, Critic<B>, Pendulum, 2>;
+
+    /// A TD3 agent whose memory holds one episode of 16 random steps, without having learned from it
+    fn agent(policy_delay: usize, target_policy_noise: Option<TargetPolicyNoise>) -> TestAgent {
+        let actor = Actor {
+            fc: LinearConfig::new(3, 1).init(&DEVICE),
+        };
+        let config = DDPGAgentConfig {
+            memory_capacity: 64,
+            memory_batch_size: 8,
+            learning_starts: 100,
+            policy_delay,
+            target_policy_noise,
+            seed: Some(0),
+            ..Default::default()
+        };
+        let mut agent =
+            TestAgent::new(actor, Critic::new(4), Some(Critic::new(4)), config, &DEVICE);
+
+        let mut env = Pendulum::new(16);
+        env.seed(0);
+        agent.go(&mut env);
+        agent
+    }
+
+    fn actor_weights(actor: &Option<Actor<B>>) -> Vec<f32> {
+        actor.as_ref().unwrap().fc.weight.val().into_data().value
+    }
+
+    fn critic_weights(critics: &[Critic<B>]) -> Vec<Vec<f32>> {
+        critics.iter().map(Critic::weights).collect()
+    }
+
+    fn probe_states() -> Tensor<B, 2> {
+        vec![[1.0, 0.0, 0.0], [0.0, 1.0, -2.0], [-1.0, 0.0, 4.0]].to_tensor(&DEVICE)
+    }
+
+    #[test]
+    fn td3_policy_delay() {
+        let mut agent = agent(2, None);
+        let actor = actor_weights(&agent.actor);
+        let target_actor = actor_weights(&agent.target_actor);
+        let critics = critic_weights(&agent.critics);
+        let target_critics = critic_weights(&agent.target_critics);
+
+        assert!(agent.learn(), "Memory holds a full batch");
+        assert_ne!(
+            critic_weights(&agent.critics),
+            critics,
+            "Critics learn every gradient step"
+        );
+        assert_eq!(
+            actor_weights(&agent.actor),
+            actor,
+            "Actor waits for the policy delay"
+        );
+        assert_eq!(
+            actor_weights(&agent.target_actor),
+            target_actor,
+            "Target actor waits for the policy delay"
+        );
+        assert_eq!(
+            critic_weights(&agent.target_critics),
+            target_critics,
+            "Target critics wait for the policy delay"
+        );
+
+        agent.learn();
+        assert_ne!(
+            actor_weights(&agent.actor),
+            actor,
+            "Actor learns once the policy delay has elapsed"
+        );
+        assert_ne!(
+            actor_weights(&agent.target_actor),
+            target_actor,
+            "Target actor is updated once the policy delay has elapsed"
+        );
+        assert_ne!(
+            critic_weights(&agent.target_critics),
+            target_critics,
+            "Target critics are updated once the policy delay has elapsed"
+        );
+
+        let report = agent.report().unwrap().take();
+        assert_eq!(
+            (report["critic_loss"].count(), report["actor_loss"].count()),
+            (2, 1),
+            "Report records the losses of each update"
+        );
+    }
+
+    #[test]
+    fn td3_target_policy_noise_is_clipped() {
+        let clip = 0.2;
+        let mut agent = agent(1, Some(TargetPolicyNoise { std: 1000.0, clip }));
+        let actions = agent
+            .target_actor
+            .as_ref()
+            .unwrap()
+            .forward(probe_states())
+            .into_data()
+            .value;
+        let noise = agent
+            .target_actions(probe_states())
+            .into_data()
+            .value
+            .into_iter()
+            .zip(actions)
+            .map(|(noisy, action)| noisy - action)
+            .collect::<Vec<_>>();
+
+        assert!(
+            noise.iter().all(|n| n.abs() <= clip + 1e-6),
+            "Target policy noise is clipped"
+        );
+        assert!(
+            noise.iter().any(|n| (n.abs() - clip).abs() < 1e-6),
+            "Target policy noise is applied"
+        );
+    }
+
+    #[test]
+    fn td3_twin_critic_target() {
+        let mut agent = agent(1, None);
+        let actions = agent.target_actions(probe_states());
+        let estimates = agent
+            .target_critics
+            .iter()
+            .map(|critic| {
+                critic
+                    .forward(probe_states(), actions.clone())
+                    .into_data()
+                    .value
+            })
+            .collect::<Vec<_>>();
+        let expected = estimates[0]
+            .iter()
+            .zip(&estimates[1])
+            .map(|(a, b)| a.min(*b))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            agent.next_q_values(probe_states()).into_data().value,
+            expected,
+            "Target is the smaller of the twin target critics' estimates"
+        );
+    }
+}
@@ -0,0 +1,521 @@
+use std::collections::HashMap;
+
+use crate::{
+    assert_interval,
+    env::{DiscreteStateSpace, StepResult, TransitionModel},
+};
+
+use super::tabular::Hashable;
+
+/// Configuration for the [`DPAgent`]
+#[derive(Debug, Clone)]
+pub struct DPConfig {
+    /// The discount factor
+    ///
+    /// **Default:** `0.9`
+    pub gamma: f32,
+    /// The convergence threshold θ, where a sweep that changes no state value by more than θ is considered converged
+    ///
+    /// **Default:** `1e-4`
+    pub theta: f32,
+    /// The maximum number of sweeps over the state space in a single call to a policy evaluation or value iteration
+    /// routine, or `None` to sweep until convergence
+    ///
+    /// **Default:** `None`
+    pub max_sweeps: Option<usize>,
+    /// The maximum number of evaluation and improvement steps in a single call to
+    /// [policy_iteration](DPAgent::policy_iteration), or `None` to iterate until the policy is stable
+    ///
+    /// **Default:** `Some(1000)`
+    pub max_iterations: Option<usize>,
+    /// Whether to perform in-place backups, where updated values are used immediately within a sweep, instead of
+    /// synchronous backups, where each sweep only uses values from the previous sweep
+    ///
+    /// In-place backups usually converge faster, but depend on the order of [states](DiscreteStateSpace::states)
+    ///
+    /// **Default:** `true`
+    pub in_place: bool,
+}
+
+impl Default for DPConfig {
+    fn default() -> Self {
+        Self {
+            gamma: 0.9,
+            theta: 1e-4,
+            max_sweeps: None,
+            max_iterations: Some(1000),
+            in_place: true,
+        }
+    }
+}
+
+/// A dynamic programming agent that computes optimal policies from a complete model of its environment
+///
+/// Implements policy evaluation, policy improvement, policy iteration, value iteration, and modified (generalized)
+/// policy iteration over any environment with a finite state space and an enumerable [`TransitionModel`].
+/// These are planning algorithms, so the environment is never stepped during learning.
+///
+/// The policy is deterministic. States without an entry in the policy take their first
+/// [available action](TransitionModel::available_actions).
+///
+/// ### Generics
+/// - `E` - The [`TransitionModel`] to plan in
+///     - The state and action types must be `Copy`, `Eq`, and `Hash` to be used as keys in a [`HashMap`]
+#[derive(Debug, Clone)]
+pub struct DPAgent<E>
+where
+    E: TransitionModel + DiscreteStateSpace,
+    E::State: Hashable,
+    E::Action: Hashable,
+{
+    state_value: HashMap<E::State, f32>,
+    policy: HashMap<E::State, E::Action>,
+    gamma: f32,
+    theta: f32,
+    max_sweeps: Option<usize>,
+    max_iterations: Option<usize>,
+    in_place: bool,
+}
+
+impl<E> DPAgent<E>
+where
+    E: TransitionModel + DiscreteStateSpace,
+    E::State: Hashable,
+    E::Action: Hashable,
+{
+    /// Initialize a new `DPAgent`
+    ///
+    /// **Panics** if `gamma` is not in the interval `[0,1]` or `theta` is not positive
+    pub fn new(config: DPConfig) -> Self {
+        assert_interval!(config.gamma, 0.0, 1.0);
+        assert!(config.theta > 0.0, "theta must be positive");
+        Self {
+            state_value: HashMap::new(),
+            policy: HashMap::new(),
+            gamma: config.gamma,
+            theta: config.theta,
+            max_sweeps: config.max_sweeps,
+            max_iterations: config.max_iterations,
+            in_place: config.in_place,
+        }
+    }
+
+    /// Start from the given policy instead of the default
+    pub fn with_policy(mut self, policy: HashMap<E::State, E::Action>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Get the policy
+    pub fn policy(&self) -> &HashMap<E::State, E::Action> {
+        &self.policy
+    }
+
+    /// Get the state value function
+    pub fn state_value(&self) -> &HashMap<E::State, f32> {
+        &self.state_value
+    }
+
+    /// Compute the expected return of taking `action` in `state` and following the current value estimates thereafter
+    ///
+    /// q(s, a) = Σ p(s', r | s, a) [r + γ v(s')]
+    pub fn action_value(&self, env: &E, state: E::State, action: E::Action) -> f32 {
+        env.outcomes(state, action)
+            .into_iter()
+            .map(|outcome| {
+                let next_value = outcome.next_state.map_or(0.0, |s| self.value(s));
+                outcome.prob * (outcome.reward + self.gamma * next_value)
+            })
+            .sum()
+    }
+
+    /// Get the current value estimate of a state
+    fn value(&self, state: E::State) -> f32 {
+        self.state_value.get(&state).copied().unwrap_or(0.0)
+    }
+
+    /// Get the action the policy takes in a state, or `None` if no actions are available
+    fn policy_action(&self, env: &E, state: E::State) -> Option<E::Action> {
+        self.policy
+            .get(&state)
+            .copied()
+            .or_else(|| env.available_actions(state).first().copied())
+    }
+
+    /// Get the available action with the highest value in a state along with its value, or `None` if no actions are
+    /// available
+    fn best_action(&self, env: &E, state: E::State) -> Option<(E::Action, f32)> {
+        env.available_actions(state)
+            .into_iter()
+            .map(|a| (a, self.action_value(env, state, a)))
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+    }
+
+    /// Back up the value of every state once
+    ///
+    /// If `greedy` is true, states are backed up with the value of their best action, otherwise with the value of
+    /// the policy's action
+    ///
+    /// **Returns** the largest change in any state value
+    fn sweep(&mut self, env: &E, states: &[E::State], greedy: bool) -> f32 {
+        let mut delta = 0f32;
+        let mut synchronous_values = (!self.in_place).then(|| self.state_value.clone());
+
+        for &state in states {
+            let new_value = if greedy {
+                self.best_action(env, state).map_or(0.0, |(_, v)| v)
+            } else {
+                self.policy_action(env, state)
+                    .map_or(0.0, |a| self.action_value(env, state, a))
+            };
+            delta = delta.max((new_value - self.value(state)).abs());
+
+            match &mut synchronous_values {
+                Some(values) => values.insert(state, new_value),
+                None => self.state_value.insert(state, new_value),
+            };
+        }
+
+        if let Some(values) = synchronous_values {
+            self.state_value = values;
+        }
+
+        delta
+    }
+
+    /// Sweep until the values converge or `max_sweeps` is reached
+    ///
+    /// **Returns** the number of sweeps and the largest value change in the last sweep
+    fn sweep_until_converged(
+        &mut self,
+        env: &E,
+        states: &[E::State],
+        greedy: bool,
+        max_sweeps: Option<usize>,
+    ) -> (usize, f32) {
+        let mut sweeps = 0;
+        let mut delta = f32::INFINITY;
+        while delta >= self.theta && max_sweeps.map_or(true, |max| sweeps < max) {
+            delta = self.sweep(env, states, greedy);
+            sweeps += 1;
+        }
+        (sweeps, delta)
+    }
+
+    /// Estimate the value function of the current policy by iterative policy evaluation
+    ///
+    /// **Returns** the number of sweeps performed
+    pub fn evaluate_policy(&mut self, env: &E) -> usize {
+        let states = env.states();
+        self.sweep_until_converged(env, &states, false, self.max_sweeps)
+            .0
+    }
+
+    /// Make the policy greedy with respect to the current value function
+    ///
+    /// The current action is only replaced if another action's value exceeds its value by more than `theta`, so
+    /// ties and evaluation noise cannot make the policy cycle between equally good actions
+    ///
+    /// **Returns** `true` if the policy is unchanged, meaning it is stable
+    pub fn improve_policy(&mut self, env: &E) -> bool {
+        let mut stable = true;
+        for state in env.states() {
+            let Some((best_action, best_value)) = self.best_action(env, state) else {
+                continue;
+            };
+
+            let current = self
+                .policy_action(env, state)
+                .map(|a| (a, self.action_value(env, state, a)));
+            if current.map_or(true, |(a, v)| {
+                a != best_action && best_value - v > self.theta
+            }) {
+                stable = false;
+                self.policy.insert(state, best_action);
+            } else if let Some((a, _)) = current {
+                self.policy.insert(state, a);
+            }
+        }
+
+        stable
+    }
+
+    /// Run policy iteration, alternating between policy evaluation and policy improvement until the policy is stable,
+    /// or until `max_iterations` iterations have been performed
+    ///
+    /// **Returns** the number of iterations performed
+    pub fn policy_iteration(&mut self, env: &E) -> usize {
+        let mut iterations = 0;
+        loop {
+            self.evaluate_policy(env);
+            iterations += 1;
+            if self.improve_policy(env) || self.max_iterations.is_some_and(|max| iterations >= max)
+            {
+                return iterations;
+            }
+        }
+    }
+
+    /// Run value iteration, backing up every state with its best action value until the values converge,
+    /// and then extract the greedy policy
+    ///
+    /// **Returns** the number of sweeps performed
+    pub fn value_iteration(&mut self, env: &E) -> usize {
+        let states = env.states();
+        let (sweeps, _) = self.sweep_until_converged(env, &states, true, self.max_sweeps);
+        self.improve_policy(env);
+        sweeps
+    }
+
+    /// Run modified policy iteration, where each policy evaluation is truncated to `sweeps_per_evaluation` sweeps
+    ///
+    /// This generalizes both value iteration, with one sweep per evaluation, and policy iteration, with unlimited
+    /// sweeps. It stops when the policy is stable and the last evaluation sweep converged, or after `max_sweeps`
+    /// total sweeps.
+    ///
+    /// **Returns** the number of iterations performed
+    ///
+    /// **Panics** if `sweeps_per_evaluation` is 0
+    pub fn modified_policy_iteration(&mut self, env: &E, sweeps_per_evaluation: usize) -> usize {
+        assert!(
+            sweeps_per_evaluation > 0,
+            "sweeps_per_evaluation must be greater than 0"
+        );
+        let states = env.states();
+        let mut iterations = 0;
+        let mut total_sweeps = 0;
+        loop {
+            let (sweeps, delta) =
+                self.sweep_until_converged(env, &states, false, Some(sweeps_per_evaluation));
+            total_sweeps += sweeps;
+            iterations += 1;
+
+            let stable = self.improve_policy(env);
+            if (stable && delta < self.theta)
+                || self.max_sweeps.is_some_and(|max| total_sweeps >= max)
+            {
+                return iterations;
+            }
+        }
+    }
+
+    /// Deploy the agent into the environment for one episode, following its policy
+    pub fn go(&self, env: &mut E) {
+        let mut next_state = Some(env.reset());
+        while let Some(state) = next_state {
+            let Some(action) = self.policy_action(env, state) else {
+                break;
+            };
+            let StepResult {
+                next_state: next,
+                truncated,
+                ..
+            } = env.step(action);
+            next_state = next.filter(|_| !truncated);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{distributions::WeightedIndex, prelude::Distribution, rngs::StdRng, SeedableRng};
+
+    use crate::env::{DiscreteActionSpace, Environment, Outcome};
+
+    use super::*;
+
+    /// A chain of 3 states where moving right from the last state reaches a terminal state with a reward of 1
+    ///
+    /// Moving right succeeds with probability 0.8, otherwise the agent stays in place
+    struct Chain {
+        state: u8,
+        total_reward: f32,
+        rng: StdRng,
+    }
+
+    impl Chain {
+        fn new() -> Self {
+            Self {
+                state: 0,
+                total_reward: 0.0,
+                rng: StdRng::seed_from_u64(0),
+            }
+        }
+    }
+
+    impl Environment for Chain {
+        type State = u8;
+        type Action = bool;
+
+        /// Sample an outcome from the transition model
+        fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
+            let outcomes = self.outcomes(self.state, action);
+            let dist = WeightedIndex::new(outcomes.iter().map(|outcome| outcome.prob)).unwrap();
+            let Outcome {
+                next_state, reward, ..
+            } = outcomes[dist.sample(&mut self.rng)];
+
+            if let Some(state) = next_state {
+                self.state = state;
+            }
+            self.total_reward += reward;
+            StepResult::new(next_state, reward)
+        }
+
+        fn reset(&mut self) -> Self::State {
+            self.state = 0;
+            self.total_reward = 0.0;
+            self.state
+        }
+
+        fn random_action(&mut self) -> Self::Action {
+            true
+        }
+    }
+
+    impl DiscreteActionSpace for Chain {
+        fn actions(&self) -> Vec<Self::Action> {
+            vec![false, true]
+        }
+    }
+
+    impl DiscreteStateSpace for Chain {
+        fn states(&self) -> Vec<Self::State> {
+            vec![0, 1, 2]
+        }
+    }
+
+    impl TransitionModel for Chain {
+        fn outcomes(&self, state: Self::State, right: Self::Action) -> Vec<Outcome<Self::State>> {
+            if !right {
+                let next_state = Some(state.saturating_sub(1));
+                return vec![Outcome {
+                    next_state,
+                    reward: 0.0,
+                    prob: 1.0,
+                }];
+            }
+
+            let (next_state, reward) = match state {
+                2 => (None, 1.0),
+                s => (Some(s + 1), 0.0),
+            };
+            vec![
+                Outcome {
+                    next_state,
+                    reward,
+                    prob: 0.8,
+                },
+                Outcome {
+                    next_state: Some(state),
+                    reward: 0.0,
+                    prob: 0.2,
+                },
+            ]
+        }
+    }
+
+    fn values(agent: &DPAgent<Chain>) -> Vec<f32> {
+        (0..3).map(|s| agent.state_value()[&s]).collect()
+    }
+
+    #[test]
+    fn dp_algorithms_agree() {
+        let config = DPConfig {
+            theta: 1e-6,
+            ..Default::default()
+        };
+        let initial_policy = HashMap::from([(0, false), (1, false), (2, false)]);
+
+        let mut env = Chain::new();
+
+        let mut pi = DPAgent::new(config.clone()).with_policy(initial_policy.clone());
+        pi.policy_iteration(&env);
+        let mut vi = DPAgent::new(DPConfig {
+            in_place: false,
+            ..config.clone()
+        });
+        vi.value_iteration(&env);
+        let mut mpi = DPAgent::new(config).with_policy(initial_policy);
+        mpi.modified_policy_iteration(&env, 3);
+
+        let v2 = 0.8 / (1.0 - 0.2 * 0.9);
+        assert!(
+            (pi.state_value()[&2] - v2).abs() < 1e-4,
+            "Policy iteration finds the optimal value"
+        );
+        for agent in [&pi, &vi, &mpi] {
+            assert!(
+                agent.policy().values().all(|&right| right),
+                "Optimal policy always moves right"
+            );
+            assert!(
+                values(agent)
+                    .iter()
+                    .zip(values(&pi))
+                    .all(|(a, b)| (a - b).abs() < 1e-4),
+                "All algorithms converge to the same values"
+            );
+        }
+
+        pi.go(&mut env);
+        assert_eq!(
+            env.total_reward, 1.0,
+            "Following the policy reaches the goal"
+        );
+    }
+
+    #[test]
+    fn dp_max_sweeps() {
+        let mut agent = DPAgent::new(DPConfig {
+            max_sweeps: Some(2),
+            ..Default::default()
+        });
+        assert_eq!(
+            agent.value_iteration(&Chain::new()),
+            2,
+            "Value iteration stops at max sweeps"
+        );
+    }
+
+    #[test]
+    fn dp_max_iterations() {
+        let mut agent = DPAgent::new(DPConfig {
+            max_iterations: Some(1),
+            ..Default::default()
+        })
+        .with_policy(HashMap::from([(0, false), (1, false), (2, false)]));
+        assert_eq!(
+            agent.policy_iteration(&Chain::new()),
+            1,
+            "Policy iteration stops at max iterations"
+        );
+        assert!(
+            agent.policy()[&2] && !agent.policy()[&0],
+            "The policy was improved once, so only the last state moves right"
+        );
+    }
+
+    #[test]
+    fn dp_improve_policy_tolerance() {
+        let mut agent = DPAgent::new(DPConfig::default()).with_policy(HashMap::from([
+            (0, false),
+            (1, false),
+            (2, false),
+        ]));
+        agent.state_value = HashMap::from([(0, 0.0), (1, 1e-5), (2, 0.0)]);
+
+        assert!(
+            !agent.improve_policy(&Chain::new()),
+            "Moving right from the last state is clearly better"
+        );
+        assert!(
+            agent.policy()[&2],
+            "Action improving by more than theta is taken"
+        );
+        assert!(
+            !agent.policy()[&0] && !agent.policy()[&1],
+            "Actions improving by less than theta are ignored"
+        );
+    }
+}
@@ -1,20 +1,37 @@
+use std::{fmt::Debug, path::PathBuf};
+#[cfg(feature = "serde")]
+use std::{fs, path::Path};
+
 use burn::{
-    grad_clipping::GradientClippingConfig,
     module::AutodiffModule,
-    optim::{AdamWConfig, GradientsParams, Optimizer},
+    optim::GradientsParams,
     prelude::*,
+    record::{BinFileRecorder, FullPrecisionSettings},
     tensor::backend::AutodiffBackend,
 };
-use nn::loss::{MseLoss, Reduction};
+use nn::{Linear, LinearConfig};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
 
+#[cfg(feature = "serde")]
+use crate::persist::{self, Format};
 use crate::{
     decay::{self, Decay},
-    env::Environment,
+    env::{Environment, MaskedActionSpace},
     exploration::{Choice, EpsilonGreedy},
-    memory::{Exp, Memory, PrioritizedReplayMemory, ReplayMemory},
-    traits::ToTensor,
+    memory::{
+        bellman_targets, Exp, ExpBatch, Memory, NStep, PrioritizedReplayMemory, ReplayMemory,
+    },
+    nn::{illegal_action_tensor, legal_mask, ModelOptimizer},
+    persist::PersistError,
+    traits::{Agent, ToTensor},
+    util::rng_from_seed,
+    vec_env::{VecEnv, VecStepResult},
 };
 
+pub use crate::nn::{GradientClipping, OptimizerConfig};
+
 /// A burn module used with a Deep Q network agent
 ///
 /// ### Generics
@@ -28,15 +45,140 @@ pub trait DQNModel<B: AutodiffBackend, const D: usize>: AutodiffModule<B> {
     ///
     /// θ′ ← τθ + (1 − τ)θ′
     ///
+    /// Provided for any module with [soft_update](crate::nn::soft_update), so implementors only need to override it
+    /// to customize the update.
+    ///
     /// ```ignore
     /// target_net = target_net.soft_update(policy_net, tau);
     /// ```
-    fn soft_update(self, other: &Self, tau: f32) -> Self;
+    fn soft_update(self, other: &Self, tau: f32) -> Self {
+        crate::nn::soft_update(self, other, tau)
+    }
+}
+
+/// Configuration for a [`DuelingHead`]
+#[derive(Config, Debug)]
+pub struct DuelingHeadConfig {
+    /// The size of the input features
+    d_input: usize,
+    /// The number of actions in the environment's action space
+    num_actions: usize,
+}
+
+impl DuelingHeadConfig {
+    /// Initialize a new [`DuelingHead`]
+    pub fn init<B: Backend>(&self, device: &B::Device) -> DuelingHead<B> {
+        DuelingHead {
+            value: LinearConfig::new(self.d_input, 1).init(device),
+            advantage: LinearConfig::new(self.d_input, self.num_actions).init(device),
+        }
+    }
+}
+
+/// The output layer of a dueling network architecture ([Wang et al. 2016](https://arxiv.org/abs/1511.06581))
+///
+/// Splits the Q function into a state value stream and an action advantage stream, recombined as
+///
+/// Q(s, a) = V(s) + A(s, a) − mean<sub>a′</sub> A(s, a′)
+///
+/// Use it as the last layer of a [`DQNModel`] in place of a single linear layer:
+///
+/// ```ignore
+/// fn forward(&self, input: Tensor<B, 2>) -> Tensor<B, 2> {
+///     let x = relu(self.fc1.forward(input));
+///     self.head.forward(x)
+/// }
+/// ```
+#[derive(Module, Debug)]
+pub struct DuelingHead<B: Backend> {
+    value: Linear<B>,
+    advantage: Linear<B>,
+}
+
+impl<B: Backend> DuelingHead<B> {
+    /// Compute Q values of shape `[batch_size, num_actions]` from features of shape `[batch_size, d_input]`
+    pub fn forward(&self, features: Tensor<B, 2>) -> Tensor<B, 2> {
+        let value = self.value.forward(features.clone());
+        let advantage = self.advantage.forward(features);
+        let mean_advantage = advantage.clone().mean_dim(1);
+
+        value + advantage - mean_advantage
+    }
+}
+
+/// The loss applied to the temporal difference errors of a [`DQNAgent`]
+///
+/// With [`PrioritizedReplayMemory`], each experience's loss is scaled by its importance sampling weight before
+/// averaging.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TdLoss {
+    /// Squared error, δ<sup>2</sup>
+    #[default]
+    Mse,
+    /// Huber loss, quadratic for errors within `delta` of zero and linear beyond, which limits the gradient of
+    /// outlier errors
+    ///
+    /// With `delta = 1.0`, this is the smooth L1 loss
+    Huber { delta: f32 },
+}
+
+impl TdLoss {
+    /// Compute the mean loss of a batch of TD errors, optionally weighted per element
+    fn forward<B: Backend>(
+        &self,
+        td_errors: Tensor<B, 1>,
+        weights: Option<Tensor<B, 1>>,
+    ) -> Tensor<B, 1> {
+        let losses = match *self {
+            TdLoss::Mse => td_errors.powf_scalar(2.0),
+            TdLoss::Huber { delta } => {
+                let abs = td_errors.abs();
+                let quadratic = abs.clone().clamp_max(delta);
+                let linear = abs - quadratic.clone();
+                quadratic.powf_scalar(2.0) * 0.5 + linear * delta
+            }
+        };
+
+        match weights {
+            Some(weights) => (weights * losses).mean(),
+            None => losses.mean(),
+        }
+    }
+}
+
+/// How the target network of a [`DQNAgent`] follows the policy network
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TargetUpdate {
+    /// Polyak averaging with [`DQNModel::soft_update`], θ′ ← τθ + (1 − τ)θ′
+    Soft { tau: f32 },
+    /// Copy the policy network into the target network, as in the original DQN
+    Hard,
+}
+
+/// An interval measured in agent steps or in gradient steps
+///
+/// With a [`VecEnv`], one lockstep step counts as a step in each environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interval {
+    /// Every `n` environment steps
+    Steps(usize),
+    /// Every `n` gradient steps
+    GradientSteps(usize),
+}
+
+/// Whether a counter passed a multiple of `interval` when it advanced from `prev` to `now`
+fn crossed(prev: u32, now: u32, interval: usize) -> bool {
+    let interval = interval.max(1) as u32;
+    now / interval > prev / interval
 }
 
 /// Configuration for the [`DQNAgent`]
+///
+/// ### Generics
+/// - `D` - The decay strategy for epsilon-greedy exploration
+/// - `LR` - The learning rate schedule
 #[derive(Debug, Clone)]
-pub struct DQNAgentConfig<D> {
+pub struct DQNAgentConfig<D, LR = decay::Constant> {
     /// The capacity of the replay memory
     ///
     /// **Default:** `16384`
@@ -68,8 +210,14 @@ pub struct DQNAgentConfig<D> {
     ///
     /// **Default:** `0.5`
     pub prioritized_memory_beta_0: f32,
-    // /// The [`Optimizer`] to train the policy network with
-    // pub optimizer: O,
+    /// The optimizer to train the policy network with
+    ///
+    /// **Default:** [`OptimizerConfig::adamw`]
+    pub optimizer: OptimizerConfig,
+    /// The gradient clipping applied before each optimizer step, or `None` to leave gradients unclipped
+    ///
+    /// **Default:** `Some(GradientClipping::Value(100.0))`
+    pub grad_clipping: Option<GradientClipping>,
     /// The epsilon decay strategy
     ///
     /// **Default:** [`Exponential`](decay::Exponential) decay with decay rate `1e-3`, start value `1.0`, and end value `0.05`
@@ -78,22 +226,59 @@ pub struct DQNAgentConfig<D> {
     ///
     /// **Default:** `0.999`
     pub gamma: f32,
-    /// The interval at which to perform soft updates on the target network
+    /// The number of steps over which rewards are accumulated before bootstrapping from the target network
+    ///
+    /// With `n_step > 1`, experiences are aggregated by [`NStep`] before entering the replay memory, and the
+    /// next state's value is discounted by γ<sup>n</sup>. Must be at least `1`.
     ///
     /// **Default:** `1`
-    pub target_update_interval: usize,
-    /// The rate at which the target network's parameters are soft updated with the policy network's parameters
+    pub n_step: usize,
+    /// The loss applied to the temporal difference errors
+    ///
+    /// **Default:** [`TdLoss::Mse`]
+    pub td_loss: TdLoss,
+    /// Use the Double DQN target ([van Hasselt et al. 2015](https://arxiv.org/abs/1509.06461)), where the policy
+    /// network selects the best next action and the target network evaluates it, reducing overestimation of Q values
+    ///
+    /// **Default:** `false`
+    pub double_dqn: bool,
+    /// How the target network is updated
+    ///
+    /// **Default:** `TargetUpdate::Soft { tau: 5e-3 }`
+    pub target_update: TargetUpdate,
+    /// The interval at which the target network is updated
     ///
-    /// **Default:** `5e-3`
-    pub tau: f32,
-    /// The learning rate for the optimizer
+    /// Classic DQN copies the policy network every `10000` steps, i.e. [`TargetUpdate::Hard`] with
+    /// `Interval::Steps(10000)`.
     ///
-    /// **Default:** `1e-3`
-    pub lr: f32,
+    /// **Default:** `Interval::GradientSteps(1)`
+    pub target_update_interval: Interval,
+    /// The number of steps taken before learning begins, letting the replay memory fill up with experiences
+    ///
+    /// **Default:** `0`
+    pub learning_starts: usize,
+    /// The number of steps between learning updates
+    ///
+    /// **Default:** `1`
+    pub train_freq: usize,
+    /// The number of gradient steps performed in each learning update
+    ///
+    /// **Default:** `1`
+    pub gradient_steps: usize,
+    /// The learning rate schedule for the optimizer, evaluated at the agent's total number of steps
+    ///
+    /// **Default:** [`Constant`](decay::Constant) learning rate `1e-3`
+    pub lr_schedule: LR,
+    /// Seed for the agent's random number generator, which drives exploration and replay memory sampling,
+    /// or `None` to seed from system entropy
+    ///
+    /// For a fully reproducible run, also seed the environment with [`Environment::seed`] and the backend
+    /// with [`Backend::seed`] before initializing the model, and use a deterministic backend
+    ///
+    /// **Default:** `None`
+    pub seed: Option<u64>,
 }
 
-// type AdamWOptimizer<M, B> = OptimizerAdaptor<AdamW<<B as AutodiffBackend>::InnerBackend>, M, B>;
-
 impl Default for DQNAgentConfig<decay::Exponential> {
     fn default() -> Self {
         Self {
@@ -103,12 +288,20 @@ impl Default for DQNAgentConfig<decay::Exponential> {
             num_episodes: 500,
             prioritized_memory_alpha: 0.7,
             prioritized_memory_beta_0: 0.5,
-            // optimizer: AdamWConfig::new().init(),
+            optimizer: OptimizerConfig::default(),
+            grad_clipping: Some(GradientClipping::Value(100.0)),
             epsilon_decay_strategy: decay::Exponential::new(1e-3, 1.0, 0.05).unwrap(),
             gamma: 0.999,
-            target_update_interval: 1,
-            tau: 5e-3,
-            lr: 1e-3,
+            n_step: 1,
+            td_loss: TdLoss::default(),
+            double_dqn: false,
+            target_update: TargetUpdate::Soft { tau: 5e-3 },
+            target_update_interval: Interval::GradientSteps(1),
+            learning_starts: 0,
+            train_freq: 1,
+            gradient_steps: 1,
+            lr_schedule: decay::Constant::new(1e-3),
+            seed: None,
         }
     }
 }
@@ -120,43 +313,56 @@ impl Default for DQNAgentConfig<decay::Exponential> {
 /// - `M` - The [`DQNModel`] used for the policy and target networks
 /// - `E` - The [`Environment`] in which the agent will learn
 ///     - The environment's action space must be discrete, since the policy network produces a Q value for each action.
+///       Actions that are illegal according to its [`MaskedActionSpace`] are never chosen or bootstrapped from.
 ///     - The state and action types' implementations of [`Clone`] should be very lightweight, as they are cloned often.
 ///       Ideally, both types are [`Copy`].
 /// - `DEC` - The decay strategy for epsilon-greedy exploration
 /// - `D` - The dimension of the input
+/// - `LR` - The learning rate schedule
 ///
-/// A generic optimizer will be added when burn v0.14.0 releases, until then the [`AdamW`](burn::optim::AdamW) optimizer will be used
-#[derive(Debug, Clone)]
-pub struct DQNAgent<B, M, E, DEC, const D: usize>
+/// The optimizer is selected at runtime with [`OptimizerConfig`], and its state is kept for the agent's lifetime
+#[derive(Clone)]
+pub struct DQNAgent<B, M, E, DEC, const D: usize, LR = decay::Constant>
 where
     B: AutodiffBackend,
+    M: AutodiffModule<B>,
     E: Environment,
     DEC: Decay,
+    LR: Decay,
 {
     policy_net: Option<M>,
     target_net: Option<M>,
     device: &'static B::Device,
     memory: Memory<E>,
-    // optimizer: O,
+    /// One n-step aggregator per environment, where the first is used by [Agent::go]
+    n_step: Vec<NStep<E>>,
+    optimizer: ModelOptimizer<M, B>,
     exploration: EpsilonGreedy<DEC>,
     gamma: f32,
-    target_update_interval: usize,
-    tau: f32,
-    lr: f32,
+    td_loss: TdLoss,
+    double_dqn: bool,
+    target_update: TargetUpdate,
+    target_update_interval: Interval,
+    learning_starts: usize,
+    train_freq: usize,
+    gradient_steps: usize,
+    lr_schedule: LR,
     total_steps: u32,
+    total_gradient_steps: u32,
     episodes_elapsed: usize,
+    rng: StdRng,
 }
 
-impl<B, M, E, DEC, const D: usize> DQNAgent<B, M, E, DEC, D>
+impl<B, M, E, DEC, const D: usize, LR> DQNAgent<B, M, E, DEC, D, LR>
 where
     B: AutodiffBackend<FloatElem = f32, IntElem = i32>,
     M: DQNModel<B, D>,
-    E: Environment,
+    E: MaskedActionSpace,
     DEC: Decay,
-    // O: Optimizer<M, B>,
+    LR: Decay,
     Vec<E::State>: ToTensor<B, D, Float>,
     Vec<E::Action>: ToTensor<B, 2, Int>,
-    E::Action: From<usize>,
+    E::Action: From<usize> + Into<usize>,
 {
     /// Initialize a new `DQNAgent`
     ///
@@ -164,21 +370,23 @@ where
     /// - `model` A [`DQNModel`] to be used as the policy and target networks
     /// - `config` A [`DQNAgentConfig`] containing components and hyperparameters for the agent
     /// - `device` A static reference to the device used for the `model`
-    pub fn new(model: M, config: DQNAgentConfig<DEC>, device: &'static B::Device) -> Self {
+    pub fn new(model: M, config: DQNAgentConfig<DEC, LR>, device: &'static B::Device) -> Self {
         let model_clone = model.clone();
+        let mut rng = rng_from_seed(config.seed);
         let memory = if config.use_prioritized_memory {
-            Memory::Prioritized(PrioritizedReplayMemory::new(
+            let mut memory = PrioritizedReplayMemory::new(
                 config.memory_capacity,
                 config.memory_batch_size,
                 config.prioritized_memory_alpha,
                 config.prioritized_memory_beta_0,
                 config.num_episodes,
-            ))
+            );
+            memory.seed(rng.gen());
+            Memory::Prioritized(memory)
         } else {
-            Memory::Base(ReplayMemory::new(
-                config.memory_capacity,
-                config.memory_batch_size,
-            ))
+            let mut memory = ReplayMemory::new(config.memory_capacity, config.memory_batch_size);
+            memory.seed(rng.gen());
+            Memory::Base(memory)
         };
 
         Self {
@@ -186,195 +394,866 @@ where
             target_net: Some(model_clone),
             device,
             memory,
-            // optimizer: config.optimizer,
+            n_step: vec![NStep::new(config.n_step, config.gamma)],
+            optimizer: ModelOptimizer::new(&config.optimizer, config.grad_clipping),
             exploration: EpsilonGreedy::new(config.epsilon_decay_strategy),
             gamma: config.gamma,
+            td_loss: config.td_loss,
+            double_dqn: config.double_dqn,
+            target_update: config.target_update,
             target_update_interval: config.target_update_interval,
-            tau: config.tau,
-            lr: config.lr,
+            learning_starts: config.learning_starts,
+            train_freq: config.train_freq,
+            gradient_steps: config.gradient_steps,
+            lr_schedule: config.lr_schedule,
             total_steps: 0,
+            total_gradient_steps: 0,
             episodes_elapsed: 0,
+            rng,
         }
     }
 
-    /// Invoke the agent's policy along with the exploration strategy to choose an action from the given state
-    fn act(&self, env: &E, state: E::State) -> E::Action {
-        match self.exploration.choose(self.total_steps) {
-            Choice::Explore => env.random_action(),
-            Choice::Exploit => {
-                let input = vec![state].to_tensor(self.device);
-                let output = self
-                    .policy_net
-                    .as_ref()
-                    .unwrap()
-                    .forward(input)
-                    .argmax(1)
-                    .into_scalar();
-                E::Action::from(output.try_into().unwrap())
-            }
-        }
+    /// Choose the legal action with the highest Q value in each state
+    fn greedy_actions(&self, states: Vec<E::State>, masks: &[Option<Vec<bool>>]) -> Vec<E::Action> {
+        let q_values = self
+            .policy_net
+            .as_ref()
+            .unwrap()
+            .forward(states.to_tensor(self.device));
+        let illegal_mask = illegal_action_tensor(masks, q_values.dims()[1], self.device);
+
+        q_values
+            .mask_fill(illegal_mask, f32::NEG_INFINITY)
+            .argmax(1)
+            .to_data()
+            .value
+            .into_iter()
+            .map(|a| E::Action::from(a.try_into().unwrap()))
+            .collect()
     }
 
-    /// Perform one DQN learning step
-    fn learn(&mut self, optimizer: &mut impl Optimizer<M, B>) {
-        // Sample a batch of memories to train on
-        let Memory::Base(memory) = &mut self.memory else {
-            return;
-        };
-        let Some(batch) = memory.sample_zipped() else {
-            return;
-        };
-        let batch_size = memory.batch_size;
-
-        // Create a boolean mask for non-terminal next states so tensor shapes can match in the Bellman Equation
-        let non_terminal_mask = batch
-            .next_states
-            .iter()
-            .map(Option::is_some)
-            .collect::<Vec<_>>()
-            .to_tensor(self.device)
-            .unsqueeze_dim(1);
-
-        // Tensor conversions
-        let states = batch.states.to_tensor(self.device);
-        let actions = batch.actions.to_tensor(self.device);
-        let next_states = batch
-            .next_states
+    /// Choose a legal action in each environment of a [`VecEnv`] from its current state, exploring independently per environment
+    fn act_batch(&mut self, envs: &VecEnv<E>, states: Vec<E::State>) -> Vec<E::Action> {
+        let masks = envs.envs().iter().map(legal_mask).collect::<Vec<_>>();
+        let greedy_actions = self.greedy_actions(states, &masks);
+
+        greedy_actions
             .into_iter()
-            .flatten()
-            .collect::<Vec<_>>()
-            .to_tensor(self.device);
-        let rewards = batch.rewards.to_tensor(self.device).unsqueeze_dim(1);
+            .zip(envs.envs())
+            .map(
+                |(action, env)| match self.exploration.choose(self.total_steps, &mut self.rng) {
+                    Choice::Explore => env
+                        .actions()
+                        .choose(&mut self.rng)
+                        .cloned()
+                        .expect("There is always at least one action available"),
+                    Choice::Exploit => action,
+                },
+            )
+            .collect()
+    }
 
-        let policy_net = self.policy_net.take().unwrap();
-        let target_net = self.target_net.take().unwrap();
+    /// Compute the value of each non-terminal next state as a detached tensor of shape `[next_states, 1]`
+    ///
+    /// With vanilla DQN this is the target network's maximum legal Q value. With Double DQN, the policy network
+    /// selects the best legal action and the target network evaluates it.
+    fn next_q_values(
+        &self,
+        policy_net: &M,
+        target_net: &M,
+        next_states: Tensor<B, D>,
+        next_masks: &[Option<Vec<bool>>],
+    ) -> Tensor<B, 2> {
+        let next_q_values = target_net.forward(next_states.clone());
+        let illegal_mask = illegal_action_tensor(next_masks, next_q_values.dims()[1], self.device);
 
-        // Compute the Q values of the chosen actions in each state
-        let q_values = policy_net.forward(states).gather(1, actions);
+        if self.double_dqn {
+            let next_actions = policy_net
+                .forward(next_states)
+                .mask_fill(illegal_mask, f32::NEG_INFINITY)
+                .argmax(1);
+            next_q_values.gather(1, next_actions).detach()
+        } else {
+            next_q_values
+                .mask_fill(illegal_mask, f32::NEG_INFINITY)
+                .max_dim(1)
+                .detach()
+        }
+    }
 
-        // Compute the maximum Q values obtainable from each next state
-        let expected_q_values = Tensor::zeros([batch_size, 1], self.device).mask_where(
-            non_terminal_mask,
-            target_net.forward(next_states).max_dim(1).detach(),
-        );
+    /// Account for `steps` new environment steps, performing any learning updates and target network updates that
+    /// are due
+    fn advance(&mut self, steps: u32) {
+        let prev = self.total_steps;
+        self.total_steps += steps;
 
-        let discounted_expected_return = rewards + (expected_q_values * self.gamma);
+        if self.total_steps as usize > self.learning_starts
+            && crossed(prev, self.total_steps, self.train_freq)
+        {
+            for _ in 0..self.gradient_steps {
+                let learned = match self.memory {
+                    Memory::Base(_) => self.learn(),
+                    Memory::Prioritized(_) => self.learn_prioritized(),
+                };
+                if !learned {
+                    break;
+                }
 
-        // Compute loss (mean sqared temporal difference error)
-        let loss = MseLoss::new().forward(q_values, discounted_expected_return, Reduction::Mean);
+                self.total_gradient_steps += 1;
+                if let Interval::GradientSteps(interval) = self.target_update_interval {
+                    if crossed(
+                        self.total_gradient_steps - 1,
+                        self.total_gradient_steps,
+                        interval,
+                    ) {
+                        self.update_target();
+                    }
+                }
+            }
+        }
 
-        // Perform backpropagation on policy net
-        let grads = GradientsParams::from_grads(loss.backward(), &policy_net);
-        self.policy_net = Some(optimizer.step(self.lr.into(), policy_net, grads));
+        if let Interval::Steps(interval) = self.target_update_interval {
+            if crossed(prev, self.total_steps, interval) {
+                self.update_target();
+            }
+        }
+    }
 
-        // Perform a periodic soft update on the parameters of the target network for stable convergence
-        self.target_net = if self.episodes_elapsed % self.target_update_interval == 0 {
-            Some(target_net.soft_update(self.policy_net.as_ref().unwrap(), self.tau))
-        } else {
-            Some(target_net)
+    /// Update the target network from the policy network for stable convergence
+    fn update_target(&mut self) {
+        let target_net = self.target_net.take().unwrap();
+        let policy_net = self.policy_net.as_ref().unwrap();
+        self.target_net = Some(match self.target_update {
+            TargetUpdate::Soft { tau } => target_net.soft_update(policy_net, tau),
+            TargetUpdate::Hard => policy_net.clone(),
+        });
+    }
+
+    /// Perform one DQN learning step, returning `false` if there are not enough experiences to fill a batch
+    fn learn(&mut self) -> bool {
+        // Sample a batch of memories to train on
+        let Memory::Base(memory) = &mut self.memory else {
+            return false;
         };
+        let Some(batch) = memory.sample_zipped() else {
+            return false;
+        };
+
+        self.learn_batch(batch, None);
+        true
     }
 
-    fn learn_prioritized(&mut self, optimizer: &mut impl Optimizer<M, B>) {
+    /// Perform one DQN learning step with prioritized experience replay, returning `false` if there are not enough
+    /// experiences to fill a batch
+    fn learn_prioritized(&mut self) -> bool {
         // Sample a batch of memories to train on
         let Memory::Prioritized(memory) = &mut self.memory else {
-            return;
+            return false;
         };
         let Some((batch, weights, indices)) = memory.sample_zipped(self.episodes_elapsed) else {
-            return;
+            return false;
         };
-        let batch_size = memory.batch_size;
-
-        // Create a boolean mask for non-terminal next states so tensor shapes can match in the Bellman Equation
-        let non_terminal_mask = batch
-            .next_states
-            .iter()
-            .map(Option::is_some)
-            .collect::<Vec<_>>()
-            .to_tensor(self.device)
-            .unsqueeze_dim(1);
-
-        // Tensor conversions
-        let states = batch.states.to_tensor(self.device);
-        let actions = batch.actions.to_tensor(self.device);
-        let next_states = batch
-            .next_states
-            .into_iter()
-            .flatten()
-            .collect::<Vec<_>>()
-            .to_tensor(self.device);
-        let rewards = batch.rewards.to_tensor(self.device).unsqueeze_dim(1);
 
+        // Apply importance sampling weights from prioritized memory replay to the loss of each TD error
+        let td_errors = self.learn_batch(batch, Some(weights));
+
+        // Update priorities of sampled experiences
+        if let Memory::Prioritized(memory) = &mut self.memory {
+            memory.update_priorities(&indices, &td_errors);
+        }
+        true
+    }
+
+    /// Perform one gradient step on the policy network over a batch of experiences, optionally weighting the loss of
+    /// each experience
+    ///
+    /// **Returns** the temporal difference error of each experience
+    fn learn_batch(&mut self, batch: ExpBatch<E>, weights: Option<Vec<f32>>) -> Vec<f32> {
         let policy_net = self.policy_net.take().unwrap();
         let target_net = self.target_net.take().unwrap();
 
-        // Compute the Q values of the chosen actions in each state
-        let q_values = policy_net.forward(states).gather(1, actions);
-
-        // Compute the maximum Q values obtainable from each next state
-        let expected_q_values = Tensor::zeros([batch_size, 1], self.device).mask_where(
-            non_terminal_mask,
-            target_net.forward(next_states).max_dim(1).detach(),
+        // Compute the discounted return expected from each state, considering only legal actions in the next states
+        let expected_returns = bellman_targets(
+            &batch,
+            self.gamma,
+            self.device,
+            |next_states, next_masks| {
+                self.next_q_values(&policy_net, &target_net, next_states, next_masks)
+            },
         );
 
-        let discounted_expected_return = rewards + (expected_q_values * self.gamma);
-
-        // Compute temporal difference errors
-        let tde: Tensor<B, 1> = (discounted_expected_return - q_values).squeeze(1);
+        // Compute the Q values of the chosen actions in each state
+        let states = batch.states.to_tensor(self.device);
+        let actions = batch.actions.to_tensor(self.device);
+        let q_values = policy_net.forward(states).gather(1, actions);
 
-        // Update priorities of sampled experiences
+        // Compute the loss over the temporal difference errors
+        let tde: Tensor<B, 1> = (expected_returns - q_values).squeeze(1);
         let td_errors = tde.to_data().value;
-        memory.update_priorities(&indices, &td_errors);
-
-        // Apply importance sampling weights from prioritized memory replay and compute mean squared weighted TD error
-        let weights = weights.to_tensor(self.device);
-        let loss = (weights * tde.powf_scalar(2.0)).mean();
+        let weights = weights.map(|weights| weights.to_tensor(self.device));
+        let loss = self.td_loss.forward(tde, weights);
 
         // Perform backpropagation on policy net
         let grads = GradientsParams::from_grads(loss.backward(), &policy_net);
-        self.policy_net = Some(optimizer.step(self.lr.into(), policy_net, grads));
+        let lr = self.lr_schedule.evaluate(self.total_steps as f32);
+        self.policy_net = Some(self.optimizer.step(lr.into(), policy_net, grads));
+        self.target_net = Some(target_net);
 
-        // Perform a periodic soft update on the parameters of the target network for stable convergence
-        self.target_net = if self.episodes_elapsed % self.target_update_interval == 0 {
-            Some(target_net.soft_update(self.policy_net.as_ref().unwrap(), self.tau))
-        } else {
-            Some(target_net)
-        };
+        td_errors
     }
 
-    /// Deploy the `DQNAgent` into the environment for one episode
-    pub fn go(&mut self, env: &mut E) {
-        let mut optimizer = AdamWConfig::new()
-            .with_grad_clipping(Some(GradientClippingConfig::Value(100.0)))
-            .init();
-        let mut next_state = Some(env.reset());
-
-        while let Some(state) = next_state {
-            let action = self.act(env, state.clone());
-            let (next, reward) = env.step(action.clone());
-            next_state = next;
-
-            let exp = Exp {
-                state,
-                action,
-                reward,
-                next_state: next_state.clone(),
-            };
-
-            match &mut self.memory {
-                Memory::Base(memory) => {
-                    memory.push(exp);
-                    self.learn(&mut optimizer);
+    /// Deploy the `DQNAgent` into a vectorized environment for `num_steps` lockstep steps
+    ///
+    /// Each step collects one experience from every environment in `envs` and counts as `envs.len()` steps toward
+    /// the training schedule, so with `train_freq` at most the number of environments, each step performs one
+    /// learning update and more environments means more experience per gradient update. Environments are reset before the first step
+    /// if needed and automatically thereafter, and every finished episode counts toward the agent's elapsed episodes.
+    pub fn go_vec(&mut self, envs: &mut VecEnv<E>, num_steps: usize) {
+        if envs.states().is_empty() {
+            envs.reset();
+        }
+        let n = self.n_step[0].n();
+        while self.n_step.len() < envs.len() {
+            self.n_step.push(NStep::new(n, self.gamma));
+        }
+
+        for _ in 0..num_steps {
+            let states = envs.states().to_vec();
+            let actions = self.act_batch(envs, states.clone());
+            let (VecStepResult { steps, .. }, final_masks) = envs.step_masked(actions.clone());
+
+            let experiences = states.into_iter().zip(actions).zip(steps).zip(final_masks);
+            for ((((state, action), step), next_mask), n_step) in experiences.zip(&mut self.n_step)
+            {
+                let done = step.is_done();
+                if done {
+                    self.episodes_elapsed += 1;
                 }
-                Memory::Prioritized(memory) => {
-                    memory.push(exp);
-                    self.learn_prioritized(&mut optimizer);
+
+                let mut completed = n_step.push(Exp {
+                    state,
+                    action,
+                    reward: step.reward,
+                    next_state: step.next_state,
+                    next_mask,
+                    steps: 1,
+                });
+                if done {
+                    completed.extend(n_step.flush());
+                }
+
+                for exp in completed {
+                    self.memory.push(exp);
                 }
             }
 
-            self.total_steps += 1;
+            self.advance(envs.len() as u32);
+        }
+    }
+}
+
+impl<B, M, E, DEC, const D: usize, LR> DQNAgent<B, M, E, DEC, D, LR>
+where
+    B: AutodiffBackend,
+    M: DQNModel<B, D>,
+    E: Environment,
+    DEC: Decay,
+    LR: Decay,
+{
+    /// Save the policy network to a file with burn's [BinFileRecorder]
+    ///
+    /// This is all that is needed to ship a trained policy. Use [save_checkpoint](Self::save_checkpoint) to be able
+    /// to resume training.
+    pub fn save_policy(&self, path: impl Into<PathBuf>) -> Result<(), PersistError> {
+        let recorder = BinFileRecorder::<FullPrecisionSettings>::new();
+        self.policy_net
+            .clone()
+            .unwrap()
+            .save_file(path, &recorder)?;
+        Ok(())
+    }
+
+    /// Load a policy network saved with [save_policy](Self::save_policy), using it as both the policy and target
+    /// networks
+    ///
+    /// The agent must have been initialized with a model of the same architecture. On error, the agent is left unchanged.
+    pub fn load_policy(&mut self, path: impl Into<PathBuf>) -> Result<(), PersistError> {
+        let recorder = BinFileRecorder::<FullPrecisionSettings>::new();
+        let policy_net =
+            self.policy_net
+                .clone()
+                .unwrap()
+                .load_file(path, &recorder, self.device)?;
+        self.target_net = Some(policy_net.clone());
+        self.policy_net = Some(policy_net);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<B, M, E, DEC, const D: usize, LR> DQNAgent<B, M, E, DEC, D, LR>
+where
+    B: AutodiffBackend,
+    M: DQNModel<B, D>,
+    E: Environment,
+    E::State: Serialize + DeserializeOwned,
+    E::Action: Serialize + DeserializeOwned,
+    DEC: Decay,
+    LR: Decay,
+{
+    /// Save a full training checkpoint into the directory `dir`, creating it if needed
+    ///
+    /// The policy network, target network, and optimizer state are recorded with burn's [BinFileRecorder], and the
+    /// replay memory, the pending n-step transitions of unfinished episodes, and training progress are serialized
+    /// alongside them. Pending transitions continue the episodes in progress when the checkpoint was taken, so resume
+    /// [go_vec](Self::go_vec) with the same environments. Random number generator states are not saved, so a resumed
+    /// run will not exactly reproduce an uninterrupted one.
+    pub fn save_checkpoint(&self, dir: impl AsRef<Path>) -> Result<(), PersistError> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        let recorder = BinFileRecorder::<FullPrecisionSettings>::new();
+        let policy_net = self.policy_net.clone().unwrap();
+        let target_net = self.target_net.clone().unwrap();
+        policy_net.save_file(dir.join("policy_net"), &recorder)?;
+        target_net.save_file(dir.join("target_net"), &recorder)?;
+        self.optimizer.save_file(&recorder, dir.join("optimizer"))?;
+
+        persist::save(
+            &(
+                &self.memory,
+                &self.n_step,
+                self.total_steps,
+                self.total_gradient_steps,
+                self.episodes_elapsed,
+            ),
+            &dir.join("agent.bin"),
+            Format::Binary,
+        )?;
+
+        Ok(())
+    }
+
+    /// Resume from a checkpoint saved with [save_checkpoint](Self::save_checkpoint)
+    ///
+    /// The agent must have been initialized with a model of the same architecture and the same configuration.
+    /// On error, the agent is left unchanged.
+    pub fn load_checkpoint(&mut self, dir: impl AsRef<Path>) -> Result<(), PersistError> {
+        let dir = dir.as_ref();
+
+        let recorder = BinFileRecorder::<FullPrecisionSettings>::new();
+        let model = self.policy_net.clone().unwrap();
+        let policy_net = model
+            .clone()
+            .load_file(dir.join("policy_net"), &recorder, self.device)?;
+        let target_net = model.load_file(dir.join("target_net"), &recorder, self.device)?;
+        let optimizer =
+            self.optimizer
+                .clone()
+                .load_file(&recorder, dir.join("optimizer"), self.device)?;
+
+        let (memory, n_step, total_steps, total_gradient_steps, episodes_elapsed) =
+            persist::load(&dir.join("agent.bin"), Format::Binary)?;
+
+        self.policy_net = Some(policy_net);
+        self.target_net = Some(target_net);
+        self.optimizer = optimizer;
+        self.memory = memory;
+        self.n_step = n_step;
+        self.total_steps = total_steps;
+        self.total_gradient_steps = total_gradient_steps;
+        self.episodes_elapsed = episodes_elapsed;
+
+        Ok(())
+    }
+}
+
+impl<B, M, E, DEC, const D: usize, LR> Agent<E> for DQNAgent<B, M, E, DEC, D, LR>
+where
+    B: AutodiffBackend<FloatElem = f32, IntElem = i32>,
+    M: DQNModel<B, D>,
+    E: MaskedActionSpace,
+    DEC: Decay,
+    LR: Decay,
+    Vec<E::State>: ToTensor<B, D, Float>,
+    Vec<E::Action>: ToTensor<B, 2, Int>,
+    E::Action: From<usize> + Into<usize>,
+{
+    /// Invoke the agent's policy along with the exploration strategy to choose a legal action from the given state
+    fn act(&mut self, env: &mut E, state: E::State) -> E::Action {
+        match self.exploration.choose(self.total_steps, &mut self.rng) {
+            Choice::Explore => env
+                .actions()
+                .choose(&mut self.rng)
+                .cloned()
+                .expect("There is always at least one action available"),
+            Choice::Exploit => self
+                .greedy_actions(vec![state], &[legal_mask(env)])
+                .remove(0),
+        }
+    }
+
+    fn act_greedy(&mut self, env: &mut E, state: E::State) -> E::Action {
+        self.greedy_actions(vec![state], &[legal_mask(env)])
+            .remove(0)
+    }
+
+    /// Store the experience in replay memory, aggregated over `n_step` steps, and learn if an update is due
+    fn observe(&mut self, env: &E, mut exp: Exp<E>) {
+        exp.next_mask = exp.next_state.as_ref().and_then(|_| legal_mask(env));
+
+        for exp in self.n_step[0].push(exp) {
+            self.memory.push(exp);
+        }
+
+        self.advance(1);
+    }
+
+    /// Store the remaining n-step experiences of a truncated episode
+    fn end_episode(&mut self) {
+        for exp in self.n_step[0].flush() {
+            self.memory.push(exp);
         }
 
         self.episodes_elapsed += 1;
     }
 }
+
+impl<B, M, E, DEC, const D: usize, LR> Debug for DQNAgent<B, M, E, DEC, D, LR>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+    E: Environment,
+    DEC: Decay,
+    LR: Decay,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DQNAgent")
+            .field("gamma", &self.gamma)
+            .field("td_loss", &self.td_loss)
+            .field("double_dqn", &self.double_dqn)
+            .field("target_update", &self.target_update)
+            .field("target_update_interval", &self.target_update_interval)
+            .field("learning_starts", &self.learning_starts)
+            .field("train_freq", &self.train_freq)
+            .field("gradient_steps", &self.gradient_steps)
+            .field("total_steps", &self.total_steps)
+            .field("total_gradient_steps", &self.total_gradient_steps)
+            .field("episodes_elapsed", &self.episodes_elapsed)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn::module::Param;
+
+    use crate::{
+        algo::tests::{B, DEVICE},
+        env::{DiscreteActionSpace, StepResult},
+    };
+
+    use super::*;
+
+    /// Counts steps and is truncated after three, where action `1` is only legal after an even number of steps
+    struct Parity {
+        count: usize,
+    }
+
+    impl Environment for Parity {
+        type State = [f32; 2];
+        type Action = usize;
+
+        fn step(&mut self, _action: Self::Action) -> StepResult<Self::State> {
+            self.count += 1;
+            let state = [self.count as f32, 1.0];
+            if self.count == 3 {
+                StepResult::truncated(state, 0.0)
+            } else {
+                StepResult::new(Some(state), 0.0)
+            }
+        }
+
+        fn reset(&mut self) -> Self::State {
+            self.count = 0;
+            [0.0, 1.0]
+        }
+
+        fn random_action(&mut self) -> Self::Action {
+            0
+        }
+    }
+
+    impl DiscreteActionSpace for Parity {
+        fn actions(&self) -> Vec<Self::Action> {
+            if self.count % 2 == 0 {
+                vec![0, 1]
+            } else {
+                vec![0]
+            }
+        }
+    }
+
+    impl MaskedActionSpace for Parity {
+        fn num_actions(&self) -> usize {
+            2
+        }
+    }
+
+    #[derive(Module, Debug)]
+    struct QNet<B: Backend> {
+        fc: Linear<B>,
+    }
+
+    impl QNet<B> {
+        fn new() -> Self {
+            Self {
+                fc: LinearConfig::new(2, 2).init(&DEVICE),
+            }
+        }
+    }
+
+    impl DQNModel<B, 2> for QNet<B> {
+        fn forward(&self, input: Tensor<B, 2>) -> Tensor<B, 2> {
+            self.fc.forward(input)
+        }
+    }
+
+    /// A Q network with the given weights and no bias
+    fn q_net(weights: [[f32; 2]; 2]) -> QNet<B> {
+        QNet {
+            fc: Linear {
+                weight: Param::from(Tensor::from_floats(weights, &DEVICE)),
+                bias: None,
+            },
+        }
+    }
+
+    type Dqn = DQNAgent<B, QNet<B>, Parity, decay::Exponential, 2>;
+
+    fn config() -> DQNAgentConfig<decay::Exponential> {
+        DQNAgentConfig {
+            memory_capacity: 64,
+            memory_batch_size: 4,
+            gamma: 0.9,
+            seed: Some(0),
+            ..Default::default()
+        }
+    }
+
+    /// An experience that is always available to learn from with a batch size of 1
+    fn exp() -> Exp<Parity> {
+        Exp {
+            state: [0.0, 1.0],
+            action: 0,
+            reward: 1.0,
+            next_state: Some([1.0, 1.0]),
+            next_mask: None,
+            steps: 1,
+        }
+    }
+
+    fn weights(net: &Option<QNet<B>>) -> Vec<f32> {
+        net.as_ref().unwrap().fc.weight.val().into_data().value
+    }
+
+    fn probe_states() -> Vec<[f32; 2]> {
+        vec![[0.0, 1.0], [1.0, 1.0], [2.0, 1.0]]
+    }
+
+    /// The Q values of a network in the probe states
+    fn q_values(net: &Option<QNet<B>>) -> Vec<f32> {
+        net.as_ref()
+            .unwrap()
+            .forward(probe_states().to_tensor(&DEVICE))
+            .into_data()
+            .convert::<f32>()
+            .value
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn dqn_checkpoint_round_trip() {
+        let config = || DQNAgentConfig {
+            n_step: 3,
+            ..config()
+        };
+        let mut agent = Dqn::new(QNet::new(), config(), &DEVICE);
+        let mut env = Parity { count: 0 };
+        for _ in 0..4 {
+            agent.go(&mut env);
+        }
+        let mut envs = VecEnv::from_fn(2, |_| Parity { count: 0 });
+        agent.go_vec(&mut envs, 2);
+
+        let dir = std::env::temp_dir().join(format!("rl_dqn_checkpoint_{}", std::process::id()));
+        agent.save_checkpoint(&dir).unwrap();
+        agent.save_policy(dir.join("policy")).unwrap();
+        let mut resumed = Dqn::new(QNet::new(), config(), &DEVICE);
+        resumed.load_checkpoint(&dir).unwrap();
+        let mut deployed = Dqn::new(QNet::new(), config(), &DEVICE);
+        deployed.load_policy(dir.join("policy")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(
+            agent.total_gradient_steps > 0,
+            "Agent trained before saving"
+        );
+        assert_eq!(
+            q_values(&resumed.policy_net),
+            q_values(&agent.policy_net),
+            "Policy network restored from checkpoint"
+        );
+        assert_eq!(
+            q_values(&resumed.target_net),
+            q_values(&agent.target_net),
+            "Target network restored from checkpoint"
+        );
+        assert_eq!(
+            (
+                resumed.total_steps,
+                resumed.total_gradient_steps,
+                resumed.episodes_elapsed
+            ),
+            (
+                agent.total_steps,
+                agent.total_gradient_steps,
+                agent.episodes_elapsed
+            ),
+            "Training progress restored from checkpoint"
+        );
+        let pending = |agent: &mut Dqn| {
+            agent
+                .n_step
+                .iter_mut()
+                .flat_map(|n_step| n_step.flush())
+                .map(|exp| (exp.state, exp.reward, exp.next_state, exp.steps))
+                .collect::<Vec<_>>()
+        };
+        let agent_pending = pending(&mut agent);
+        assert_eq!(agent_pending.len(), 4, "Two steps into both episodes");
+        assert_eq!(
+            pending(&mut resumed),
+            agent_pending,
+            "Pending n-step transitions restored from checkpoint"
+        );
+        assert_eq!(
+            resumed.greedy_actions(probe_states(), &[None, None, None]),
+            agent.greedy_actions(probe_states(), &[None, None, None]),
+            "Greedy actions unchanged by the round trip"
+        );
+        assert_eq!(
+            q_values(&deployed.policy_net),
+            q_values(&agent.policy_net),
+            "Policy network restored from policy file"
+        );
+        assert_eq!(
+            q_values(&deployed.target_net),
+            q_values(&agent.policy_net),
+            "Loaded policy is also the target network"
+        );
+    }
+
+    #[test]
+    fn dqn_vec_env_keeps_final_masks() {
+        let mut agent = Dqn::new(
+            QNet::new(),
+            DQNAgentConfig {
+                learning_starts: 100,
+                ..config()
+            },
+            &DEVICE,
+        );
+        let mut envs = VecEnv::from_fn(2, |_| Parity { count: 0 });
+        agent.go_vec(&mut envs, 6);
+
+        assert_eq!(agent.episodes_elapsed, 4, "Truncated episodes counted");
+        let Memory::Base(memory) = &mut agent.memory else {
+            unreachable!()
+        };
+        memory.batch_size = 12;
+        for exp in memory.sample().expect("Every step was stored") {
+            let count = exp.next_state.unwrap()[0] as usize;
+            assert_eq!(
+                exp.next_mask,
+                (count % 2 == 1).then(|| vec![true, false]),
+                "Next mask matches the next state, including the final state of truncated episodes"
+            );
+        }
+    }
+
+    #[test]
+    fn dueling_head_centres_advantages() {
+        let head = DuelingHeadConfig::new(3, 4).init::<B>(&DEVICE);
+        let features = Tensor::<B, 2>::from_floats([[1.0, 2.0, 3.0], [-1.0, 0.5, 2.0]], &DEVICE);
+        let q_values = head.forward(features.clone());
+        let values = head.value.forward(features);
+
+        assert!(
+            (q_values - values).mean_dim(1).abs().max().into_scalar() < 1e-5,
+            "Advantages are mean-centred, so Q values average to the state value"
+        );
+    }
+
+    #[test]
+    fn double_dqn_next_q_values() {
+        // The policy network prefers action 0, while the target network values action 1 more
+        let policy_net = q_net([[1.0, 0.0], [0.0, 0.0]]);
+        let target_net = q_net([[2.0, 3.0], [0.0, 0.0]]);
+        let next_q = |double_dqn, mask: Option<Vec<bool>>| {
+            let agent = Dqn::new(
+                QNet::new(),
+                DQNAgentConfig {
+                    double_dqn,
+                    ..config()
+                },
+                &DEVICE,
+            );
+            agent
+                .next_q_values(
+                    &policy_net,
+                    &target_net,
+                    vec![[1.0, 0.0]].to_tensor(&DEVICE),
+                    &[mask],
+                )
+                .into_scalar()
+        };
+
+        assert_eq!(
+            next_q(false, None),
+            3.0,
+            "DQN evaluates the target network's best action"
+        );
+        assert_eq!(
+            next_q(true, None),
+            2.0,
+            "Double DQN evaluates the policy network's best action with the target network"
+        );
+        assert_eq!(
+            next_q(true, Some(vec![false, true])),
+            3.0,
+            "Double DQN only selects legal actions"
+        );
+    }
+
+    #[test]
+    fn crossed_functional() {
+        assert!(crossed(3, 4, 4), "Reaching a multiple crosses it");
+        assert!(!crossed(4, 7, 4), "Leaving a multiple does not cross it");
+        assert!(
+            crossed(2, 6, 4),
+            "A batch of steps crosses the multiple within it"
+        );
+        assert!(!crossed(4, 4, 4), "No steps cross nothing");
+        assert!(crossed(0, 1, 0), "An interval of zero is treated as one");
+    }
+
+    #[test]
+    fn dqn_soft_and_hard_target_updates() {
+        let mut agent = Dqn::new(
+            QNet::new(),
+            DQNAgentConfig {
+                target_update: TargetUpdate::Soft { tau: 0.25 },
+                ..config()
+            },
+            &DEVICE,
+        );
+        agent.policy_net = Some(q_net([[4.0, 0.0], [0.0, 4.0]]));
+        agent.target_net = Some(q_net([[0.0, 0.0], [0.0, 0.0]]));
+
+        agent.update_target();
+        assert_eq!(
+            weights(&agent.target_net),
+            [1.0, 0.0, 0.0, 1.0],
+            "Soft update moves the target network by tau"
+        );
+
+        agent.target_update = TargetUpdate::Hard;
+        agent.update_target();
+        assert_eq!(
+            weights(&agent.target_net),
+            [4.0, 0.0, 0.0, 4.0],
+            "Hard update copies the policy network"
+        );
+    }
+
+    #[test]
+    fn dqn_training_schedule() {
+        let mut agent = Dqn::new(
+            QNet::new(),
+            DQNAgentConfig {
+                memory_batch_size: 1,
+                learning_starts: 3,
+                train_freq: 2,
+                gradient_steps: 3,
+                ..config()
+            },
+            &DEVICE,
+        );
+        agent.memory.push(exp());
+
+        let gradient_steps = [1, 2, 1, 1, 1, 4]
+            .into_iter()
+            .map(|steps| {
+                agent.advance(steps);
+                agent.total_gradient_steps
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            gradient_steps,
+            [0, 0, 3, 3, 6, 9],
+            "Learning starts after learning_starts steps, and every train_freq steps performs gradient_steps \
+             gradient steps, once per batch of steps"
+        );
+    }
+
+    #[test]
+    fn dqn_target_update_intervals() {
+        let synced = |agent: &Dqn| q_values(&agent.target_net) == q_values(&agent.policy_net);
+
+        let mut agent = Dqn::new(
+            QNet::new(),
+            DQNAgentConfig {
+                learning_starts: 100,
+                target_update: TargetUpdate::Hard,
+                target_update_interval: Interval::Steps(4),
+                ..config()
+            },
+            &DEVICE,
+        );
+        agent.policy_net = Some(q_net([[1.0, 2.0], [3.0, 4.0]]));
+        agent.advance(3);
+        assert!(
+            !synced(&agent),
+            "Target network waits for the step interval"
+        );
+        agent.advance(2);
+        assert!(
+            synced(&agent),
+            "Target network updated when a batch of steps crosses the step interval"
+        );
+
+        let mut agent = Dqn::new(
+            QNet::new(),
+            DQNAgentConfig {
+                memory_batch_size: 1,
+                gradient_steps: 3,
+                target_update: TargetUpdate::Hard,
+                target_update_interval: Interval::GradientSteps(2),
+                ..config()
+            },
+            &DEVICE,
+        );
+        agent.memory.push(exp());
+        agent.advance(1);
+        assert!(
+            !synced(&agent),
+            "Policy network moved on after the target update at the second gradient step"
+        );
+        agent.advance(1);
+        assert!(
+            synced(&agent),
+            "Target network updated at the sixth gradient step"
+        );
+    }
+}
@@ -1,4 +1,155 @@
+/// Advantage actor-critic
+pub mod a2c;
+
+/// Shared components of continuous control algorithms
+pub mod continuous;
+
+/// Deep deterministic policy gradient and TD3
+pub mod ddpg;
+
 /// Deep Q Network
 pub mod dqn;
 
+/// Dynamic programming
+pub mod dp;
+
+/// Shared components of policy gradient algorithms
+pub mod policy_gradient;
+
+/// Proximal policy optimization
+pub mod ppo;
+
+/// REINFORCE policy gradient
+pub mod reinforce;
+
+/// Soft actor-critic
+pub mod sac;
+
 pub mod tabular;
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use burn::{
+        backend::{ndarray::NdArrayDevice, Autodiff, NdArray},
+        nn::{Linear, LinearConfig},
+        prelude::*,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{
+        algo::{continuous::CriticModel, policy_gradient::ActorCriticModel},
+        env::{DiscreteActionSpace, Environment, MaskedActionSpace, StepResult},
+        traits::ToTensor,
+    };
+
+    pub(crate) type B = Autodiff<NdArray>;
+
+    pub(crate) static DEVICE: NdArrayDevice = NdArrayDevice::Cpu;
+
+    impl ToTensor<B, 2, Int> for Vec<usize> {
+        fn to_tensor(self, device: &<B as Backend>::Device) -> Tensor<B, 2, Int> {
+            let actions: Tensor<B, 1, Int> = self
+                .into_iter()
+                .map(|a| a as i32)
+                .collect::<Vec<_>>()
+                .to_tensor(device);
+            actions.unsqueeze_dim(1)
+        }
+    }
+
+    /// Linear policy and value heads on a shared input
+    #[derive(Module, Debug)]
+    pub(crate) struct ActorCritic<B: Backend> {
+        policy: Linear<B>,
+        value: Linear<B>,
+    }
+
+    impl ActorCritic<B> {
+        pub fn new(inputs: usize, actions: usize) -> Self {
+            Self {
+                policy: LinearConfig::new(inputs, actions).init(&DEVICE),
+                value: LinearConfig::new(inputs, 1).init(&DEVICE),
+            }
+        }
+    }
+
+    impl ActorCriticModel<B, 2> for ActorCritic<B> {
+        fn forward(&self, input: Tensor<B, 2>) -> (Tensor<B, 2>, Tensor<B, 2>) {
+            (
+                self.policy.forward(input.clone()),
+                self.value.forward(input),
+            )
+        }
+    }
+
+    /// A linear critic of the concatenated state and action
+    #[derive(Module, Debug)]
+    pub(crate) struct Critic<B: Backend> {
+        fc: Linear<B>,
+    }
+
+    impl Critic<B> {
+        pub fn new(inputs: usize) -> Self {
+            Self {
+                fc: LinearConfig::new(inputs, 1).init(&DEVICE),
+            }
+        }
+
+        pub fn weights(&self) -> Vec<f32> {
+            self.fc.weight.val().into_data().value
+        }
+    }
+
+    impl CriticModel<B, 2> for Critic<B> {
+        fn forward(&self, state: Tensor<B, 2>, action: Tensor<B, 2>) -> Tensor<B, 2> {
+            self.fc.forward(Tensor::cat(vec![state, action], 1))
+        }
+    }
+
+    /// A one-step episode in which the agent is rewarded for choosing the arm indicated by the state
+    pub(crate) struct Signal {
+        pub signal: usize,
+        rng: StdRng,
+    }
+
+    impl Signal {
+        pub fn new(seed: u64) -> Self {
+            Self {
+                signal: 0,
+                rng: StdRng::seed_from_u64(seed),
+            }
+        }
+    }
+
+    impl Environment for Signal {
+        type State = [f32; 2];
+        type Action = usize;
+
+        fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
+            StepResult::new(None, (action == self.signal) as i32 as f32)
+        }
+
+        fn reset(&mut self) -> Self::State {
+            self.signal = self.rng.gen_range(0..2);
+            let mut state = [0.0; 2];
+            state[self.signal] = 1.0;
+            state
+        }
+
+        fn random_action(&mut self) -> Self::Action {
+            self.rng.gen_range(0..2)
+        }
+    }
+
+    impl DiscreteActionSpace for Signal {
+        fn actions(&self) -> Vec<Self::Action> {
+            vec![0, 1]
+        }
+    }
+
+    impl MaskedActionSpace for Signal {
+        fn num_actions(&self) -> usize {
+            2
+        }
+    }
+}
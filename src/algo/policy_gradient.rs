@@ -0,0 +1,301 @@
+use burn::{
+    module::AutodiffModule,
+    prelude::*,
+    tensor::{activation::log_softmax, backend::AutodiffBackend},
+};
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+
+use crate::{env::Environment, memory::Exp, nn::illegal_action_tensor, traits::ToTensor};
+
+/// A burn module that outputs action logits, used as the policy of a policy gradient agent
+///
+/// ### Generics
+/// - `B` - A burn backend
+/// - `D` - The dimension of the input tensor
+pub trait PolicyModel<B: AutodiffBackend, const D: usize>: AutodiffModule<B> {
+    /// Forward pass through the model, producing unnormalized log probabilities of shape `[batch_size, num_actions]`
+    fn forward(&self, input: Tensor<B, D>) -> Tensor<B, 2>;
+}
+
+/// A burn module that estimates state values, used as a baseline or critic by policy gradient agents
+///
+/// ### Generics
+/// - `B` - A burn backend
+/// - `D` - The dimension of the input tensor
+pub trait ValueModel<B: AutodiffBackend, const D: usize>: AutodiffModule<B> {
+    /// Forward pass through the model, producing state values of shape `[batch_size, 1]`
+    fn forward(&self, input: Tensor<B, D>) -> Tensor<B, 2>;
+}
+
+/// A burn module with a policy head producing action logits and a value head estimating state values, used by
+/// actor-critic agents
+///
+/// ### Generics
+/// - `B` - A burn backend
+/// - `D` - The dimension of the input tensor
+pub trait ActorCriticModel<B: AutodiffBackend, const D: usize>: AutodiffModule<B> {
+    /// Forward pass through the model, producing unnormalized log probabilities of shape `[batch_size, num_actions]`
+    /// and state values of shape `[batch_size, 1]`
+    fn forward(&self, input: Tensor<B, D>) -> (Tensor<B, 2>, Tensor<B, 2>);
+}
+
+/// Compute the discounted return G<sub>t</sub> = r<sub>t</sub> + γG<sub>t+1</sub> from each step of an episode
+///
+/// `bootstrap` is the value of the state after the last reward, which is `0.0` if the episode terminated or an
+/// estimate of the state's value if it was truncated.
+pub fn discounted_returns(rewards: &[f32], gamma: f32, bootstrap: f32) -> Vec<f32> {
+    let mut returns = rewards
+        .iter()
+        .rev()
+        .scan(bootstrap, |g, &reward| {
+            *g = reward + gamma * *g;
+            Some(*g)
+        })
+        .collect::<Vec<_>>();
+    returns.reverse();
+    returns
+}
+
+/// Compute generalized advantage estimates ([Schulman et al. 2015](https://arxiv.org/abs/1506.02438)) over a
+/// rollout of consecutive steps
+///
+/// A<sub>t</sub> = δ<sub>t</sub> + γλA<sub>t+1</sub>, where δ<sub>t</sub> = r<sub>t</sub> + γV(s<sub>t+1</sub>) − V(s<sub>t</sub>)
+///
+/// ### Arguments
+/// - `rewards` - The reward of each step
+/// - `values` - The estimated value of the state of each step
+/// - `next_values` - The estimated value of the next state of each step, which is `0.0` if the step was terminal
+/// - `dones` - Whether each step ended an episode, terminal or truncated, so that advantages are not carried across
+///   episodes
+/// - `gamma` - The discount factor
+/// - `lambda` - The GAE parameter, trading off bias (`0.0`, one-step TD) against variance (`1.0`, Monte Carlo)
+///
+/// Add `values` to the advantages to get the value targets.
+pub fn gae(
+    rewards: &[f32],
+    values: &[f32],
+    next_values: &[f32],
+    dones: &[bool],
+    gamma: f32,
+    lambda: f32,
+) -> Vec<f32> {
+    let mut advantages = vec![0.0; rewards.len()];
+    let mut advantage = 0.0;
+    for t in (0..rewards.len()).rev() {
+        if dones[t] {
+            advantage = 0.0;
+        }
+        let delta = rewards[t] + gamma * next_values[t] - values[t];
+        advantage = delta + gamma * lambda * advantage;
+        advantages[t] = advantage;
+    }
+
+    advantages
+}
+
+/// The transitions collected by an on-policy agent since its last update
+///
+/// Rollouts may span several episodes, which are separated by `dones`.
+pub(crate) struct Rollout<E: Environment> {
+    pub(crate) states: Vec<E::State>,
+    pub(crate) actions: Vec<E::Action>,
+    pub(crate) rewards: Vec<f32>,
+    pub(crate) masks: Vec<Option<Vec<bool>>>,
+    pub(crate) next_states: Vec<Option<E::State>>,
+    pub(crate) dones: Vec<bool>,
+}
+
+impl<E: Environment> Rollout<E> {
+    pub(crate) fn len(&self) -> usize {
+        self.rewards.len()
+    }
+
+    /// Add a transition, whose action mask must already have been pushed when the action was chosen
+    pub(crate) fn push(&mut self, exp: Exp<E>) {
+        self.dones.push(exp.next_state.is_none());
+        self.states.push(exp.state);
+        self.actions.push(exp.action);
+        self.rewards.push(exp.reward);
+        self.next_states.push(exp.next_state);
+    }
+
+    /// Mark the last transition as the end of an episode, so advantages are not carried across it
+    pub(crate) fn end_episode(&mut self) {
+        if let Some(done) = self.dones.last_mut() {
+            *done = true;
+        }
+    }
+}
+
+impl<E: Environment> Default for Rollout<E> {
+    fn default() -> Self {
+        Self {
+            states: Vec::new(),
+            actions: Vec::new(),
+            rewards: Vec::new(),
+            masks: Vec::new(),
+            next_states: Vec::new(),
+            dones: Vec::new(),
+        }
+    }
+}
+
+impl<E: Environment> Clone for Rollout<E> {
+    fn clone(&self) -> Self {
+        Self {
+            states: self.states.clone(),
+            actions: self.actions.clone(),
+            rewards: self.rewards.clone(),
+            masks: self.masks.clone(),
+            next_states: self.next_states.clone(),
+            dones: self.dones.clone(),
+        }
+    }
+}
+
+/// Estimate the value of each next state of a rollout with the critic of `model`, where terminal states have no
+/// value
+pub(crate) fn next_state_values<B, M, S, const D: usize>(
+    model: &M,
+    next_states: &[Option<S>],
+    device: &B::Device,
+) -> Vec<f32>
+where
+    B: AutodiffBackend,
+    M: ActorCriticModel<B, D>,
+    S: Clone,
+    Vec<S>: ToTensor<B, D, Float>,
+{
+    let bootstrapped = next_states.iter().flatten().cloned().collect::<Vec<_>>();
+    if bootstrapped.is_empty() {
+        return vec![0.0; next_states.len()];
+    }
+    let (_, values) = model.forward(bootstrapped.to_tensor(device));
+    let mut values = values.into_data().convert::<f32>().value.into_iter();
+
+    next_states
+        .iter()
+        .map(|next_state| match next_state {
+            Some(_) => values.next().unwrap(),
+            None => 0.0,
+        })
+        .collect()
+}
+
+/// Standardize values to zero mean and unit variance
+pub(crate) fn normalize<B: Backend>(values: Tensor<B, 1>) -> Tensor<B, 1> {
+    let mean = values.clone().mean();
+    let centered = values - mean;
+    let std = centered.clone().powf_scalar(2.0).mean().sqrt();
+    centered / (std + 1e-8)
+}
+
+/// The logit given to illegal actions
+///
+/// This is finite so that illegal actions have zero probability without producing `NaN` in the entropy
+const MASKED_LOGIT: f32 = -1e9;
+
+/// A batch of categorical distributions over actions, parameterized by logits
+pub(crate) struct Categorical<B: Backend> {
+    log_probs: Tensor<B, 2>,
+}
+
+impl<B: Backend> Categorical<B> {
+    /// Build the distributions from logits of shape `[batch_size, num_actions]`, giving illegal actions zero
+    /// probability
+    pub(crate) fn new(
+        logits: Tensor<B, 2>,
+        masks: &[Option<Vec<bool>>],
+        device: &B::Device,
+    ) -> Self {
+        let illegal_mask = illegal_action_tensor(masks, logits.dims()[1], device);
+        Self {
+            log_probs: log_softmax(logits.mask_fill(illegal_mask, MASKED_LOGIT), 1),
+        }
+    }
+
+    /// The log probability of each action in a tensor of shape `[batch_size, 1]`
+    pub(crate) fn log_prob(&self, actions: Tensor<B, 2, Int>) -> Tensor<B, 1> {
+        self.log_probs.clone().gather(1, actions).squeeze(1)
+    }
+
+    /// The entropy of each distribution
+    pub(crate) fn entropy(&self) -> Tensor<B, 1> {
+        let probs = self.log_probs.clone().exp();
+        (probs * self.log_probs.clone())
+            .sum_dim(1)
+            .squeeze::<1>(1)
+            .neg()
+    }
+
+    /// Sample an action index from each distribution
+    pub(crate) fn sample(&self, rng: &mut impl Rng) -> Vec<usize> {
+        let [batch_size, num_actions] = self.log_probs.dims();
+        let probs = self
+            .log_probs
+            .clone()
+            .exp()
+            .into_data()
+            .convert::<f32>()
+            .value;
+
+        (0..batch_size)
+            .map(|i| {
+                WeightedIndex::new(&probs[i * num_actions..(i + 1) * num_actions])
+                    .expect("At least one action has a nonzero probability")
+                    .sample(rng)
+            })
+            .collect()
+    }
+
+    /// The most likely action index of each distribution
+    pub(crate) fn mode(&self) -> Vec<usize> {
+        self.log_probs
+            .clone()
+            .argmax(1)
+            .into_data()
+            .convert::<i64>()
+            .value
+            .into_iter()
+            .map(|a| a as usize)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discounted_returns_functional() {
+        assert_eq!(
+            discounted_returns(&[1.0, 2.0, 4.0], 0.5, 0.0),
+            [3.0, 4.0, 4.0],
+            "Returns discounted from the end of a terminated episode"
+        );
+        assert_eq!(
+            discounted_returns(&[1.0, 2.0, 4.0], 0.5, 8.0),
+            [4.0, 6.0, 8.0],
+            "Truncated episodes bootstrap from the value of the final state"
+        );
+    }
+
+    #[test]
+    fn gae_functional() {
+        let rewards = [1.0, 1.0, 1.0];
+        let values = [2.0, 2.0, 2.0];
+        let next_values = [2.0, 0.0, 2.0];
+        let dones = [false, true, false];
+
+        assert_eq!(
+            gae(&rewards, &values, &next_values, &dones, 0.5, 1.0),
+            [-0.5, -1.0, 0.0],
+            "Advantages accumulate within an episode only"
+        );
+        assert_eq!(
+            gae(&rewards, &values, &next_values, &dones, 0.5, 0.0),
+            [0.0, -1.0, 0.0],
+            "Lambda of zero gives one-step TD errors"
+        );
+    }
+}
@@ -0,0 +1,465 @@
+use std::fmt::Debug;
+
+use burn::{
+    module::AutodiffModule, optim::GradientsParams, prelude::*, tensor::backend::AutodiffBackend,
+};
+use rand::{rngs::StdRng, seq::SliceRandom};
+
+use crate::{
+    algo::policy_gradient::{
+        gae, next_state_values, normalize, ActorCriticModel, Categorical, Rollout,
+    },
+    decay::{self, Decay},
+    env::{Aggregation, Environment, MaskedActionSpace, Report},
+    memory::Exp,
+    nn::{legal_mask, GradientClipping, ModelOptimizer, OptimizerConfig},
+    traits::{Agent, ToTensor},
+    util::rng_from_seed,
+};
+
+/// Configuration for the [`PPOAgent`]
+///
+/// ### Generics
+/// - `LR` - The learning rate schedule
+#[derive(Debug, Clone)]
+pub struct PPOAgentConfig<LR = decay::Constant> {
+    /// The number of steps in each rollout, after which the agent learns
+    ///
+    /// Rollouts continue across episode boundaries.
+    ///
+    /// **Default:** `2048`
+    pub n_steps: usize,
+    /// The number of steps in each minibatch
+    ///
+    /// **Default:** `64`
+    pub batch_size: usize,
+    /// The number of passes over each rollout
+    ///
+    /// **Default:** `10`
+    pub n_epochs: usize,
+    /// The discount factor
+    ///
+    /// **Default:** `0.99`
+    pub gamma: f32,
+    /// The GAE parameter λ (see [`gae`])
+    ///
+    /// **Default:** `0.95`
+    pub gae_lambda: f32,
+    /// The clipping range ε of the surrogate objective, which limits the probability ratio of the new and old policy
+    /// to `[1 - ε, 1 + ε]`
+    ///
+    /// **Default:** `0.2`
+    pub clip_range: f32,
+    /// The clipping range of the value function, which limits how far value estimates move from those of the old
+    /// model, or `None` to leave them unclipped
+    ///
+    /// This depends on the scale of the rewards.
+    ///
+    /// **Default:** `None`
+    pub clip_range_vf: Option<f32>,
+    /// Standardize the advantages of each minibatch to zero mean and unit variance
+    ///
+    /// **Default:** `true`
+    pub normalize_advantages: bool,
+    /// The weight of the value loss relative to the policy loss
+    ///
+    /// **Default:** `0.5`
+    pub value_coef: f32,
+    /// The weight of the entropy bonus
+    ///
+    /// **Default:** `0.0`
+    pub entropy_coef: f32,
+    /// Stop learning from a rollout early once the approximate KL divergence between the old and new policy exceeds
+    /// `1.5 * target_kl`, or `None` to always run every epoch
+    ///
+    /// **Default:** `None`
+    pub target_kl: Option<f32>,
+    /// The optimizer to train the model with
+    ///
+    /// **Default:** [`OptimizerConfig::adam`]
+    pub optimizer: OptimizerConfig,
+    /// The gradient clipping applied before each optimizer step, or `None` to leave gradients unclipped
+    ///
+    /// **Default:** `Some(GradientClipping::Norm(0.5))`
+    pub grad_clipping: Option<GradientClipping>,
+    /// The learning rate schedule for the optimizer, evaluated at the agent's total number of steps
+    ///
+    /// **Default:** [`Constant`](decay::Constant) learning rate `3e-4`
+    pub lr_schedule: LR,
+    /// Seed for the agent's random number generator, which drives action sampling and minibatch shuffling, or `None`
+    /// to seed from system entropy
+    ///
+    /// **Default:** `None`
+    pub seed: Option<u64>,
+}
+
+impl Default for PPOAgentConfig {
+    fn default() -> Self {
+        Self {
+            n_steps: 2048,
+            batch_size: 64,
+            n_epochs: 10,
+            gamma: 0.99,
+            gae_lambda: 0.95,
+            clip_range: 0.2,
+            clip_range_vf: None,
+            normalize_advantages: true,
+            value_coef: 0.5,
+            entropy_coef: 0.0,
+            target_kl: None,
+            optimizer: OptimizerConfig::adam(),
+            grad_clipping: Some(GradientClipping::Norm(0.5)),
+            lr_schedule: decay::Constant::new(3e-4),
+            seed: None,
+        }
+    }
+}
+
+/// A Proximal Policy Optimization agent ([Schulman et al. 2017](https://arxiv.org/abs/1707.06347))
+///
+/// The agent collects rollouts of `n_steps` steps and estimates advantages with [`gae`]. It then makes several
+/// passes over each rollout in shuffled minibatches, maximizing the clipped surrogate objective so that the policy
+/// cannot move far from the one that collected the rollout.
+///
+/// The agent's [report](Agent::report) records the mean `policy_loss`, `value_loss`, `entropy`, `approx_kl`, and
+/// `clip_fraction` of each episode's minibatch updates.
+///
+/// ### Generics
+/// - `B` - A burn backend
+/// - `M` - The [`ActorCriticModel`] with the policy and value heads
+/// - `E` - The [`Environment`] in which the agent will learn
+///     - The environment's action space must be discrete. Actions that are illegal according to its
+///       [`MaskedActionSpace`] are never chosen.
+/// - `D` - The dimension of the input
+/// - `LR` - The learning rate schedule
+#[derive(Clone)]
+pub struct PPOAgent<B, M, E, const D: usize, LR = decay::Constant>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+    E: Environment,
+    LR: Decay,
+{
+    model: Option<M>,
+    optimizer: ModelOptimizer<M, B>,
+    device: &'static B::Device,
+    rollout: Rollout<E>,
+    n_steps: usize,
+    batch_size: usize,
+    n_epochs: usize,
+    gamma: f32,
+    gae_lambda: f32,
+    clip_range: f32,
+    clip_range_vf: Option<f32>,
+    normalize_advantages: bool,
+    value_coef: f32,
+    entropy_coef: f32,
+    target_kl: Option<f32>,
+    lr_schedule: LR,
+    report: Report,
+    total_steps: u32,
+    rng: StdRng,
+}
+
+impl<B, M, E, const D: usize, LR> PPOAgent<B, M, E, D, LR>
+where
+    B: AutodiffBackend<FloatElem = f32, IntElem = i32>,
+    M: ActorCriticModel<B, D>,
+    E: MaskedActionSpace,
+    LR: Decay,
+    Vec<E::State>: ToTensor<B, D, Float>,
+    Vec<E::Action>: ToTensor<B, 2, Int>,
+    E::Action: From<usize> + Into<usize>,
+{
+    /// Initialize a new `PPOAgent`
+    ///
+    /// ### Arguments
+    /// - `model` An [`ActorCriticModel`] to be trained
+    /// - `config` A [`PPOAgentConfig`] containing hyperparameters for the agent
+    /// - `device` A static reference to the device used for the `model`
+    ///
+    /// **Panics** if `n_steps` or `batch_size` is zero
+    pub fn new(model: M, config: PPOAgentConfig<LR>, device: &'static B::Device) -> Self {
+        assert!(
+            config.n_steps > 0 && config.batch_size > 0,
+            "n_steps and batch_size must be at least 1"
+        );
+        Self {
+            model: Some(model),
+            optimizer: ModelOptimizer::new(&config.optimizer, config.grad_clipping),
+            device,
+            rollout: Rollout::default(),
+            n_steps: config.n_steps,
+            batch_size: config.batch_size,
+            n_epochs: config.n_epochs,
+            gamma: config.gamma,
+            gae_lambda: config.gae_lambda,
+            clip_range: config.clip_range,
+            clip_range_vf: config.clip_range_vf,
+            normalize_advantages: config.normalize_advantages,
+            value_coef: config.value_coef,
+            entropy_coef: config.entropy_coef,
+            target_kl: config.target_kl,
+            lr_schedule: config.lr_schedule,
+            report: Report::default()
+                .with_metric("policy_loss", Aggregation::Mean)
+                .with_metric("value_loss", Aggregation::Mean)
+                .with_metric("entropy", Aggregation::Mean)
+                .with_metric("approx_kl", Aggregation::Mean)
+                .with_metric("clip_fraction", Aggregation::Mean),
+            total_steps: 0,
+            rng: rng_from_seed(config.seed),
+        }
+    }
+
+    /// Compute the action distribution of the policy in a single state
+    fn policy(&self, state: E::State, mask: Option<Vec<bool>>) -> Categorical<B> {
+        let (logits, _) = self
+            .model
+            .as_ref()
+            .unwrap()
+            .forward(vec![state].to_tensor(self.device));
+        Categorical::new(logits, &[mask], self.device)
+    }
+
+    /// Update the model from the collected rollout
+    fn learn(&mut self) {
+        let Rollout {
+            states,
+            actions,
+            rewards,
+            masks,
+            next_states,
+            dones,
+        } = std::mem::take(&mut self.rollout);
+        let mut model = self.model.take().unwrap();
+        let len = rewards.len();
+
+        // Evaluate the rollout under the policy that collected it
+        let next_values = next_state_values(&model, &next_states, self.device);
+        let states: Tensor<B, D> = states.to_tensor(self.device);
+        let actions: Tensor<B, 2, Int> = actions.to_tensor(self.device);
+        let (logits, values) = model.forward(states.clone());
+        let old_log_probs = Categorical::new(logits, &masks, self.device)
+            .log_prob(actions.clone())
+            .detach();
+        let old_values: Tensor<B, 1> = values.squeeze::<1>(1).detach();
+
+        let value_data = old_values.clone().into_data().convert::<f32>().value;
+        let advantages = gae(
+            &rewards,
+            &value_data,
+            &next_values,
+            &dones,
+            self.gamma,
+            self.gae_lambda,
+        );
+        let returns = advantages
+            .iter()
+            .zip(&value_data)
+            .map(|(advantage, value)| advantage + value)
+            .collect::<Vec<_>>();
+        let advantages: Tensor<B, 1> = advantages.to_tensor(self.device);
+        let returns: Tensor<B, 1> = returns.to_tensor(self.device);
+
+        let lr = self.lr_schedule.evaluate(self.total_steps as f32).into();
+        let mut indices = (0..len).collect::<Vec<_>>();
+        'epochs: for _ in 0..self.n_epochs {
+            indices.shuffle(&mut self.rng);
+            for batch in indices.chunks(self.batch_size) {
+                let batch_masks = batch.iter().map(|&i| masks[i].clone()).collect::<Vec<_>>();
+                let batch: Tensor<B, 1, Int> = batch
+                    .iter()
+                    .map(|&i| i as i32)
+                    .collect::<Vec<_>>()
+                    .to_tensor(self.device);
+                let advantages = advantages.clone().select(0, batch.clone());
+                let advantages = match self.normalize_advantages && batch.dims()[0] > 1 {
+                    true => normalize(advantages),
+                    false => advantages,
+                };
+                let old_log_probs = old_log_probs.clone().select(0, batch.clone());
+                let old_values = old_values.clone().select(0, batch.clone());
+                let returns = returns.clone().select(0, batch.clone());
+
+                let (logits, values) = model.forward(states.clone().select(0, batch.clone()));
+                let dist = Categorical::new(logits, &batch_masks, self.device);
+                let log_probs = dist.log_prob(actions.clone().select(0, batch));
+
+                // Stop early if the policy has already moved too far from the old policy
+                let log_ratio = log_probs - old_log_probs;
+                let ratio = log_ratio.clone().exp();
+                let approx_kl = (ratio.clone() - 1.0 - log_ratio)
+                    .mean()
+                    .into_data()
+                    .convert::<f32>()
+                    .value[0];
+                if self
+                    .target_kl
+                    .is_some_and(|target_kl| approx_kl > 1.5 * target_kl)
+                {
+                    break 'epochs;
+                }
+
+                // Clipped surrogate objective
+                let surrogate = ratio.clone() * advantages.clone();
+                let clipped_surrogate = ratio
+                    .clone()
+                    .clamp(1.0 - self.clip_range, 1.0 + self.clip_range)
+                    * advantages;
+                let policy_loss = surrogate
+                    .clone()
+                    .mask_where(
+                        clipped_surrogate.clone().lower(surrogate),
+                        clipped_surrogate,
+                    )
+                    .mean()
+                    .neg();
+                let clip_fraction = (ratio - 1.0)
+                    .abs()
+                    .greater_elem(self.clip_range)
+                    .float()
+                    .mean();
+
+                // Value loss, pessimistic over the clipped and unclipped value estimates
+                let values: Tensor<B, 1> = values.squeeze(1);
+                let value_errors = (values.clone() - returns.clone()).powf_scalar(2.0);
+                let value_errors = match self.clip_range_vf {
+                    Some(clip_range_vf) => {
+                        let clipped_values = old_values.clone()
+                            + (values - old_values).clamp(-clip_range_vf, clip_range_vf);
+                        let clipped_errors = (clipped_values - returns).powf_scalar(2.0);
+                        value_errors.clone().mask_where(
+                            clipped_errors.clone().greater(value_errors),
+                            clipped_errors,
+                        )
+                    }
+                    None => value_errors,
+                };
+                let value_loss = value_errors.mean();
+
+                let entropy = dist.entropy().mean();
+                let loss = policy_loss.clone() + value_loss.clone() * self.value_coef
+                    - entropy.clone() * self.entropy_coef;
+
+                let grads = GradientsParams::from_grads(loss.backward(), &model);
+                model = self.optimizer.step(lr, model, grads);
+
+                self.report.record("approx_kl", approx_kl as f64);
+                for (key, metric) in [
+                    ("policy_loss", policy_loss),
+                    ("value_loss", value_loss),
+                    ("entropy", entropy),
+                    ("clip_fraction", clip_fraction),
+                ] {
+                    let value = metric.into_data().convert::<f32>().value[0];
+                    self.report.record(key, value as f64);
+                }
+            }
+        }
+
+        self.model = Some(model);
+    }
+}
+
+impl<B, M, E, const D: usize, LR> Agent<E> for PPOAgent<B, M, E, D, LR>
+where
+    B: AutodiffBackend<FloatElem = f32, IntElem = i32>,
+    M: ActorCriticModel<B, D>,
+    E: MaskedActionSpace,
+    LR: Decay,
+    Vec<E::State>: ToTensor<B, D, Float>,
+    Vec<E::Action>: ToTensor<B, 2, Int>,
+    E::Action: From<usize> + Into<usize>,
+{
+    /// Sample a legal action from the policy
+    fn act(&mut self, env: &mut E, state: E::State) -> E::Action {
+        let mask = legal_mask(env);
+        let action = self.policy(state, mask.clone()).sample(&mut self.rng)[0];
+        self.rollout.masks.push(mask);
+        E::Action::from(action)
+    }
+
+    /// Choose the most likely legal action under the policy
+    fn act_greedy(&mut self, env: &mut E, state: E::State) -> E::Action {
+        E::Action::from(self.policy(state, legal_mask(env)).mode()[0])
+    }
+
+    /// Add the transition to the rollout, and learn once the rollout is full
+    fn observe(&mut self, _env: &E, exp: Exp<E>) {
+        self.rollout.push(exp);
+        self.total_steps += 1;
+
+        if self.rollout.len() >= self.n_steps {
+            self.learn();
+        }
+    }
+
+    /// Mark the last transition as the end of an episode, so advantages are not carried across it
+    fn end_episode(&mut self) {
+        self.rollout.end_episode();
+    }
+
+    fn report(&mut self) -> Option<&mut Report> {
+        Some(&mut self.report)
+    }
+}
+
+impl<B, M, E, const D: usize, LR> Debug for PPOAgent<B, M, E, D, LR>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+    E: Environment,
+    LR: Decay,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PPOAgent")
+            .field("n_steps", &self.n_steps)
+            .field("batch_size", &self.batch_size)
+            .field("n_epochs", &self.n_epochs)
+            .field("gamma", &self.gamma)
+            .field("gae_lambda", &self.gae_lambda)
+            .field("clip_range", &self.clip_range)
+            .field("clip_range_vf", &self.clip_range_vf)
+            .field("target_kl", &self.target_kl)
+            .field("total_steps", &self.total_steps)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algo::tests::{ActorCritic, Signal, DEVICE};
+
+    use super::*;
+
+    #[test]
+    fn ppo_learns_signal() {
+        let model = ActorCritic::new(2, 2);
+        let config = PPOAgentConfig {
+            n_steps: 64,
+            batch_size: 16,
+            n_epochs: 4,
+            lr_schedule: decay::Constant::new(1e-2),
+            seed: Some(0),
+            ..Default::default()
+        };
+        let mut agent = PPOAgent::new(model, config, &DEVICE);
+        let mut env = Signal::new(0);
+
+        for _ in 0..2000 {
+            agent.go(&mut env);
+        }
+
+        let report = agent.report().unwrap().take();
+        assert!(
+            report["policy_loss"].count() > 0,
+            "Agent learned from full rollouts"
+        );
+        assert_eq!(
+            agent.evaluate(&mut env, 20).mean_reward,
+            1.0,
+            "Agent follows the signal"
+        );
+    }
+}
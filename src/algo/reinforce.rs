@@ -0,0 +1,426 @@
+use std::fmt::Debug;
+
+use burn::{
+    module::AutodiffModule, optim::GradientsParams, prelude::*, tensor::backend::AutodiffBackend,
+};
+use rand::rngs::StdRng;
+
+use crate::{
+    algo::policy_gradient::{discounted_returns, normalize, Categorical, PolicyModel, ValueModel},
+    decay::{self, Decay},
+    env::{Environment, MaskedActionSpace},
+    memory::Exp,
+    nn::{legal_mask, GradientClipping, ModelOptimizer, OptimizerConfig},
+    traits::{Agent, ToTensor},
+    util::rng_from_seed,
+};
+
+/// Configuration for the [`ReinforceAgent`]
+///
+/// ### Generics
+/// - `LR` - The learning rate schedule
+#[derive(Debug, Clone)]
+pub struct ReinforceAgentConfig<LR = decay::Constant> {
+    /// The discount factor
+    ///
+    /// **Default:** `0.99`
+    pub gamma: f32,
+    /// The weight of the entropy bonus, which encourages exploration by penalizing overly confident policies
+    ///
+    /// **Default:** `0.01`
+    pub entropy_coef: f32,
+    /// Standardize the advantages of each episode to zero mean and unit variance before the policy update
+    ///
+    /// **Default:** `true`
+    pub normalize_advantages: bool,
+    /// The optimizer to train the policy and baseline networks with
+    ///
+    /// **Default:** [`OptimizerConfig::adam`]
+    pub optimizer: OptimizerConfig,
+    /// The gradient clipping applied before each optimizer step, or `None` to leave gradients unclipped
+    ///
+    /// **Default:** `None`
+    pub grad_clipping: Option<GradientClipping>,
+    /// The learning rate schedule for the optimizers, evaluated at the number of elapsed episodes
+    ///
+    /// **Default:** [`Constant`](decay::Constant) learning rate `1e-3`
+    pub lr_schedule: LR,
+    /// Seed for the agent's random number generator, which drives action sampling, or `None` to seed from system
+    /// entropy
+    ///
+    /// **Default:** `None`
+    pub seed: Option<u64>,
+}
+
+impl Default for ReinforceAgentConfig {
+    fn default() -> Self {
+        Self {
+            gamma: 0.99,
+            entropy_coef: 0.01,
+            normalize_advantages: true,
+            optimizer: OptimizerConfig::adam(),
+            grad_clipping: None,
+            lr_schedule: decay::Constant::new(1e-3),
+            seed: None,
+        }
+    }
+}
+
+/// The transitions of the current episode
+struct Trajectory<E: Environment> {
+    states: Vec<E::State>,
+    actions: Vec<E::Action>,
+    rewards: Vec<f32>,
+    masks: Vec<Option<Vec<bool>>>,
+    final_state: Option<E::State>,
+}
+
+impl<E: Environment> Default for Trajectory<E> {
+    fn default() -> Self {
+        Self {
+            states: Vec::new(),
+            actions: Vec::new(),
+            rewards: Vec::new(),
+            masks: Vec::new(),
+            final_state: None,
+        }
+    }
+}
+
+impl<E: Environment> Clone for Trajectory<E> {
+    fn clone(&self) -> Self {
+        Self {
+            states: self.states.clone(),
+            actions: self.actions.clone(),
+            rewards: self.rewards.clone(),
+            masks: self.masks.clone(),
+            final_state: self.final_state.clone(),
+        }
+    }
+}
+
+/// A REINFORCE agent, which learns a stochastic policy by Monte Carlo policy gradient
+/// ([Williams 1992](https://link.springer.com/article/10.1007/BF00992696))
+///
+/// At the end of each episode, the policy is updated to make each action more likely in proportion to the
+/// discounted return that followed it. With a baseline network, the baseline's estimate of each state's value is
+/// subtracted from the return, which reduces the variance of the update without biasing it, and truncated episodes
+/// bootstrap from the baseline's estimate of the final state.
+///
+/// ### Generics
+/// - `B` - A burn backend
+/// - `P` - The [`PolicyModel`] producing action logits
+/// - `V` - The [`ValueModel`] used as a baseline
+/// - `E` - The [`Environment`] in which the agent will learn
+///     - The environment's action space must be discrete. Actions that are illegal according to its
+///       [`MaskedActionSpace`] are never chosen.
+/// - `D` - The dimension of the input
+/// - `LR` - The learning rate schedule
+///
+/// Without a baseline, `V` must still be named, e.g. `ReinforceAgent::new(policy, None::<Value<B>>, config, device)`.
+#[derive(Clone)]
+pub struct ReinforceAgent<B, P, V, E, const D: usize, LR = decay::Constant>
+where
+    B: AutodiffBackend,
+    P: AutodiffModule<B>,
+    V: AutodiffModule<B>,
+    E: Environment,
+    LR: Decay,
+{
+    policy_net: Option<P>,
+    policy_optimizer: ModelOptimizer<P, B>,
+    baseline_net: Option<V>,
+    baseline_optimizer: ModelOptimizer<V, B>,
+    device: &'static B::Device,
+    trajectory: Trajectory<E>,
+    gamma: f32,
+    entropy_coef: f32,
+    normalize_advantages: bool,
+    lr_schedule: LR,
+    episodes_elapsed: usize,
+    rng: StdRng,
+}
+
+impl<B, P, V, E, const D: usize, LR> ReinforceAgent<B, P, V, E, D, LR>
+where
+    B: AutodiffBackend<FloatElem = f32, IntElem = i32>,
+    P: PolicyModel<B, D>,
+    V: ValueModel<B, D>,
+    E: MaskedActionSpace,
+    LR: Decay,
+    Vec<E::State>: ToTensor<B, D, Float>,
+    Vec<E::Action>: ToTensor<B, 2, Int>,
+    E::Action: From<usize> + Into<usize>,
+{
+    /// Initialize a new `ReinforceAgent`
+    ///
+    /// ### Arguments
+    /// - `policy` A [`PolicyModel`] to be trained
+    /// - `baseline` An optional [`ValueModel`] to be trained as a baseline
+    /// - `config` A [`ReinforceAgentConfig`] containing hyperparameters for the agent
+    /// - `device` A static reference to the device used for the models
+    pub fn new(
+        policy: P,
+        baseline: Option<V>,
+        config: ReinforceAgentConfig<LR>,
+        device: &'static B::Device,
+    ) -> Self {
+        Self {
+            policy_net: Some(policy),
+            policy_optimizer: ModelOptimizer::new(&config.optimizer, config.grad_clipping),
+            baseline_net: baseline,
+            baseline_optimizer: ModelOptimizer::new(&config.optimizer, config.grad_clipping),
+            device,
+            trajectory: Trajectory::default(),
+            gamma: config.gamma,
+            entropy_coef: config.entropy_coef,
+            normalize_advantages: config.normalize_advantages,
+            lr_schedule: config.lr_schedule,
+            episodes_elapsed: 0,
+            rng: rng_from_seed(config.seed),
+        }
+    }
+
+    /// Compute the action distribution of the policy in a single state
+    fn policy(&self, state: E::State, mask: Option<Vec<bool>>) -> Categorical<B> {
+        let logits = self
+            .policy_net
+            .as_ref()
+            .unwrap()
+            .forward(vec![state].to_tensor(self.device));
+        Categorical::new(logits, &[mask], self.device)
+    }
+
+    /// Update the policy, and the baseline if there is one, from the trajectory of the finished episode
+    fn learn(&mut self) {
+        let Trajectory {
+            states,
+            actions,
+            rewards,
+            masks,
+            final_state,
+        } = std::mem::take(&mut self.trajectory);
+        if states.is_empty() {
+            return;
+        }
+        let lr = f64::from(self.lr_schedule.evaluate(self.episodes_elapsed as f32));
+
+        // Truncated episodes bootstrap from the baseline's estimate of the final state, if there is a baseline
+        let bootstrap = match (final_state, &self.baseline_net) {
+            (Some(state), Some(baseline_net)) => {
+                baseline_net
+                    .forward(vec![state].to_tensor(self.device))
+                    .into_data()
+                    .convert::<f32>()
+                    .value[0]
+            }
+            _ => 0.0,
+        };
+        let returns: Tensor<B, 1> =
+            discounted_returns(&rewards, self.gamma, bootstrap).to_tensor(self.device);
+        let states = states.to_tensor(self.device);
+
+        // Fit the baseline to the returns and subtract its estimates to get the advantages
+        let advantages = match self.baseline_net.take() {
+            Some(baseline_net) => {
+                let values: Tensor<B, 1> = baseline_net.forward(states.clone()).squeeze(1);
+                let advantages = returns.clone() - values.clone().detach();
+                let loss = (values - returns).powf_scalar(2.0).mean();
+                let grads = GradientsParams::from_grads(loss.backward(), &baseline_net);
+                self.baseline_net = Some(self.baseline_optimizer.step(lr, baseline_net, grads));
+                advantages
+            }
+            None => returns,
+        };
+        let advantages = if self.normalize_advantages && rewards.len() > 1 {
+            normalize(advantages)
+        } else {
+            advantages
+        };
+
+        // Policy gradient loss with an entropy bonus
+        let policy_net = self.policy_net.take().unwrap();
+        let dist = Categorical::new(policy_net.forward(states), &masks, self.device);
+        let log_probs = dist.log_prob(actions.to_tensor(self.device));
+        let loss = (log_probs * advantages.detach()).mean().neg()
+            - dist.entropy().mean() * self.entropy_coef;
+
+        let grads = GradientsParams::from_grads(loss.backward(), &policy_net);
+        self.policy_net = Some(self.policy_optimizer.step(lr, policy_net, grads));
+    }
+}
+
+impl<B, P, V, E, const D: usize, LR> Agent<E> for ReinforceAgent<B, P, V, E, D, LR>
+where
+    B: AutodiffBackend<FloatElem = f32, IntElem = i32>,
+    P: PolicyModel<B, D>,
+    V: ValueModel<B, D>,
+    E: MaskedActionSpace,
+    LR: Decay,
+    Vec<E::State>: ToTensor<B, D, Float>,
+    Vec<E::Action>: ToTensor<B, 2, Int>,
+    E::Action: From<usize> + Into<usize>,
+{
+    /// Sample a legal action from the policy
+    fn act(&mut self, env: &mut E, state: E::State) -> E::Action {
+        let mask = legal_mask(env);
+        let action = self.policy(state, mask.clone()).sample(&mut self.rng)[0];
+        self.trajectory.masks.push(mask);
+        E::Action::from(action)
+    }
+
+    /// Choose the most likely legal action under the policy
+    fn act_greedy(&mut self, env: &mut E, state: E::State) -> E::Action {
+        E::Action::from(self.policy(state, legal_mask(env)).mode()[0])
+    }
+
+    /// Record the transition in the episode's trajectory
+    fn observe(&mut self, _env: &E, exp: Exp<E>) {
+        self.trajectory.states.push(exp.state);
+        self.trajectory.actions.push(exp.action);
+        self.trajectory.rewards.push(exp.reward);
+        self.trajectory.final_state = exp.next_state;
+    }
+
+    /// Learn from the finished episode
+    fn end_episode(&mut self) {
+        self.learn();
+        self.episodes_elapsed += 1;
+    }
+}
+
+impl<B, P, V, E, const D: usize, LR> Debug for ReinforceAgent<B, P, V, E, D, LR>
+where
+    B: AutodiffBackend,
+    P: AutodiffModule<B>,
+    V: AutodiffModule<B>,
+    E: Environment,
+    LR: Decay,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReinforceAgent")
+            .field("gamma", &self.gamma)
+            .field("entropy_coef", &self.entropy_coef)
+            .field("normalize_advantages", &self.normalize_advantages)
+            .field("baseline", &self.baseline_net.is_some())
+            .field("episodes_elapsed", &self.episodes_elapsed)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn::{
+        module::Param,
+        nn::{Linear, LinearConfig},
+    };
+
+    use crate::algo::tests::{Signal, B, DEVICE};
+
+    use super::*;
+
+    #[derive(Module, Debug)]
+    struct Net<B: Backend> {
+        fc: Linear<B>,
+    }
+
+    impl PolicyModel<B, 2> for Net<B> {
+        fn forward(&self, input: Tensor<B, 2>) -> Tensor<B, 2> {
+            self.fc.forward(input)
+        }
+    }
+
+    impl ValueModel<B, 2> for Net<B> {
+        fn forward(&self, input: Tensor<B, 2>) -> Tensor<B, 2> {
+            self.fc.forward(input)
+        }
+    }
+
+    type Reinforce = ReinforceAgent<B, Net<B>, Net<B>, Signal, 2>;
+
+    fn net(outputs: usize) -> Net<B> {
+        Net {
+            fc: LinearConfig::new(2, outputs).init(&DEVICE),
+        }
+    }
+
+    #[test]
+    fn reinforce_learns_signal() {
+        for baseline in [None, Some(net(1))] {
+            let config = ReinforceAgentConfig {
+                lr_schedule: decay::Constant::new(1e-2),
+                seed: Some(0),
+                ..Default::default()
+            };
+            let with_baseline = baseline.is_some();
+            let mut agent = Reinforce::new(net(2), baseline, config, &DEVICE);
+            let mut env = Signal::new(0);
+
+            for _ in 0..1000 {
+                agent.go(&mut env);
+            }
+
+            assert_eq!(
+                agent.evaluate(&mut env, 20).mean_reward,
+                1.0,
+                "Agent follows the signal with baseline: {with_baseline}"
+            );
+        }
+    }
+
+    #[test]
+    fn reinforce_truncated_episode_bootstraps() {
+        // A baseline that values every state at 5.0, trained by plain gradient descent
+        let baseline = || Net {
+            fc: Linear {
+                weight: Param::from(Tensor::zeros([2, 1], &DEVICE)),
+                bias: Some(Param::from(Tensor::from_floats([5.0], &DEVICE))),
+            },
+        };
+        let config = ReinforceAgentConfig {
+            gamma: 0.5,
+            normalize_advantages: false,
+            optimizer: OptimizerConfig::Sgd {
+                momentum: 0.0,
+                dampening: 0.0,
+                nesterov: false,
+            },
+            lr_schedule: decay::Constant::new(0.1),
+            seed: Some(0),
+            ..Default::default()
+        };
+
+        let baseline_after = |truncated: bool| {
+            let mut agent = Reinforce::new(net(2), Some(baseline()), config.clone(), &DEVICE);
+            let mut env = Signal::new(0);
+            let state = [0.0, 0.0];
+            let action = agent.act(&mut env, state);
+            agent.observe(
+                &env,
+                Exp {
+                    state,
+                    action,
+                    reward: 0.0,
+                    next_state: truncated.then_some(state),
+                    next_mask: None,
+                    steps: 1,
+                },
+            );
+            agent.end_episode();
+
+            let baseline_net = agent.baseline_net.unwrap();
+            baseline_net.fc.bias.unwrap().val().into_scalar()
+        };
+
+        // The return is 0.5 * 5.0 when bootstrapped and 0.0 otherwise, and the squared error gradient on the bias is
+        // 2 * (5.0 - return)
+        assert!(
+            (baseline_after(true) - 4.5).abs() < 1e-6,
+            "Truncated episode bootstraps from the baseline"
+        );
+        assert!(
+            (baseline_after(false) - 4.0).abs() < 1e-6,
+            "Terminated episode does not bootstrap"
+        );
+    }
+}
@@ -0,0 +1,570 @@
+use std::fmt::Debug;
+
+use burn::{
+    module::{AutodiffModule, Param},
+    optim::GradientsParams,
+    prelude::*,
+    tensor::backend::AutodiffBackend,
+};
+use rand::{rngs::StdRng, Rng};
+
+use crate::{
+    algo::continuous::{minimum, ActionBounds, CriticModel, GaussianPolicyModel, SquashedGaussian},
+    decay::{self, Decay},
+    env::{Aggregation, Environment, Report, Spaces},
+    memory::{bellman_targets, Exp, ReplayMemory},
+    nn::{soft_update, GradientClipping, ModelOptimizer, OptimizerConfig},
+    traits::{Agent, ToTensor},
+    util::rng_from_seed,
+};
+
+/// The entropy coefficient α of a [`SACAgent`], which weighs the entropy bonus against the reward
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntropyCoef {
+    /// A constant coefficient
+    Fixed(f32),
+    /// A coefficient tuned during training so that the entropy of the policy tracks a target entropy
+    Auto {
+        /// The coefficient before any tuning
+        initial: f32,
+        /// The target entropy, or `None` for the negated number of action dimensions
+        target_entropy: Option<f32>,
+    },
+}
+
+impl Default for EntropyCoef {
+    fn default() -> Self {
+        Self::Auto {
+            initial: 1.0,
+            target_entropy: None,
+        }
+    }
+}
+
+/// Configuration for the [`SACAgent`]
+///
+/// ### Generics
+/// - `LR` - The learning rate schedule
+#[derive(Debug, Clone)]
+pub struct SACAgentConfig<LR = decay::Constant> {
+    /// The maximum number of experiences held in replay memory
+    ///
+    /// **Default:** `100000`
+    pub memory_capacity: usize,
+    /// The number of experiences sampled from replay memory for each gradient step
+    ///
+    /// **Default:** `256`
+    pub memory_batch_size: usize,
+    /// The discount factor
+    ///
+    /// **Default:** `0.99`
+    pub gamma: f32,
+    /// The Polyak averaging coefficient τ of the target critics (see [`soft_update`])
+    ///
+    /// **Default:** `0.005`
+    pub tau: f32,
+    /// The number of gradient steps between updates of the target critics
+    ///
+    /// **Default:** `1`
+    pub target_update_interval: usize,
+    /// The number of steps taken with uniformly random actions before learning begins
+    ///
+    /// **Default:** `100`
+    pub learning_starts: usize,
+    /// The number of steps between training rounds
+    ///
+    /// **Default:** `1`
+    pub train_freq: usize,
+    /// The number of gradient steps in each training round
+    ///
+    /// **Default:** `1`
+    pub gradient_steps: usize,
+    /// The entropy coefficient, fixed or automatically tuned
+    ///
+    /// **Default:** [`EntropyCoef::Auto`] with initial coefficient `1.0` and the default target entropy
+    pub entropy_coef: EntropyCoef,
+    /// The optimizer to train the actor, critics, and entropy coefficient with
+    ///
+    /// **Default:** [`OptimizerConfig::adam`]
+    pub optimizer: OptimizerConfig,
+    /// The gradient clipping applied before each optimizer step, or `None` to leave gradients unclipped
+    ///
+    /// **Default:** `None`
+    pub grad_clipping: Option<GradientClipping>,
+    /// The learning rate schedule for the optimizers, evaluated at the agent's total number of steps
+    ///
+    /// **Default:** [`Constant`](decay::Constant) learning rate `3e-4`
+    pub lr_schedule: LR,
+    /// Seed for the agent's random number generator, which drives action sampling and replay memory sampling, or
+    /// `None` to seed from system entropy
+    ///
+    /// **Default:** `None`
+    pub seed: Option<u64>,
+}
+
+impl Default for SACAgentConfig {
+    fn default() -> Self {
+        Self {
+            memory_capacity: 100000,
+            memory_batch_size: 256,
+            gamma: 0.99,
+            tau: 0.005,
+            target_update_interval: 1,
+            learning_starts: 100,
+            train_freq: 1,
+            gradient_steps: 1,
+            entropy_coef: EntropyCoef::default(),
+            optimizer: OptimizerConfig::adam(),
+            grad_clipping: None,
+            lr_schedule: decay::Constant::new(3e-4),
+            seed: None,
+        }
+    }
+}
+
+/// The learnable log entropy coefficient
+#[derive(Module, Debug)]
+struct Temperature<B: Backend> {
+    log_alpha: Param<Tensor<B, 1>>,
+}
+
+/// A Soft Actor-Critic agent ([Haarnoja et al. 2018](https://arxiv.org/abs/1812.05905)) for continuous action spaces
+///
+/// The agent learns a stochastic actor that maximizes both reward and the entropy of its actions, which are sampled
+/// from a [`GaussianPolicyModel`] and squashed into the bounds of the environment's action space. Twin critics are
+/// trained from a [`ReplayMemory`] toward the smaller of two target critic estimates to counter overestimation, and
+/// the target critics follow the critics by Polyak averaging.
+///
+/// The environment's [action space](Spaces::action_space) must be a [`Space::Box`](crate::space::Space::Box) with
+/// finite bounds, and its actions must convert to and from vectors of the same length, e.g. `[f32; N]`.
+///
+/// The agent's [report](Agent::report) records the mean `actor_loss`, `critic_loss`, and `entropy_coef` of each
+/// episode's gradient steps.
+///
+/// ### Generics
+/// - `B` - A burn backend
+/// - `A` - The [`GaussianPolicyModel`] used as the actor
+/// - `C` - The [`CriticModel`] used for each of the twin critics
+/// - `E` - The [`Environment`] in which the agent will learn
+/// - `D` - The dimension of the input
+/// - `LR` - The learning rate schedule
+#[derive(Clone)]
+pub struct SACAgent<B, A, C, E, const D: usize, LR = decay::Constant>
+where
+    B: AutodiffBackend,
+    A: AutodiffModule<B>,
+    C: AutodiffModule<B>,
+    E: Environment,
+    LR: Decay,
+{
+    actor: Option<A>,
+    actor_optimizer: ModelOptimizer<A, B>,
+    critics: Vec<C>,
+    target_critics: Vec<C>,
+    critic_optimizers: Vec<ModelOptimizer<C, B>>,
+    temperature: Option<Temperature<B>>,
+    temperature_optimizer: ModelOptimizer<Temperature<B>, B>,
+    device: &'static B::Device,
+    memory: ReplayMemory<E>,
+    bounds: Option<ActionBounds>,
+    entropy_coef: EntropyCoef,
+    gamma: f32,
+    tau: f32,
+    target_update_interval: usize,
+    learning_starts: usize,
+    train_freq: usize,
+    gradient_steps: usize,
+    lr_schedule: LR,
+    report: Report,
+    total_steps: usize,
+    total_gradient_steps: usize,
+    rng: StdRng,
+}
+
+impl<B, A, C, E, const D: usize, LR> SACAgent<B, A, C, E, D, LR>
+where
+    B: AutodiffBackend<FloatElem = f32>,
+    A: GaussianPolicyModel<B, D>,
+    C: CriticModel<B, D>,
+    E: Spaces,
+    LR: Decay,
+    Vec<E::State>: ToTensor<B, D, Float>,
+    E::Action: Into<Vec<f32>> + TryFrom<Vec<f32>>,
+{
+    /// Initialize a new `SACAgent`
+    ///
+    /// ### Arguments
+    /// - `actor` A [`GaussianPolicyModel`] to be trained
+    /// - `critics` Two independently initialized [`CriticModel`]s, which are also cloned as the target critics
+    /// - `config` A [`SACAgentConfig`] containing hyperparameters for the agent
+    /// - `device` A static reference to the device used for the models
+    ///
+    /// **Panics** if `target_update_interval` or `train_freq` is zero
+    pub fn new(
+        actor: A,
+        critics: [C; 2],
+        config: SACAgentConfig<LR>,
+        device: &'static B::Device,
+    ) -> Self {
+        assert!(
+            config.target_update_interval > 0,
+            "target_update_interval must be at least 1"
+        );
+        assert!(config.train_freq > 0, "train_freq must be at least 1");
+        let mut rng = rng_from_seed(config.seed);
+        let mut memory = ReplayMemory::new(config.memory_capacity, config.memory_batch_size);
+        memory.seed(rng.gen());
+        let temperature = match config.entropy_coef {
+            EntropyCoef::Auto { initial, .. } => Some(Temperature {
+                log_alpha: Param::from(Tensor::from_floats([initial.ln()], device)),
+            }),
+            EntropyCoef::Fixed(_) => None,
+        };
+
+        Self {
+            actor: Some(actor),
+            actor_optimizer: ModelOptimizer::new(&config.optimizer, config.grad_clipping),
+            target_critics: critics.to_vec(),
+            critics: Vec::from(critics),
+            critic_optimizers: (0..2)
+                .map(|_| ModelOptimizer::new(&config.optimizer, config.grad_clipping))
+                .collect(),
+            temperature,
+            temperature_optimizer: ModelOptimizer::new(&config.optimizer, config.grad_clipping),
+            device,
+            memory,
+            bounds: None,
+            entropy_coef: config.entropy_coef,
+            gamma: config.gamma,
+            tau: config.tau,
+            target_update_interval: config.target_update_interval,
+            learning_starts: config.learning_starts,
+            train_freq: config.train_freq,
+            gradient_steps: config.gradient_steps,
+            lr_schedule: config.lr_schedule,
+            report: Report::default()
+                .with_metric("actor_loss", Aggregation::Mean)
+                .with_metric("critic_loss", Aggregation::Mean)
+                .with_metric("entropy_coef", Aggregation::Mean),
+            total_steps: 0,
+            total_gradient_steps: 0,
+            rng,
+        }
+    }
+
+    /// The current entropy coefficient
+    fn alpha(&self) -> f32 {
+        match (&self.temperature, self.entropy_coef) {
+            (Some(temperature), _) => {
+                temperature
+                    .log_alpha
+                    .val()
+                    .exp()
+                    .into_data()
+                    .convert::<f32>()
+                    .value[0]
+            }
+            (None, EntropyCoef::Fixed(alpha)) => alpha,
+            (None, EntropyCoef::Auto { initial, .. }) => initial,
+        }
+    }
+
+    /// Compute the action distribution of the actor in each state
+    fn policy(&self, states: Tensor<B, D>) -> SquashedGaussian<B> {
+        let (mean, log_std) = self.actor.as_ref().unwrap().forward(states);
+        SquashedGaussian::new(mean, log_std)
+    }
+
+    /// Take a gradient step on the critics, the actor, and the entropy coefficient
+    ///
+    /// **Returns** `false` if there are not yet enough experiences in memory
+    fn learn(&mut self) -> bool {
+        let Some(batch) = self.memory.sample_zipped() else {
+            return false;
+        };
+        let bounds = self
+            .bounds
+            .clone()
+            .expect("Action bounds are known once the agent has acted");
+        let lr = self.lr_schedule.evaluate(self.total_steps as f32).into();
+        let alpha = self.alpha();
+
+        // Compute the soft value of each next state from the smaller of the target critics' estimates
+        let actor = self.actor.as_ref().unwrap();
+        let target_critics = &self.target_critics;
+        let rng = &mut self.rng;
+        let targets = bellman_targets(
+            &batch,
+            self.gamma,
+            self.device,
+            |next_states: Tensor<B, D>, _| {
+                let (mean, log_std) = actor.forward(next_states.clone());
+                let (next_actions, next_log_probs) =
+                    SquashedGaussian::new(mean, log_std).rsample(rng);
+                minimum(
+                    target_critics[0].forward(next_states.clone(), next_actions.clone()),
+                    target_critics[1].forward(next_states, next_actions),
+                ) - next_log_probs.unsqueeze_dim(1) * alpha
+            },
+        )
+        .detach();
+
+        // Tensor conversions
+        let states: Tensor<B, D> = batch.states.to_tensor(self.device);
+        let actions = bounds.normalize(batch.actions, self.device);
+
+        // Fit both critics to the targets
+        let critics = std::mem::take(&mut self.critics);
+        let critic_loss = critics
+            .iter()
+            .map(|critic| {
+                (critic.forward(states.clone(), actions.clone()) - targets.clone())
+                    .powf_scalar(2.0)
+                    .mean()
+            })
+            .reduce(|a, b| a + b)
+            .unwrap();
+        let mut grads = critic_loss.backward();
+        self.critics = critics
+            .into_iter()
+            .zip(&mut self.critic_optimizers)
+            .map(|(critic, optimizer)| {
+                let critic_grads = GradientsParams::from_module(&mut grads, &critic);
+                optimizer.step(lr, critic, critic_grads)
+            })
+            .collect();
+
+        // Update the actor to maximize the soft value of its actions
+        let (policy_actions, log_probs) = self.policy(states.clone()).rsample(&mut self.rng);
+        let q_values: Tensor<B, 1> = minimum(
+            self.critics[0].forward(states.clone(), policy_actions.clone()),
+            self.critics[1].forward(states, policy_actions),
+        )
+        .squeeze(1);
+        let actor_loss = (log_probs.clone() * alpha - q_values).mean();
+        let actor = self.actor.take().unwrap();
+        let actor_grads = GradientsParams::from_grads(actor_loss.backward(), &actor);
+        self.actor = Some(self.actor_optimizer.step(lr, actor, actor_grads));
+
+        // Tune the entropy coefficient toward the target entropy
+        if let Some(temperature) = self.temperature.take() {
+            let target_entropy = match self.entropy_coef {
+                EntropyCoef::Auto {
+                    target_entropy: Some(target_entropy),
+                    ..
+                } => target_entropy,
+                _ => -(bounds.dim() as f32),
+            };
+            let temperature_loss = (temperature.log_alpha.val()
+                * (log_probs.detach() + target_entropy))
+                .mean()
+                .neg();
+            let temperature_grads =
+                GradientsParams::from_grads(temperature_loss.backward(), &temperature);
+            self.temperature = Some(self.temperature_optimizer.step(
+                lr,
+                temperature,
+                temperature_grads,
+            ));
+        }
+
+        // Update the target critics
+        self.total_gradient_steps += 1;
+        if self.total_gradient_steps % self.target_update_interval == 0 {
+            self.target_critics = std::mem::take(&mut self.target_critics)
+                .into_iter()
+                .zip(&self.critics)
+                .map(|(target, critic)| soft_update(target, critic, self.tau))
+                .collect();
+        }
+
+        for (key, value) in [
+            (
+                "actor_loss",
+                actor_loss.into_data().convert::<f32>().value[0],
+            ),
+            (
+                "critic_loss",
+                critic_loss.into_data().convert::<f32>().value[0],
+            ),
+            ("entropy_coef", alpha),
+        ] {
+            self.report.record(key, value as f64);
+        }
+
+        true
+    }
+}
+
+impl<B, A, C, E, const D: usize, LR> Agent<E> for SACAgent<B, A, C, E, D, LR>
+where
+    B: AutodiffBackend<FloatElem = f32>,
+    A: GaussianPolicyModel<B, D>,
+    C: CriticModel<B, D>,
+    E: Spaces,
+    LR: Decay,
+    Vec<E::State>: ToTensor<B, D, Float>,
+    E::Action: Into<Vec<f32>> + TryFrom<Vec<f32>>,
+{
+    /// Sample an action from the actor, or a uniformly random action before learning starts
+    fn act(&mut self, env: &mut E, state: E::State) -> E::Action {
+        self.bounds
+            .get_or_insert_with(|| ActionBounds::new(&env.action_space()));
+        if self.total_steps < self.learning_starts {
+            return env.random_action();
+        }
+
+        let (action, _) = self
+            .policy(vec![state].to_tensor(self.device))
+            .rsample(&mut self.rng);
+        let action = action.into_data().convert::<f32>().value;
+        self.bounds.as_ref().unwrap().action(&action)
+    }
+
+    /// Choose the squashed mean action of the actor
+    fn act_greedy(&mut self, env: &mut E, state: E::State) -> E::Action {
+        let bounds = self
+            .bounds
+            .get_or_insert_with(|| ActionBounds::new(&env.action_space()))
+            .clone();
+        let action = self
+            .policy(vec![state].to_tensor(self.device))
+            .mode()
+            .into_data()
+            .convert::<f32>()
+            .value;
+        bounds.action(&action)
+    }
+
+    /// Store the experience in replay memory and train on schedule
+    fn observe(&mut self, _env: &E, exp: Exp<E>) {
+        self.memory.push(exp);
+        self.total_steps += 1;
+
+        if self.total_steps > self.learning_starts && self.total_steps % self.train_freq == 0 {
+            for _ in 0..self.gradient_steps {
+                if !self.learn() {
+                    break;
+                }
+            }
+        }
+    }
+
+    fn report(&mut self) -> Option<&mut Report> {
+        Some(&mut self.report)
+    }
+}
+
+impl<B, A, C, E, const D: usize, LR> Debug for SACAgent<B, A, C, E, D, LR>
+where
+    B: AutodiffBackend,
+    A: AutodiffModule<B>,
+    C: AutodiffModule<B>,
+    E: Environment,
+    LR: Decay,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SACAgent")
+            .field("entropy_coef", &self.entropy_coef)
+            .field("gamma", &self.gamma)
+            .field("tau", &self.tau)
+            .field("learning_starts", &self.learning_starts)
+            .field("total_steps", &self.total_steps)
+            .field("total_gradient_steps", &self.total_gradient_steps)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "gym"))]
+mod tests {
+    use burn::nn::{Linear, LinearConfig};
+
+    use crate::{
+        algo::tests::{Critic, B, DEVICE},
+        gym::Pendulum,
+    };
+
+    use super::*;
+
+    #[derive(Module, Debug)]
+    struct Actor<B: Backend> {
+        mean: Linear<B>,
+        log_std: Linear<B>,
+    }
+
+    impl GaussianPolicyModel<B, 2> for Actor<B> {
+        fn forward(&self, input: Tensor<B, 2>) -> (Tensor<B, 2>, Tensor<B, 2>) {
+            (
+                self.mean.forward(input.clone()),
+                self.log_std.forward(input),
+            )
+        }
+    }
+
+    type TestAgent = SACAgent<B, Actor<B>, Critic<B>, Pendulum, 2>;
+
+    /// Train for one episode of 20 steps, learning from the 9th step on
+    fn train(target_entropy: f32) -> TestAgent {
+        let actor = Actor {
+            mean: LinearConfig::new(3, 1).init(&DEVICE),
+            log_std: LinearConfig::new(3, 1).init(&DEVICE),
+        };
+        let config = SACAgentConfig {
+            memory_capacity: 64,
+            memory_batch_size: 8,
+            learning_starts: 8,
+            entropy_coef: EntropyCoef::Auto {
+                initial: 1.0,
+                target_entropy: Some(target_entropy),
+            },
+            lr_schedule: decay::Constant::new(1e-2),
+            seed: Some(0),
+            ..Default::default()
+        };
+        let mut agent = TestAgent::new(actor, [Critic::new(4), Critic::new(4)], config, &DEVICE);
+        let critics = agent
+            .critics
+            .iter()
+            .map(Critic::weights)
+            .collect::<Vec<_>>();
+
+        let mut env = Pendulum::new(20);
+        env.seed(0);
+        agent.go(&mut env);
+
+        for (i, initial) in critics.iter().enumerate() {
+            let critic = agent.critics[i].weights();
+            let target = agent.target_critics[i].weights();
+            assert_ne!(&critic, initial, "Critic {i} learned");
+            assert_ne!(&target, initial, "Target critic {i} follows its critic");
+            assert_ne!(target, critic, "Target critic {i} lags behind its critic");
+        }
+
+        agent
+    }
+
+    #[test]
+    fn sac_updates_critics_and_entropy_coef() {
+        let mut agent = train(-1.0);
+        let report = agent.report().unwrap().take();
+        assert_eq!(
+            report["actor_loss"].count(),
+            12,
+            "Report records the actor loss of each gradient step"
+        );
+        assert_eq!(
+            report["critic_loss"].count(),
+            12,
+            "Report records the critic loss of each gradient step"
+        );
+
+        assert!(
+            train(-100.0).alpha() < 1.0,
+            "Entropy coefficient decreases while the entropy is above the target"
+        );
+        assert!(
+            train(100.0).alpha() > 1.0,
+            "Entropy coefficient increases while the entropy is below the target"
+        );
+    }
+}
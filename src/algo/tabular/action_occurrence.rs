@@ -1,13 +1,25 @@
 use std::collections::HashMap;
+#[cfg(feature = "serde")]
+use std::path::Path;
 
+use rand::rngs::StdRng;
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
+
+#[cfg(feature = "serde")]
+use crate::persist::{self, Format, PersistError};
 use crate::{
     decay::{self, Decay},
     env::{DiscreteActionSpace, Environment},
     exploration::{Choice, EpsilonGreedy},
     memory::Exp,
+    traits::Agent,
+    util::rng_from_seed,
 };
 
 use super::Hashable;
+#[cfg(feature = "serde")]
+use super::Snapshot;
 
 /// Configuration for the [`SampleAverageAgent`]
 #[derive(Debug, Clone)]
@@ -24,6 +36,10 @@ pub struct ActionOccurrenceAgentConfig<D> {
     ///
     /// **Default**: `|n| 1.0 / n as f32`
     pub alpha_fn: fn(u32) -> f32,
+    /// Seed for the agent's random number generator, or `None` to seed from system entropy
+    ///
+    /// **Default**: `None`
+    pub seed: Option<u64>,
 }
 
 impl Default for ActionOccurrenceAgentConfig<decay::Constant> {
@@ -32,12 +48,14 @@ impl Default for ActionOccurrenceAgentConfig<decay::Constant> {
             epsilon_decay_strategy: decay::Constant::new(0.1),
             default_action_value: 0.0,
             alpha_fn: |n| 1.0 / n as f32,
+            seed: None,
         }
     }
 }
 
 /// An entry in the table
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Entry {
     value: f32,
     count: u32,
@@ -64,6 +82,7 @@ where
     default_action_value: f32,
     alpha_fn: fn(u32) -> f32,
     episode: u32,
+    rng: StdRng,
 }
 
 impl<E, D> ActionOccurrenceAgent<E, D>
@@ -81,38 +100,22 @@ where
             default_action_value: config.default_action_value,
             alpha_fn: config.alpha_fn,
             episode: 0,
+            rng: rng_from_seed(config.seed),
         }
     }
 
-    /// Choose an action based on the current state and exploration policy
-    fn act(&self, env: &E, state: E::State, actions: &[E::Action]) -> E::Action {
-        match self.exploration.choose(self.episode) {
-            Choice::Explore => env.random_action(),
-            Choice::Exploit => *actions
-                .iter()
-                .max_by(|&a, &b| {
-                    let a_value = self
-                        .table
-                        .get(&(state, *a))
-                        .copied()
-                        .unwrap_or(Entry {
-                            value: self.default_action_value,
-                            count: 0,
-                        })
-                        .value;
-                    let b_value = self
-                        .table
-                        .get(&(state, *b))
-                        .copied()
-                        .unwrap_or(Entry {
-                            value: self.default_action_value,
-                            count: 0,
-                        })
-                        .value;
-                    a_value.partial_cmp(&b_value).unwrap()
-                })
-                .expect("There is always at least one action available"),
-        }
+    /// Choose the action with the highest value in the given state
+    fn greedy_action(&self, env: &E, state: E::State) -> E::Action {
+        let value = |action: E::Action| {
+            self.table
+                .get(&(state, action))
+                .map_or(self.default_action_value, |e| e.value)
+        };
+
+        env.actions()
+            .into_iter()
+            .max_by(|&a, &b| value(a).partial_cmp(&value(b)).unwrap())
+            .expect("There is always at least one action available")
     }
 
     /// Learn from a given experience and update the table
@@ -135,25 +138,77 @@ where
                 count: 1,
             });
     }
+}
 
-    /// Run the agent in the given environment
-    pub fn go(&mut self, env: &mut E) {
-        let mut next_state = Some(env.reset());
-        let mut actions = env.actions();
-        while let Some(state) = next_state {
-            let action = self.act(env, state, &actions);
-            let (next, reward) = env.step(action);
-            next_state = next;
-            actions = env.actions();
-
-            self.learn(Exp {
-                state,
-                action,
-                next_state,
-                reward,
-            });
+impl<E, D> Agent<E> for ActionOccurrenceAgent<E, D>
+where
+    E: Environment + DiscreteActionSpace,
+    E::State: Hashable,
+    E::Action: Hashable,
+    D: Decay,
+{
+    fn act(&mut self, env: &mut E, state: E::State) -> E::Action {
+        match self.exploration.choose(self.episode, &mut self.rng) {
+            Choice::Explore => env.random_action(),
+            Choice::Exploit => self.greedy_action(env, state),
         }
+    }
+
+    fn act_greedy(&mut self, env: &mut E, state: E::State) -> E::Action {
+        self.greedy_action(env, state)
+    }
 
+    fn observe(&mut self, _env: &E, exp: Exp<E>) {
+        self.learn(exp);
+    }
+
+    fn end_episode(&mut self) {
         self.episode += 1;
     }
 }
+
+#[cfg(feature = "serde")]
+impl<E, D> ActionOccurrenceAgent<E, D>
+where
+    E: Environment + DiscreteActionSpace,
+    E::State: Hashable + Serialize + DeserializeOwned,
+    E::Action: Hashable + Serialize + DeserializeOwned,
+    D: Decay,
+{
+    /// Save the table and training progress to a file
+    ///
+    /// Hyperparameters and the random number generator state are not saved.
+    pub fn save(&self, path: impl AsRef<Path>, format: Format) -> Result<(), PersistError> {
+        let snapshot = Snapshot::new(&self.table, self.episode, 0);
+        persist::save(&snapshot, path.as_ref(), format)
+    }
+
+    /// Replace the table and training progress with those saved by [save](Self::save)
+    pub fn load(&mut self, path: impl AsRef<Path>, format: Format) -> Result<(), PersistError> {
+        let snapshot: Snapshot<_, Entry> = persist::load(path.as_ref(), format)?;
+        self.table = snapshot.table.into_iter().collect();
+        self.episode = snapshot.episode;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "gym", feature = "serde"))]
+mod tests {
+    use crate::{algo::tabular::tests::save_load_round_trip, gym::FrozenLake};
+
+    use super::*;
+
+    #[test]
+    fn action_occurrence_agent_save_load() {
+        save_load_round_trip(
+            "action_occurrence",
+            || ActionOccurrenceAgent::<FrozenLake, _>::new(ActionOccurrenceAgentConfig::default()),
+            |agent, path, format| agent.save(path, format),
+            |agent, path, format| agent.load(path, format),
+            |loaded, agent, format| {
+                assert_eq!(loaded.table, agent.table, "Table restored from {format:?}");
+                assert_eq!(loaded.episode, 10, "Episode count restored from {format:?}");
+            },
+        );
+    }
+}
@@ -6,3 +6,66 @@ pub mod ucb;
 pub trait Hashable: Copy + Eq + std::hash::Hash {}
 
 impl<T> Hashable for T where T: Copy + Eq + std::hash::Hash {}
+
+/// The saved contents of a tabular agent
+///
+/// Tables are stored as a list of entries because JSON only supports string keys
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Snapshot<K, V> {
+    /// The number of episodes the agent has trained for
+    episode: u32,
+    /// The number of steps the agent has trained for, if it keeps count
+    steps: u32,
+    table: Vec<(K, V)>,
+}
+
+#[cfg(feature = "serde")]
+impl<K: Hashable, V: Copy> Snapshot<K, V> {
+    fn new(table: &std::collections::HashMap<K, V>, episode: u32, steps: u32) -> Self {
+        Self {
+            episode,
+            steps,
+            table: table.iter().map(|(&k, &v)| (k, v)).collect(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "gym", feature = "serde"))]
+pub(crate) mod tests {
+    use std::path::Path;
+
+    use crate::{
+        gym::FrozenLake,
+        persist::{Format, PersistError},
+        traits::Agent,
+    };
+
+    /// Train an agent for 10 episodes of [FrozenLake], then save it and load it into a fresh agent in every format
+    ///
+    /// `check` is called with the loaded agent, the trained agent, and the format for each round trip.
+    pub(crate) fn save_load_round_trip<A: Agent<FrozenLake>>(
+        name: &str,
+        new: impl Fn() -> A,
+        save: impl Fn(&A, &Path, Format) -> Result<(), PersistError>,
+        load: impl Fn(&mut A, &Path, Format) -> Result<(), PersistError>,
+        check: impl Fn(&A, &A, Format),
+    ) {
+        let mut env = FrozenLake::new();
+        let mut agent = new();
+        for _ in 0..10 {
+            agent.go(&mut env);
+        }
+
+        for format in [Format::Json, Format::Binary] {
+            let path =
+                std::env::temp_dir().join(format!("rl_{name}_{}_{format:?}", std::process::id()));
+            save(&agent, &path, format).unwrap();
+            let mut loaded = new();
+            load(&mut loaded, &path, format).unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            check(&loaded, &agent, format);
+        }
+    }
+}
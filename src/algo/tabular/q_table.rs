@@ -1,13 +1,25 @@
 use std::collections::HashMap;
+#[cfg(feature = "serde")]
+use std::path::Path;
 
+use rand::rngs::StdRng;
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
+
+#[cfg(feature = "serde")]
+use crate::persist::{self, Format, PersistError};
 use crate::{
     assert_interval, decay,
     env::{DiscreteActionSpace, Environment},
     exploration::{Choice, EpsilonGreedy},
     memory::Exp,
+    traits::Agent,
+    util::rng_from_seed,
 };
 
 use super::Hashable;
+#[cfg(feature = "serde")]
+use super::Snapshot;
 
 /// Configuration for the [`QTableAgent`]
 #[derive(Debug, Clone)]
@@ -15,6 +27,12 @@ pub struct QTableAgentConfig {
     pub exploration: EpsilonGreedy<decay::Exponential>,
     pub alpha: f32,
     pub gamma: f32,
+    /// Seed for the agent's random number generator, or `None` to seed from system entropy
+    ///
+    /// Combined with [`Environment::seed`], this makes training runs reproducible
+    ///
+    /// **Default:** `None`
+    pub seed: Option<u64>,
 }
 
 impl Default for QTableAgentConfig {
@@ -23,6 +41,7 @@ impl Default for QTableAgentConfig {
             exploration: EpsilonGreedy::new(decay::Exponential::new(0.1, 1.0, 0.01).unwrap()),
             alpha: 0.7,
             gamma: 0.99,
+            seed: None,
         }
     }
 }
@@ -45,6 +64,7 @@ where
     alpha: f32,   // learning rate
     gamma: f32,   // discount factor
     episode: u32, // current episode
+    rng: StdRng,
 }
 
 impl<E> QTableAgent<E>
@@ -70,6 +90,7 @@ where
             alpha: config.alpha,
             gamma: config.gamma,
             episode: 0,
+            rng: rng_from_seed(config.seed),
         }
     }
 
@@ -78,19 +99,16 @@ where
         &self.q_table
     }
 
-    /// Choose an action based on the current state and exploration policy
-    fn act(&self, env: &E, state: E::State, actions: &[E::Action]) -> E::Action {
-        match self.exploration.choose(self.episode) {
-            Choice::Explore => env.random_action(),
-            Choice::Exploit => *actions
-                .iter()
-                .max_by(|&a, &b| {
-                    let a_value = *self.q_table.get(&(state, *a)).unwrap_or(&0.0);
-                    let b_value = *self.q_table.get(&(state, *b)).unwrap_or(&0.0);
-                    a_value.partial_cmp(&b_value).unwrap()
-                })
-                .expect("There is always at least one action available"), // Maybe make this more lenient by providing a default?
-        }
+    /// Choose the action with the highest Q value in the given state
+    fn greedy_action(&self, env: &E, state: E::State) -> E::Action {
+        env.actions()
+            .into_iter()
+            .max_by(|&a, &b| {
+                let a_value = *self.q_table.get(&(state, a)).unwrap_or(&0.0);
+                let b_value = *self.q_table.get(&(state, b)).unwrap_or(&0.0);
+                a_value.partial_cmp(&b_value).unwrap()
+            })
+            .expect("There is always at least one action available") // Maybe make this more lenient by providing a default?
     }
 
     /// Learn from a given experience and update the Q-table
@@ -100,6 +118,7 @@ where
             action,
             next_state,
             reward,
+            ..
         } = experience;
 
         let q_value = *self.q_table.get(&(state, action)).unwrap_or(&0.0);
@@ -117,28 +136,108 @@ where
 
         self.q_table.insert((state, action), weighted_q_value);
     }
+}
 
-    /// Run the agent in the given environment
-    pub fn go(&mut self, env: &mut E) {
-        let mut next_state = Some(env.reset());
-        let mut actions = env.actions();
-        while let Some(state) = next_state {
-            let action = self.act(env, state, &actions);
-            let (next, reward) = env.step(action);
-            next_state = next;
-            actions = env.actions();
-
-            self.learn(
-                Exp {
-                    state,
-                    action,
-                    next_state,
-                    reward,
-                },
-                &actions,
-            );
+impl<E> Agent<E> for QTableAgent<E>
+where
+    E: Environment + DiscreteActionSpace,
+    E::State: Hashable,
+    E::Action: Hashable,
+{
+    fn act(&mut self, env: &mut E, state: E::State) -> E::Action {
+        match self.exploration.choose(self.episode, &mut self.rng) {
+            Choice::Explore => env.random_action(),
+            Choice::Exploit => self.greedy_action(env, state),
         }
+    }
+
+    fn act_greedy(&mut self, env: &mut E, state: E::State) -> E::Action {
+        self.greedy_action(env, state)
+    }
+
+    fn observe(&mut self, env: &E, exp: Exp<E>) {
+        self.learn(exp, &env.actions());
+    }
 
+    fn end_episode(&mut self) {
         self.episode += 1;
     }
 }
+
+#[cfg(feature = "serde")]
+impl<E> QTableAgent<E>
+where
+    E: Environment + DiscreteActionSpace,
+    E::State: Hashable + Serialize + DeserializeOwned,
+    E::Action: Hashable + Serialize + DeserializeOwned,
+{
+    /// Save the Q-table and training progress to a file
+    ///
+    /// Hyperparameters and the random number generator state are not saved.
+    pub fn save(&self, path: impl AsRef<Path>, format: Format) -> Result<(), PersistError> {
+        let snapshot = Snapshot::new(&self.q_table, self.episode, 0);
+        persist::save(&snapshot, path.as_ref(), format)
+    }
+
+    /// Replace the Q-table and training progress with those saved by [save](Self::save)
+    pub fn load(&mut self, path: impl AsRef<Path>, format: Format) -> Result<(), PersistError> {
+        let snapshot: Snapshot<_, f32> = persist::load(path.as_ref(), format)?;
+        self.q_table = snapshot.table.into_iter().collect();
+        self.episode = snapshot.episode;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "gym"))]
+mod tests {
+    #[cfg(feature = "serde")]
+    use crate::algo::tabular::tests::save_load_round_trip;
+    use crate::gym::FrozenLake;
+
+    use super::*;
+
+    fn train(seed: u64) -> Vec<((usize, crate::gym::frozen_lake::FLAction), f32)> {
+        let mut env = FrozenLake::new();
+        env.seed(seed);
+        let mut agent = QTableAgent::new(QTableAgentConfig {
+            seed: Some(seed),
+            ..Default::default()
+        });
+
+        for _ in 0..50 {
+            agent.go(&mut env);
+        }
+
+        let mut q_table = agent.get_q_table().clone().into_iter().collect::<Vec<_>>();
+        q_table.sort_unstable_by_key(|((s, a), _)| (*s, *a as usize));
+        q_table
+    }
+
+    #[test]
+    fn q_table_agent_seeded() {
+        assert_eq!(
+            train(5),
+            train(5),
+            "Training runs are identical given the same seed"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn q_table_agent_save_load() {
+        save_load_round_trip(
+            "q_table",
+            || QTableAgent::<FrozenLake>::new(QTableAgentConfig::default()),
+            |agent, path, format| agent.save(path, format),
+            |agent, path, format| agent.load(path, format),
+            |loaded, agent, format| {
+                assert_eq!(
+                    loaded.get_q_table(),
+                    agent.get_q_table(),
+                    "Q-table restored from {format:?}"
+                );
+                assert_eq!(loaded.episode, 10, "Episode count restored from {format:?}");
+            },
+        );
+    }
+}
@@ -1,11 +1,21 @@
 use std::collections::HashMap;
+#[cfg(feature = "serde")]
+use std::path::Path;
 
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
+
+#[cfg(feature = "serde")]
+use crate::persist::{self, Format, PersistError};
 use crate::{
     env::{DiscreteActionSpace, Environment},
     memory::Exp,
+    traits::Agent,
 };
 
 use super::Hashable;
+#[cfg(feature = "serde")]
+use super::Snapshot;
 
 /// Configuration for the [`UCBAgent`]
 #[derive(Debug, Clone)]
@@ -36,6 +46,7 @@ impl Default for UCBAgentConfig {
 
 /// An entry in the table
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Entry {
     value: f32,
     count: u32,
@@ -78,8 +89,8 @@ where
         }
     }
 
-    /// Choose an action based on the current state
-    fn act(&self, state: E::State, actions: &[E::Action]) -> E::Action {
+    /// Choose the action with the highest upper confidence bound in the given state
+    fn ucb_action(&self, state: E::State, actions: &[E::Action]) -> E::Action {
         let action_entries = actions
             .iter()
             .map(|&action| {
@@ -109,13 +120,27 @@ where
         choice.into()
     }
 
+    /// Choose the action with the highest value in the given state, ignoring the exploration bonus
+    fn greedy_action(&self, state: E::State, actions: &[E::Action]) -> E::Action {
+        let value = |action: E::Action| {
+            self.table
+                .get(&(state, action))
+                .map_or(self.default_action_value, |e| e.value)
+        };
+
+        *actions
+            .iter()
+            .max_by(|&&a, &&b| value(a).partial_cmp(&value(b)).unwrap())
+            .expect("There is always at least one action available")
+    }
+
     /// Learn from a given experience and update the table
     fn learn(&mut self, experience: Exp<E>) {
         let Exp {
             state,
             action,
-            next_state: _,
             reward,
+            ..
         } = experience;
 
         self.table
@@ -129,27 +154,78 @@ where
                 count: 1,
             });
     }
+}
 
-    /// Run the agent in the given environment
-    pub fn go(&mut self, env: &mut E) {
-        let mut next_state = Some(env.reset());
-        let mut actions = env.actions();
-        while let Some(state) = next_state {
-            let action = self.act(state, &actions);
-            let (next, reward) = env.step(action);
-            next_state = next;
-            actions = env.actions();
-
-            self.learn(Exp {
-                state,
-                action,
-                next_state,
-                reward,
-            });
+impl<E> Agent<E> for UCBAgent<E>
+where
+    E: Environment + DiscreteActionSpace,
+    E::State: Hashable,
+    E::Action: Hashable + From<usize>,
+{
+    fn act(&mut self, env: &mut E, state: E::State) -> E::Action {
+        self.ucb_action(state, &env.actions())
+    }
 
-            self.t += 1;
-        }
+    fn act_greedy(&mut self, env: &mut E, state: E::State) -> E::Action {
+        self.greedy_action(state, &env.actions())
+    }
 
+    fn observe(&mut self, _env: &E, exp: Exp<E>) {
+        self.learn(exp);
+        self.t += 1;
+    }
+
+    fn end_episode(&mut self) {
         self.episode += 1;
     }
 }
+
+#[cfg(feature = "serde")]
+impl<E> UCBAgent<E>
+where
+    E: Environment + DiscreteActionSpace,
+    E::State: Hashable + Serialize + DeserializeOwned,
+    E::Action: Hashable + Serialize + DeserializeOwned + From<usize>,
+{
+    /// Save the table and training progress, including the step count used for the confidence bound, to a file
+    ///
+    /// Hyperparameters are not saved.
+    pub fn save(&self, path: impl AsRef<Path>, format: Format) -> Result<(), PersistError> {
+        let snapshot = Snapshot::new(&self.table, self.episode, self.t);
+        persist::save(&snapshot, path.as_ref(), format)
+    }
+
+    /// Replace the table and training progress with those saved by [save](Self::save)
+    pub fn load(&mut self, path: impl AsRef<Path>, format: Format) -> Result<(), PersistError> {
+        let snapshot: Snapshot<_, Entry> = persist::load(path.as_ref(), format)?;
+        self.table = snapshot.table.into_iter().collect();
+        self.episode = snapshot.episode;
+        self.t = snapshot.steps;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "gym", feature = "serde"))]
+mod tests {
+    use crate::{algo::tabular::tests::save_load_round_trip, gym::FrozenLake};
+
+    use super::*;
+
+    #[test]
+    fn ucb_agent_save_load() {
+        save_load_round_trip(
+            "ucb",
+            || UCBAgent::<FrozenLake>::new(UCBAgentConfig::default()),
+            |agent, path, format| agent.save(path, format),
+            |agent, path, format| agent.load(path, format),
+            |loaded, agent, format| {
+                assert_eq!(loaded.table, agent.table, "Table restored from {format:?}");
+                assert_eq!(
+                    (loaded.episode, loaded.t),
+                    (10, agent.t),
+                    "Episode and step counts restored from {format:?}"
+                );
+            },
+        );
+    }
+}
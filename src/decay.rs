@@ -13,6 +13,7 @@ fn validate(rate: f32, vi: f32, vf: f32) -> Result<(), String> {
 
 /// A constant value
 #[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Constant {
     value: f32,
 }
@@ -31,6 +32,7 @@ impl Decay for Constant {
 
 /// v(t) = v<sub>f</sub> + (v<sub>i</sub> - v<sub>f</sub>) * e<sup>-rt</sup>
 #[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Exponential {
     rate: f32,
     vi: f32,
@@ -53,6 +55,7 @@ impl Decay for Exponential {
 
 /// v(t) = v<sub>f</sub> + (v<sub>i</sub> - v<sub>f</sub>) / (1 + rt)
 #[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InverseTime {
     rate: f32,
     vi: f32,
@@ -75,6 +78,7 @@ impl Decay for InverseTime {
 
 /// v(t) = max(v<sub>i</sub> - rt, v<sub>f</sub>)
 #[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Linear {
     rate: f32,
     vi: f32,
@@ -97,6 +101,7 @@ impl Decay for Linear {
 
 /// v(t) = max(v<sub>i</sub> * r<sup>floor(t/s)</sup>, v<sub>f</sub>)
 #[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Step {
     rate: f32,
     vi: f32,
@@ -3,6 +3,7 @@ use std::ops::Index;
 
 /// A fixed-size ringbuffer
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RingBuffer<T> {
     buffer: Vec<T>,
     ix: usize,
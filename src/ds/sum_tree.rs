@@ -2,6 +2,7 @@ use std::ops::Index;
 
 /// A binary tree data structure where each parent node is the sum of its child nodes
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SumTree {
     tree: Vec<f32>,
     max: f32,
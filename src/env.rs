@@ -1,10 +1,10 @@
 use std::{
     collections::BTreeMap,
-    fmt::Debug,
-    ops::{Deref, DerefMut},
+    fmt::{Debug, Display},
+    ops::Index,
 };
 
-use crate::util::summary_from_keys;
+use crate::space::Space;
 
 /// Represents a Markov decision process, defining the dynamics of an environment
 /// in which an agent can operate.
@@ -29,8 +29,9 @@ pub trait Environment {
 
     /// Update the environment in response to a an action taken by an agent, producing a new state and associated reward
     ///
-    /// **Returns** `(next_state, reward)`
-    fn step(&mut self, action: Self::Action) -> (Option<Self::State>, f32);
+    /// **Returns** a [StepResult] containing the next state (or `None` if a terminal state was reached), the reward,
+    /// whether the episode was truncated, and any additional info
+    fn step(&mut self, action: Self::Action) -> StepResult<Self::State>;
 
     /// Reset the environment to an initial state
     ///
@@ -38,12 +39,101 @@ pub trait Environment {
     fn reset(&mut self) -> Self::State;
 
     /// Select a random action from the action space
-    fn random_action(&self) -> Self::Action;
+    ///
+    /// Implementations should draw from the environment's own random number generator so that
+    /// this is reproducible after calling [seed](Environment::seed)
+    fn random_action(&mut self) -> Self::Action;
+
+    /// Seed the environment's random number generator
+    ///
+    /// After seeding, the sequence of states, rewards, and random actions produced by the environment
+    /// must be fully determined by the seed and the actions taken. The default implementation does
+    /// nothing, which is only correct for environments without any randomness.
+    fn seed(&mut self, _seed: u64) {}
 
     /// Determine if the environment is in an active or terminal state
     fn is_active(&self) -> bool {
         true
     }
+
+    /// Get the [Report] the environment records its metrics to, if it keeps one
+    ///
+    /// Training loops such as the [Trainer](crate::trainer::Trainer) take the report at the end of every episode.
+    fn report(&mut self) -> Option<&mut Report> {
+        None
+    }
+}
+
+/// Additional per-step information reported by an [Environment], keyed by name
+pub type Info = BTreeMap<&'static str, f64>;
+
+/// The result of taking a [step](Environment::step) in an [Environment]
+///
+/// An episode can end in two ways:
+/// - **Termination** - a terminal state of the MDP was reached, so there is no next state and no future reward.
+///   This is represented by `next_state` being `None`.
+/// - **Truncation** - the episode was cut short for a reason outside of the MDP, such as a time limit.
+///   The next state still exists and its value should be bootstrapped, so `next_state` is `Some` and `truncated` is `true`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepResult<S> {
+    /// The state of the environment after the step, or `None` if a terminal state was reached
+    pub next_state: Option<S>,
+    /// The reward received for the step
+    pub reward: f32,
+    /// Whether the episode was cut short without reaching a terminal state
+    pub truncated: bool,
+    /// Additional information about the step
+    pub info: Info,
+}
+
+impl<S> StepResult<S> {
+    /// Create a step result that was not truncated
+    ///
+    /// `next_state` should be `None` if a terminal state was reached
+    pub fn new(next_state: Option<S>, reward: f32) -> Self {
+        Self {
+            next_state,
+            reward,
+            truncated: false,
+            info: Info::new(),
+        }
+    }
+
+    /// Create a step result for an episode that was truncated in `next_state`
+    pub fn truncated(next_state: S, reward: f32) -> Self {
+        Self {
+            next_state: Some(next_state),
+            reward,
+            truncated: true,
+            info: Info::new(),
+        }
+    }
+
+    /// Attach a piece of additional information to the step result
+    pub fn with_info(mut self, key: &'static str, value: f64) -> Self {
+        self.info.insert(key, value);
+        self
+    }
+
+    /// Map the next state to a different type, keeping the rest of the step result unchanged
+    pub fn map<T>(self, f: impl FnOnce(S) -> T) -> StepResult<T> {
+        StepResult {
+            next_state: self.next_state.map(f),
+            reward: self.reward,
+            truncated: self.truncated,
+            info: self.info,
+        }
+    }
+
+    /// Whether a terminal state was reached
+    pub fn is_terminated(&self) -> bool {
+        self.next_state.is_none()
+    }
+
+    /// Whether the episode is over, either by termination or truncation
+    pub fn is_done(&self) -> bool {
+        self.is_terminated() || self.truncated
+    }
 }
 
 /// An [Environment] with a discrete action space
@@ -54,12 +144,48 @@ pub trait DiscreteActionSpace: Environment {
     fn actions(&self) -> Vec<Self::Action>;
 }
 
+/// A [DiscreteActionSpace] with a fixed set of actions indexed `0..num_actions`, of which only some may be legal
+/// in a given state
+///
+/// This allows agents that produce a value for every action, such as a [DQNAgent](crate::algo::dqn::DQNAgent),
+/// to mask out illegal actions.
+pub trait MaskedActionSpace: DiscreteActionSpace {
+    /// The total number of actions, legal or not
+    fn num_actions(&self) -> usize;
+
+    /// Get a mask over action indices for the current state, where `true` marks a legal action
+    ///
+    /// The default implementation derives the mask from [actions](DiscreteActionSpace::actions)
+    fn action_mask(&self) -> Vec<bool>
+    where
+        Self::Action: Into<usize>,
+    {
+        let mut mask = vec![false; self.num_actions()];
+        for action in self.actions() {
+            mask[action.into()] = true;
+        }
+        mask
+    }
+}
+
 /// An [Environment] with a discrete state space
 pub trait DiscreteStateSpace: Environment {
     /// Get all possible states in the environment
     fn states(&self) -> Vec<Self::State>;
 }
 
+/// An [Environment] that describes its state and action spaces
+///
+/// This allows generic algorithms and tooling to inspect the structure of an environment, such as the
+/// number of discrete actions or the shape and bounds of continuous observations, without knowing its concrete types.
+pub trait Spaces: Environment {
+    /// The space of states the environment can produce
+    fn observation_space(&self) -> Space;
+
+    /// The space of actions the environment accepts
+    fn action_space(&self) -> Space;
+}
+
 /// An [Environment] with a deterministic model
 pub trait DeterministicModel: Environment {
     /// Get the next state and reward given the provided state and action
@@ -83,48 +209,232 @@ pub trait KnownDynamics: Environment {
     ) -> f32;
 }
 
+/// A possible result of taking an action in a state, as enumerated by a [TransitionModel]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome<S> {
+    /// The next state, or `None` if a terminal state is reached
+    pub next_state: Option<S>,
+    /// The expected reward received with this outcome
+    pub reward: f32,
+    /// The probability of this outcome
+    pub prob: f32,
+}
+
+/// An [Environment] with a known model that can enumerate every outcome of a state-action pair
+///
+/// Unlike [KnownDynamics], which gives the probability of a single transition, this allows full Bellman backups
+/// over all successor states, so dynamic programming and planning algorithms can be written once against this trait.
+pub trait TransitionModel: DiscreteActionSpace {
+    /// Enumerate the possible outcomes of taking `action` in `state`
+    ///
+    /// The probabilities of the outcomes should sum to 1. Outcomes with the same next state may be merged by
+    /// using their expected reward. Terminal states have no outcomes.
+    fn outcomes(&self, state: Self::State, action: Self::Action) -> Vec<Outcome<Self::State>>;
+
+    /// Get the actions available in `state`
+    ///
+    /// The default implementation returns [actions](DiscreteActionSpace::actions), which is only correct if the
+    /// available actions do not depend on the state
+    fn available_actions(&self, _state: Self::State) -> Vec<Self::Action> {
+        self.actions()
+    }
+}
+
+/// How a [Metric] combines the values recorded over an episode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Aggregation {
+    /// The sum of all recorded values
+    #[default]
+    Sum,
+    /// The arithmetic mean of all recorded values
+    Mean,
+    /// The smallest recorded value
+    Min,
+    /// The largest recorded value
+    Max,
+    /// The most recently recorded value
+    Last,
+    /// The number of recorded values, regardless of their magnitude
+    Count,
+}
+
+impl Display for Aggregation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Aggregation::Sum => "sum",
+            Aggregation::Mean => "mean",
+            Aggregation::Min => "min",
+            Aggregation::Max => "max",
+            Aggregation::Last => "last",
+            Aggregation::Count => "count",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A single named quantity tracked by a [Report]
+///
+/// Values are folded into a running aggregate as they are recorded, and can optionally be kept as a per-step series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metric {
+    aggregation: Aggregation,
+    acc: f64,
+    count: usize,
+    series: Option<Vec<f64>>,
+}
+
+impl Metric {
+    /// Create an empty metric with the given aggregation
+    pub fn new(aggregation: Aggregation) -> Self {
+        Self {
+            aggregation,
+            acc: 0.0,
+            count: 0,
+            series: None,
+        }
+    }
+
+    /// Keep every recorded value in addition to the aggregate
+    pub fn with_series(mut self) -> Self {
+        self.series.get_or_insert_with(Vec::new);
+        self
+    }
+
+    /// Record a value
+    pub fn record(&mut self, value: f64) {
+        self.acc = match self.aggregation {
+            _ if self.count == 0 => value,
+            Aggregation::Sum | Aggregation::Mean => self.acc + value,
+            Aggregation::Min => self.acc.min(value),
+            Aggregation::Max => self.acc.max(value),
+            Aggregation::Last => value,
+            Aggregation::Count => 0.0,
+        };
+        self.count += 1;
+        if let Some(series) = &mut self.series {
+            series.push(value);
+        }
+    }
+
+    /// The aggregated value, or `0.0` if nothing has been recorded
+    pub fn value(&self) -> f64 {
+        match self.aggregation {
+            _ if self.count == 0 => 0.0,
+            Aggregation::Mean => self.acc / self.count as f64,
+            Aggregation::Count => self.count as f64,
+            _ => self.acc,
+        }
+    }
+
+    /// The way recorded values are combined
+    pub fn aggregation(&self) -> Aggregation {
+        self.aggregation
+    }
+
+    /// The number of values recorded
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Every recorded value in order, if series tracking is enabled
+    pub fn series(&self) -> Option<&[f64]> {
+        self.series.as_deref()
+    }
+
+    /// An empty metric with the same aggregation and series tracking
+    fn cleared(&self) -> Self {
+        Self {
+            aggregation: self.aggregation,
+            acc: 0.0,
+            count: 0,
+            series: self.series.as_ref().map(|_| Vec::new()),
+        }
+    }
+}
+
 /// A format for reporting training results to [viz](crate::viz)
 ///
-/// Functionally a wrapper around a [BTreeMap] such that values are always returned in the same order.
-/// Meant to be initialized once and used for the lifetime of an [Environment].
+/// A collection of named [Metric]s kept in a [BTreeMap] such that they are always returned in the same order.
+/// Metrics can be declared up front with a specific [Aggregation], or created on the fly by [Report::record],
+/// in which case they are summed. Meant to be initialized once and used for the lifetime of an [Environment].
 ///
 /// See examples for implementation
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct Report {
-    keys: Vec<&'static str>,
-    map: BTreeMap<&'static str, f64>,
+    metrics: BTreeMap<String, Metric>,
 }
 
 impl Report {
-    /// Create a new report format
-    pub fn new(mut keys: Vec<&'static str>) -> Self {
-        keys.sort_unstable();
-        let map = summary_from_keys(&keys);
-        Self { keys, map }
+    /// Create a new report with summed metrics for each of the given keys
+    pub fn new<K: Into<String>>(keys: impl IntoIterator<Item = K>) -> Self {
+        Self {
+            metrics: keys
+                .into_iter()
+                .map(|k| (k.into(), Metric::new(Aggregation::Sum)))
+                .collect(),
+        }
     }
 
-    /// Get keys as a slice
-    pub fn keys(&self) -> &[&'static str] {
-        &self.keys
+    /// Declare a metric with the given aggregation, replacing any existing metric of the same name
+    pub fn with_metric(mut self, key: impl Into<String>, aggregation: Aggregation) -> Self {
+        self.metrics.insert(key.into(), Metric::new(aggregation));
+        self
     }
 
-    /// Take the report by extracting the inner map and leaving a default
-    pub fn take(&mut self) -> BTreeMap<&'static str, f64> {
-        std::mem::replace(&mut self.map, summary_from_keys(&self.keys))
+    /// Keep a per-step series of every value recorded to `key`, declaring it as a summed metric if necessary
+    pub fn with_series(mut self, key: impl Into<String>) -> Self {
+        let metric = self
+            .metrics
+            .entry(key.into())
+            .or_insert_with(|| Metric::new(Aggregation::Sum));
+        metric.series.get_or_insert_with(Vec::new);
+        self
+    }
+
+    /// Record a value to the metric named `key`, creating a summed metric if it does not exist
+    pub fn record(&mut self, key: &str, value: f64) {
+        match self.metrics.get_mut(key) {
+            Some(metric) => metric.record(value),
+            None => {
+                let mut metric = Metric::new(Aggregation::Sum);
+                metric.record(value);
+                self.metrics.insert(key.to_owned(), metric);
+            }
+        }
     }
-}
 
-impl Deref for Report {
-    type Target = BTreeMap<&'static str, f64>;
+    /// Get a metric by name
+    pub fn get(&self, key: &str) -> Option<&Metric> {
+        self.metrics.get(key)
+    }
+
+    /// Get the names of all metrics in sorted order
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.metrics.keys().map(String::as_str)
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.map
+    /// Get all metrics keyed by name
+    pub fn metrics(&self) -> &BTreeMap<String, Metric> {
+        &self.metrics
+    }
+
+    /// Take the current metrics, leaving each one empty but with the same aggregation and series tracking
+    pub fn take(&mut self) -> BTreeMap<String, Metric> {
+        let cleared = self
+            .metrics
+            .iter()
+            .map(|(k, m)| (k.clone(), m.cleared()))
+            .collect();
+        std::mem::replace(&mut self.metrics, cleared)
     }
 }
 
-impl DerefMut for Report {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.map
+impl Index<&str> for Report {
+    type Output = Metric;
+
+    /// **Panics** if the metric does not exist
+    fn index(&self, key: &str) -> &Self::Output {
+        &self.metrics[key]
     }
 }
 
@@ -138,43 +448,82 @@ pub(crate) mod tests {
         type State = i32;
         type Action = i32;
 
-        fn step(&mut self, _action: Self::Action) -> (Option<Self::State>, f32) {
-            (None, 0.0)
+        fn step(&mut self, _action: Self::Action) -> StepResult<Self::State> {
+            StepResult::new(None, 0.0)
         }
 
         fn reset(&mut self) -> Self::State {
             0
         }
 
-        fn random_action(&self) -> Self::Action {
+        fn random_action(&mut self) -> Self::Action {
             0
         }
     }
 
+    #[test]
+    fn step_result_functional() {
+        let step = StepResult::new(Some(1), 1.0);
+        assert!(!step.is_terminated() && !step.is_done(), "Step continues");
+
+        let step = StepResult::<i32>::new(None, 1.0);
+        assert!(step.is_terminated() && step.is_done(), "Step terminates");
+
+        let step = StepResult::truncated(1, 1.0).with_info("success", 1.0);
+        assert!(!step.is_terminated(), "Truncated step is not terminal");
+        assert!(step.is_done(), "Truncated step is done");
+        assert_eq!(step.next_state, Some(1), "Truncated step keeps next state");
+        assert_eq!(step.info["success"], 1.0, "Info is attached");
+    }
+
     #[test]
     fn report_functional() {
-        let mut report = Report::new(vec!["c", "a", "b"]);
-        assert_eq!(
-            *report.keys(),
-            ["a", "b", "c"],
-            "Keys were sorted on initialization"
+        let mut report = Report::new(vec!["c", "a"])
+            .with_metric("b", Aggregation::Mean)
+            .with_metric("d", Aggregation::Max)
+            .with_series("a");
+        assert!(
+            report.keys().eq(["a", "b", "c", "d"]),
+            "Keys are kept in sorted order"
         );
 
-        report.entry("a").and_modify(|x| *x += 1.0);
+        for x in [1.0, 2.0, 6.0] {
+            report.record("a", x);
+            report.record("b", x);
+            report.record("d", -x);
+        }
+        report.record("e", 3.0);
+        assert_eq!(report["a"].value(), 9.0, "Sum aggregates");
+        assert_eq!(report["b"].value(), 3.0, "Mean aggregates");
+        assert_eq!(report["d"].value(), -1.0, "Max aggregates");
+        assert_eq!(report["e"].value(), 3.0, "Unknown keys are created");
         assert_eq!(
-            *report.get("a").unwrap(),
-            1.0,
-            "Mutations on entries work and report derefs into inner map"
+            report["a"].series(),
+            Some([1.0, 2.0, 6.0].as_slice()),
+            "Series are recorded"
         );
 
-        let inner_map = report.take();
+        let metrics = report.take();
         assert!(
-            inner_map.values().eq([1.0, 0.0, 0.0].iter()),
-            "Inner map can be taken with correct values"
+            metrics
+                .values()
+                .map(Metric::value)
+                .eq([9.0, 3.0, 0.0, -1.0, 3.0]),
+            "Metrics can be taken with correct values"
         );
         assert!(
-            report.values().eq([0.0, 0.0, 0.0].iter()),
-            "Taking inner map leaves default values in report"
+            report.metrics().values().all(|m| m.count() == 0),
+            "Taking metrics leaves them empty"
+        );
+        assert_eq!(
+            report["b"].aggregation(),
+            Aggregation::Mean,
+            "Taking metrics keeps their aggregation"
+        );
+        assert_eq!(
+            report["a"].series(),
+            Some([].as_slice()),
+            "Taking metrics keeps series tracking"
         );
     }
 }
@@ -0,0 +1,106 @@
+use rand::Rng;
+use rand_distr::StandardNormal;
+
+/// Noise added to the actions of a deterministic continuous policy for exploration
+///
+/// Noise is applied to actions normalized to `[-1, 1]`, so its scale is relative to the bounds of the action space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ActionNoise {
+    /// Independent Gaussian noise with mean zero and standard deviation `std`
+    Gaussian { std: f32 },
+    /// Temporally correlated noise from an Ornstein-Uhlenbeck process
+    ///
+    /// x<sub>t+1</sub> = x<sub>t</sub> − θx<sub>t</sub>dt + σ√dt 𝒩(0, 1), starting from zero each episode
+    OrnsteinUhlenbeck { theta: f32, sigma: f32, dt: f32 },
+}
+
+impl ActionNoise {
+    /// Gaussian noise with the given standard deviation
+    pub fn gaussian(std: f32) -> Self {
+        Self::Gaussian { std }
+    }
+
+    /// An Ornstein-Uhlenbeck process with the given σ, θ = `0.15`, and dt = `1e-2`
+    pub fn ornstein_uhlenbeck(sigma: f32) -> Self {
+        Self::OrnsteinUhlenbeck {
+            theta: 0.15,
+            sigma,
+            dt: 1e-2,
+        }
+    }
+}
+
+/// A source of [ActionNoise] that keeps the state of correlated noise between steps
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoiseProcess {
+    noise: ActionNoise,
+    state: Vec<f32>,
+}
+
+impl NoiseProcess {
+    /// Initialize a noise process
+    pub fn new(noise: ActionNoise) -> Self {
+        Self {
+            noise,
+            state: Vec::new(),
+        }
+    }
+
+    /// Sample the noise for each of `dim` action dimensions, drawing from the provided random number generator
+    pub fn sample(&mut self, dim: usize, rng: &mut impl Rng) -> Vec<f32> {
+        let mut normal = || rng.sample::<f32, _>(StandardNormal);
+        match self.noise {
+            ActionNoise::Gaussian { std } => (0..dim).map(|_| std * normal()).collect(),
+            ActionNoise::OrnsteinUhlenbeck { theta, sigma, dt } => {
+                self.state.resize(dim, 0.0);
+                for x in &mut self.state {
+                    *x += -theta * *x * dt + sigma * dt.sqrt() * normal();
+                }
+                self.state.clone()
+            }
+        }
+    }
+
+    /// Reset correlated noise to zero, e.g. at the end of an episode
+    pub fn reset(&mut self) {
+        self.state.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn ornstein_uhlenbeck_functional() {
+        let mut process = NoiseProcess::new(ActionNoise::OrnsteinUhlenbeck {
+            theta: 0.5,
+            sigma: 1.0,
+            dt: 1.0,
+        });
+        let mut rng = StdRng::seed_from_u64(0);
+        let first = process.sample(2, &mut rng);
+        let second = process.sample(2, &mut rng);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let normals = (0..4)
+            .map(|_| rng.sample::<f32, _>(StandardNormal))
+            .collect::<Vec<_>>();
+        assert_eq!(first, normals[..2], "Process starts from zero");
+        assert_eq!(
+            second,
+            [0.5 * first[0] + normals[2], 0.5 * first[1] + normals[3]],
+            "Process reverts toward zero"
+        );
+
+        process.reset();
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(
+            process.sample(2, &mut rng),
+            first,
+            "Reset restarts the process"
+        );
+    }
+}
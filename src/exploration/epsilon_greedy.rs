@@ -1,4 +1,4 @@
-use rand::{thread_rng, Rng};
+use rand::Rng;
 
 use crate::decay::Decay;
 
@@ -16,10 +16,10 @@ impl<D: Decay> EpsilonGreedy<D> {
         Self { epsilon: decay }
     }
 
-    /// Invoke epsilon greedy policy for current episode
-    pub fn choose(&self, episode: u32) -> Choice {
+    /// Invoke epsilon greedy policy for current episode, drawing from the provided random number generator
+    pub fn choose(&self, episode: u32, rng: &mut impl Rng) -> Choice {
         let epsilon = self.epsilon.evaluate(episode as f32);
-        if thread_rng().gen::<f32>() > epsilon {
+        if rng.gen::<f32>() > epsilon {
             Choice::Exploit
         } else {
             Choice::Explore
@@ -29,6 +29,8 @@ impl<D: Decay> EpsilonGreedy<D> {
 
 #[cfg(test)]
 mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
     use crate::decay;
 
     use super::*;
@@ -37,6 +39,20 @@ mod tests {
     fn epsilon_greedy_functional() {
         let exploration = EpsilonGreedy::new(decay::Exponential::new(0.001, 1.0, 0.05).unwrap());
 
-        exploration.choose(12);
+        exploration.choose(12, &mut rand::thread_rng());
+    }
+
+    #[test]
+    fn epsilon_greedy_seeded() {
+        let exploration = EpsilonGreedy::new(decay::Constant::new(0.5));
+        let mut rng1 = StdRng::seed_from_u64(7);
+        let mut rng2 = StdRng::seed_from_u64(7);
+
+        let choices1 = (0..32).map(|t| exploration.choose(t, &mut rng1));
+        let choices2 = (0..32).map(|t| exploration.choose(t, &mut rng2));
+        assert!(
+            choices1.eq(choices2),
+            "Choices are identical given the same seed"
+        );
     }
 }
@@ -1,14 +1,17 @@
 /// Exploration policy result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Choice {
     Explore,
     Exploit,
 }
 
+mod action_noise;
 mod epsilon_greedy;
 mod softmax;
 mod thompson;
 mod ucb;
 
+pub use action_noise::{ActionNoise, NoiseProcess};
 pub use epsilon_greedy::EpsilonGreedy;
 pub use softmax::Softmax;
 // pub use thompson::Thompson;
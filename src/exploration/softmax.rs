@@ -3,7 +3,7 @@ use std::ops::AddAssign;
 use burn::tensor::{self, backend::Backend, Tensor};
 use rand::{
     distributions::{uniform::SampleUniform, Distribution, WeightedIndex},
-    thread_rng,
+    Rng,
 };
 
 use crate::decay::Decay;
@@ -20,17 +20,17 @@ impl<D: Decay> Softmax<D> {
     }
 
     /// Invoke softmax exploration policy at time `t` with provided Q values
-    pub fn choose(&self, t: f32, q_values: &[f32]) -> usize {
+    pub fn choose(&self, t: f32, q_values: &[f32], rng: &mut impl Rng) -> usize {
         let tau = self.temperature.evaluate(t);
         let exponentials = q_values.iter().map(|x| (x / tau).exp());
         let sum: f32 = exponentials.clone().sum();
         let weights = exponentials.map(|x| x / sum);
         let dist = WeightedIndex::new(weights).expect("`q_values` is not empty");
-        dist.sample(&mut thread_rng())
+        dist.sample(rng)
     }
 
     /// Invoke softmax exploration policy at time `t` with provided 1D [Tensor] of Q values
-    pub fn choose_from_tensor<B>(&self, t: f32, tensor: Tensor<B, 1>) -> usize
+    pub fn choose_from_tensor<B>(&self, t: f32, tensor: Tensor<B, 1>, rng: &mut impl Rng) -> usize
     where
         B: Backend,
         B::FloatElem: PartialOrd + SampleUniform,
@@ -41,6 +41,6 @@ impl<D: Decay> Softmax<D> {
             .iter_dim(0)
             .map(|t| t.into_scalar());
         let dist = WeightedIndex::new(weights).expect("`tensor` is not empty");
-        dist.sample(&mut thread_rng())
+        dist.sample(rng)
     }
 }
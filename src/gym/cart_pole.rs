@@ -2,11 +2,13 @@ use burn::prelude::*;
 use gym_rs::core::{ActionReward, Env};
 use gym_rs::envs::classical_control::cartpole::{CartPoleEnv, CartPoleObservation};
 use gym_rs::utils::renderer::RenderMode;
+use rand::rngs::StdRng;
 use rand::seq::IteratorRandom;
-use rand::thread_rng;
+use rand::SeedableRng;
 use strum::{EnumIter, FromRepr, IntoEnumIterator, VariantArray};
 
-use crate::env::{DiscreteActionSpace, Environment, Report};
+use crate::env::{DiscreteActionSpace, Environment, MaskedActionSpace, Report, Spaces, StepResult};
+use crate::space::Space;
 use crate::traits::ToTensor;
 
 fn obs2arr(observation: CartPoleObservation) -> [f32; 4] {
@@ -31,6 +33,12 @@ impl From<usize> for CPAction {
     }
 }
 
+impl From<CPAction> for usize {
+    fn from(value: CPAction) -> Self {
+        value as usize
+    }
+}
+
 impl<B: Backend<IntElem = i32>> ToTensor<B, 2, Int> for Vec<CPAction> {
     fn to_tensor(self, device: &B::Device) -> Tensor<B, 2, Int> {
         let len = self.len();
@@ -48,13 +56,17 @@ impl<B: Backend<IntElem = i32>> ToTensor<B, 2, Int> for Vec<CPAction> {
 #[derive(Debug, Clone)]
 pub struct CartPole {
     gym_env: CartPoleEnv,
-    pub report: Report,
+    rng: StdRng,
+    seed: Option<u64>,
+    report: Report,
 }
 
 impl CartPole {
     pub fn new(render_mode: RenderMode) -> Self {
         Self {
             gym_env: CartPoleEnv::new(render_mode),
+            rng: StdRng::from_entropy(),
+            seed: None,
             report: Report::new(vec!["reward"]),
         }
     }
@@ -64,11 +76,23 @@ impl Environment for CartPole {
     type State = [f32; 4];
     type Action = CPAction;
 
-    fn random_action(&self) -> Self::Action {
-        CPAction::iter().choose(&mut thread_rng()).unwrap()
+    fn random_action(&mut self) -> Self::Action {
+        CPAction::iter().choose(&mut self.rng).unwrap()
+    }
+
+    /// Seed the environment
+    ///
+    /// The inner gym environment is reseeded on the next call to [`reset`](Environment::reset)
+    fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.seed = Some(seed);
+    }
+
+    fn report(&mut self) -> Option<&mut Report> {
+        Some(&mut self.report)
     }
 
-    fn step(&mut self, action: Self::Action) -> (Option<Self::State>, f32) {
+    fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
         let ActionReward {
             observation,
             reward,
@@ -82,13 +106,13 @@ impl Environment for CartPole {
             Some(obs2arr(observation))
         };
 
-        self.report.entry("reward").and_modify(|x| *x += *reward);
+        self.report.record("reward", *reward);
 
-        (next_state, *reward as f32)
+        StepResult::new(next_state, *reward as f32)
     }
 
     fn reset(&mut self) -> Self::State {
-        obs2arr(self.gym_env.reset(None, false, None).0)
+        obs2arr(self.gym_env.reset(self.seed.take(), false, None).0)
     }
 }
 
@@ -98,6 +122,31 @@ impl DiscreteActionSpace for CartPole {
     }
 }
 
+impl MaskedActionSpace for CartPole {
+    fn num_actions(&self) -> usize {
+        CPAction::VARIANTS.len()
+    }
+}
+
+impl Spaces for CartPole {
+    /// Cart position, cart velocity, pole angle, and pole angular velocity
+    ///
+    /// Position and angle are bounded by twice the thresholds at which an episode terminates
+    fn observation_space(&self) -> Space {
+        let x_bound = 2.0 * 2.4;
+        let theta_bound = 2.0 * 12.0_f32.to_radians();
+        Space::bounded_elementwise(
+            &[4],
+            vec![-x_bound, f32::NEG_INFINITY, -theta_bound, f32::NEG_INFINITY],
+            vec![x_bound, f32::INFINITY, theta_bound, f32::INFINITY],
+        )
+    }
+
+    fn action_space(&self) -> Space {
+        Space::discrete(CPAction::VARIANTS.len())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
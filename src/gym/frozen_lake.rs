@@ -1,6 +1,12 @@
-use rand::{seq::IteratorRandom, thread_rng};
+use rand::{rngs::StdRng, seq::IteratorRandom, SeedableRng};
 
-use crate::env::{DiscreteActionSpace, Environment, Report};
+use crate::{
+    env::{
+        DiscreteActionSpace, DiscreteStateSpace, Environment, MaskedActionSpace, Outcome, Report,
+        Spaces, StepResult, TransitionModel,
+    },
+    space::Space,
+};
 
 /// The possible types of squares in the [`FrozenLake`] grid
 #[derive(PartialEq)]
@@ -13,6 +19,7 @@ pub enum Square {
 
 /// Actions for the [`FrozenLake`] environment, representing taking a step in a direction
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FLAction {
     Left = 0,
     Down = 1,
@@ -20,13 +27,32 @@ pub enum FLAction {
     Up = 3,
 }
 
+impl From<FLAction> for usize {
+    fn from(value: FLAction) -> Self {
+        value as usize
+    }
+}
+
+impl From<usize> for FLAction {
+    fn from(value: usize) -> Self {
+        match value {
+            0 => Self::Left,
+            1 => Self::Down,
+            2 => Self::Right,
+            3 => Self::Up,
+            _ => panic!("FLAction::from is only called with valid values [0, 3]"),
+        }
+    }
+}
+
 /// A very simple RL environment taken from Python [gymnasium](https://gymnasium.farama.org/)
 ///
 /// Intended for use with a [QTableAgent](crate::algo::q_table::QTableAgent)
 pub struct FrozenLake {
     map: [Square; 16],
     pos: usize,
-    pub report: Report,
+    rng: StdRng,
+    report: Report,
 }
 
 impl Default for FrozenLake {
@@ -59,30 +85,64 @@ impl FrozenLake {
         Self {
             map,
             pos: 0,
+            rng: StdRng::from_entropy(),
             report: Report::new(vec!["reward", "steps"]),
         }
     }
 }
 
-impl DiscreteActionSpace for FrozenLake {
-    fn actions(&self) -> Vec<Self::Action> {
+impl FrozenLake {
+    /// Get the actions that do not move off the board from `pos`
+    fn actions_at(pos: usize) -> Vec<FLAction> {
         let mut actions = Vec::with_capacity(4);
 
-        if self.pos % 4 != 0 {
+        if pos % 4 != 0 {
             actions.push(FLAction::Left)
         }
-        if self.pos < 12 {
+        if pos < 12 {
             actions.push(FLAction::Down)
         }
-        if self.pos % 4 != 3 {
+        if pos % 4 != 3 {
             actions.push(FLAction::Right)
         }
-        if self.pos > 3 {
+        if pos > 3 {
             actions.push(FLAction::Up)
         }
 
         actions
     }
+
+    /// Get the position reached by taking `action` from `pos`
+    fn move_from(pos: usize, action: FLAction) -> usize {
+        match action {
+            FLAction::Left => pos - 1,
+            FLAction::Down => pos + 4,
+            FLAction::Right => pos + 1,
+            FLAction::Up => pos - 4,
+        }
+    }
+
+    /// Get the next state and reward for arriving at `pos`, where the next state is `None` if `pos` is a hole
+    /// or the goal
+    fn arrive(&self, pos: usize) -> (Option<usize>, f32) {
+        match self.map[pos] {
+            Square::Hole => (None, -1.0),
+            Square::Goal => (None, 1.0),
+            _ => (Some(pos), -0.1),
+        }
+    }
+}
+
+impl DiscreteActionSpace for FrozenLake {
+    fn actions(&self) -> Vec<Self::Action> {
+        Self::actions_at(self.pos)
+    }
+}
+
+impl MaskedActionSpace for FrozenLake {
+    fn num_actions(&self) -> usize {
+        4
+    }
 }
 
 impl Environment for FrozenLake {
@@ -96,32 +156,35 @@ impl Environment for FrozenLake {
         }
     }
 
-    fn random_action(&self) -> Self::Action {
+    fn random_action(&mut self) -> Self::Action {
         self.actions()
             .into_iter()
-            .choose(&mut thread_rng())
+            .choose(&mut self.rng)
             .expect("There is always at least one available action in this environment")
     }
 
-    fn step(&mut self, action: Self::Action) -> (Option<Self::State>, f32) {
-        self.report.entry("steps").and_modify(|x| *x += 1.0);
+    fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
 
-        match action {
-            FLAction::Left => self.pos -= 1,
-            FLAction::Down => self.pos += 4,
-            FLAction::Right => self.pos += 1,
-            FLAction::Up => self.pos -= 4,
-        };
+    fn report(&mut self) -> Option<&mut Report> {
+        Some(&mut self.report)
+    }
 
-        let (next_state, reward) = match self.map[self.pos] {
-            Square::Hole => (None, -1.0),
-            Square::Goal => (None, 1.0),
-            _ => (Some(self.pos), -0.1),
-        };
+    fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
+        self.report.record("steps", 1.0);
 
-        self.report.entry("reward").and_modify(|x| *x += reward);
+        self.pos = Self::move_from(self.pos, action);
+        let (next_state, reward) = self.arrive(self.pos);
 
-        (next_state, reward as f32)
+        self.report.record("reward", reward as f64);
+
+        let step = StepResult::new(next_state, reward);
+        match self.map[self.pos] {
+            Square::Goal => step.with_info("success", 1.0),
+            Square::Hole => step.with_info("success", 0.0),
+            _ => step,
+        }
     }
 
     fn reset(&mut self) -> Self::State {
@@ -129,3 +192,90 @@ impl Environment for FrozenLake {
         self.pos
     }
 }
+
+impl DiscreteStateSpace for FrozenLake {
+    fn states(&self) -> Vec<Self::State> {
+        (0..self.map.len()).collect()
+    }
+}
+
+impl TransitionModel for FrozenLake {
+    /// Moves are deterministic, and holes and the goal are terminal
+    fn outcomes(&self, state: Self::State, action: Self::Action) -> Vec<Outcome<Self::State>> {
+        if matches!(self.map[state], Square::Hole | Square::Goal) {
+            return Vec::new();
+        }
+
+        let (next_state, reward) = self.arrive(Self::move_from(state, action));
+        vec![Outcome {
+            next_state,
+            reward,
+            prob: 1.0,
+        }]
+    }
+
+    fn available_actions(&self, state: Self::State) -> Vec<Self::Action> {
+        Self::actions_at(state)
+    }
+}
+
+impl Spaces for FrozenLake {
+    fn observation_space(&self) -> Space {
+        Space::discrete(self.map.len())
+    }
+
+    fn action_space(&self) -> Space {
+        Space::discrete(4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frozen_lake_transition_model() {
+        let mut env = FrozenLake::new();
+        env.reset();
+        for action in env.actions() {
+            let step = env.step(action);
+            let outcomes = env.outcomes(0, action);
+            assert_eq!(outcomes.len(), 1, "Moves are deterministic");
+            assert_eq!(
+                (outcomes[0].next_state, outcomes[0].reward),
+                (step.next_state, step.reward),
+                "Model matches environment steps"
+            );
+            env.reset();
+        }
+
+        assert!(
+            env.outcomes(5, FLAction::Left).is_empty(),
+            "Holes are terminal"
+        );
+        assert_eq!(
+            env.available_actions(15),
+            [FLAction::Left, FLAction::Up],
+            "Available actions depend on the state"
+        );
+    }
+
+    #[test]
+    fn frozen_lake_action_mask() {
+        let mut env = FrozenLake::new();
+        env.reset();
+        assert_eq!(
+            env.action_mask(),
+            [false, true, true, false],
+            "Moves off the board are masked from the start square"
+        );
+
+        let step = env.step(FLAction::Down);
+        assert!(
+            !step.info.contains_key("success"),
+            "Success only reported at the end"
+        );
+        let step = env.step(FLAction::Right);
+        assert_eq!(step.info["success"], 0.0, "Falling in a hole is a failure");
+    }
+}
@@ -1,14 +1,20 @@
 use std::collections::{HashSet, VecDeque};
 
-use rand::{seq::IteratorRandom, thread_rng, Rng};
+use burn::prelude::*;
+use rand::{rngs::StdRng, seq::IteratorRandom, Rng, SeedableRng};
 use strum::{EnumIter, FromRepr, IntoEnumIterator, VariantArray};
 
-use crate::env::{DiscreteActionSpace, Environment, Report};
+use crate::{
+    env::{DiscreteActionSpace, Environment, MaskedActionSpace, Report, Spaces, StepResult},
+    space::Space,
+    traits::ToTensor,
+};
 
 /// Position coordinates in the field with 1 unit of padding as a death zone
 type Pos = (usize, usize);
 
 #[derive(EnumIter, VariantArray, FromRepr, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Dir {
     Up = 0,
     Right = 1,
@@ -16,20 +22,62 @@ pub enum Dir {
     Left = 3,
 }
 
+impl From<usize> for Dir {
+    fn from(value: usize) -> Self {
+        Self::from_repr(value).expect("Dir::from is only called with valid values [0, 3]")
+    }
+}
+
+impl From<Dir> for usize {
+    fn from(value: Dir) -> Self {
+        value as usize
+    }
+}
+
+impl<B: Backend<IntElem = i32>> ToTensor<B, 2, Int> for Vec<Dir> {
+    fn to_tensor(self, device: &B::Device) -> Tensor<B, 2, Int> {
+        let len = self.len();
+        let data = Data::new(
+            self.into_iter().map(|x| x as i32).collect::<Vec<_>>(),
+            [len].into(),
+        );
+        Tensor::from_data(data, device).unsqueeze_dim(1)
+    }
+}
+
+/// The state of a [`GrassyField`] as an array of boolean features
+///
+/// `[facing_up, facing_right, facing_down, facing_left, food_up, food_right, food_down, food_left, danger_up, danger_right, danger_down, danger_left]`
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Features(pub [bool; 12]);
+
+/// Converts each boolean feature to `0.0` or `1.0`
+impl<B: Backend> ToTensor<B, 2, Float> for Vec<Features> {
+    fn to_tensor(self, device: &B::Device) -> Tensor<B, 2> {
+        let len = self.len();
+        let data = self
+            .into_iter()
+            .flat_map(|Features(features)| features)
+            .map(|x| x as u8 as f32)
+            .collect::<Vec<_>>();
+        Tensor::<B, 1>::from_floats(data.as_slice(), device).reshape([len, 12])
+    }
+}
+
 pub struct Snake {
     body: VecDeque<Pos>,
     dir: Dir,
 }
 
 impl Snake {
-    fn new(field_size: usize) -> Self {
-        let mut rng = thread_rng();
+    fn new(field_size: usize, rng: &mut impl Rng) -> Self {
         Self {
             body: VecDeque::from([(
                 rng.gen_range(3..(field_size - 1)),
                 rng.gen_range(3..(field_size - 1)),
             )]),
-            dir: Dir::iter().choose(&mut rng).unwrap(),
+            dir: Dir::iter().choose(rng).unwrap(),
         }
     }
 
@@ -61,7 +109,8 @@ impl Snake {
 pub struct GrassyField<const S: usize> {
     snake: Snake,
     food: Pos,
-    pub report: Report,
+    rng: StdRng,
+    report: Report,
 }
 
 impl<const S: usize> Default for GrassyField<S> {
@@ -72,9 +121,11 @@ impl<const S: usize> Default for GrassyField<S> {
 
 impl<const S: usize> GrassyField<S> {
     pub fn new() -> Self {
+        let mut rng = StdRng::from_entropy();
         Self {
-            snake: Snake::new(S),
+            snake: Snake::new(S, &mut rng),
             food: (1, 1),
+            rng,
             report: Report::new(vec!["score", "reward", "steps"]),
         }
     }
@@ -99,20 +150,15 @@ impl<const S: usize> GrassyField<S> {
             }
         }
 
-        self.food = vacant
-            .into_iter()
-            .choose(&mut thread_rng())
-            .unwrap_or((1, 1));
+        self.food = vacant.into_iter().choose(&mut self.rng).unwrap_or((1, 1));
     }
 
     fn is_in_bounds(&self, pos: Pos) -> bool {
         pos.0 >= 1 && pos.1 >= 1 && pos.0 <= S && pos.1 <= S
     }
 
-    /// Get environment state as an array of boolean features
-    ///
-    /// `[facing_up, facing_right, facing_down, facing_left, food_up, food_right, food_down, food_left, danger_up, danger_right, danger_down, danger_left]`
-    fn get_state(&self) -> [bool; 12] {
+    /// Get environment state as an array of boolean [`Features`]
+    fn get_state(&self) -> Features {
         let mut features = [false; 12];
 
         features[self.snake.dir as usize] = true;
@@ -134,7 +180,7 @@ impl<const S: usize> GrassyField<S> {
             features[8 + i] = !self.is_in_bounds(pos) || self.snake.body.contains(&pos);
         }
 
-        features
+        Features(features)
     }
 }
 
@@ -144,8 +190,14 @@ impl<const S: usize> DiscreteActionSpace for GrassyField<S> {
     }
 }
 
+impl<const S: usize> MaskedActionSpace for GrassyField<S> {
+    fn num_actions(&self) -> usize {
+        Dir::VARIANTS.len()
+    }
+}
+
 impl<const S: usize> Environment for GrassyField<S> {
-    type State = [bool; 12];
+    type State = Features;
     type Action = Dir;
 
     fn is_active(&self) -> bool {
@@ -154,18 +206,26 @@ impl<const S: usize> Environment for GrassyField<S> {
             && self.snake.len() < S * S
     }
 
-    fn random_action(&self) -> Self::Action {
-        Dir::iter().choose(&mut thread_rng()).unwrap()
+    fn random_action(&mut self) -> Self::Action {
+        Dir::iter().choose(&mut self.rng).unwrap()
+    }
+
+    fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn report(&mut self) -> Option<&mut Report> {
+        Some(&mut self.report)
     }
 
     fn reset(&mut self) -> Self::State {
-        self.snake = Snake::new(S);
+        self.snake = Snake::new(S, &mut self.rng);
         self.spawn_food();
         self.get_state()
     }
 
-    fn step(&mut self, action: Self::Action) -> (Option<Self::State>, f32) {
-        self.report.entry("steps").and_modify(|x| *x += 1.0);
+    fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
+        self.report.record("steps", 1.0);
         let mut reward = -0.01;
 
         let head = self.snake.head();
@@ -174,7 +234,7 @@ impl<const S: usize> Environment for GrassyField<S> {
         self.snake.body.push_front(new_head);
 
         if self.snake.head() == self.food {
-            self.report.entry("score").and_modify(|x| *x += 1.0);
+            self.report.record("score", 1.0);
             self.spawn_food();
             reward = 1.0;
         } else {
@@ -188,8 +248,19 @@ impl<const S: usize> Environment for GrassyField<S> {
             None
         };
 
-        self.report.entry("reward").and_modify(|x| *x += reward);
-        (next_state, reward as f32)
+        self.report.record("reward", reward);
+        StepResult::new(next_state, reward as f32)
+    }
+}
+
+impl<const S: usize> Spaces for GrassyField<S> {
+    /// 12 boolean features describing the snake's direction, the direction of the food, and adjacent dangers
+    fn observation_space(&self) -> Space {
+        Space::multi_discrete(vec![2; 12])
+    }
+
+    fn action_space(&self) -> Space {
+        Space::discrete(Dir::VARIANTS.len())
     }
 }
 
@@ -203,6 +274,13 @@ fn step_dir(pos: Pos, dir: Dir) -> Pos {
 
 #[cfg(test)]
 mod tests {
+    use crate::{
+        algo::{
+            ppo::{PPOAgent, PPOAgentConfig},
+            tests::{ActorCritic, B, DEVICE},
+        },
+        traits::Agent,
+    };
 
     use super::*;
 
@@ -216,6 +294,7 @@ mod tests {
         let mut env = GrassyField::<6> {
             snake,
             food: (2, 1),
+            rng: StdRng::seed_from_u64(0),
             report: Report::new(vec!["score", "reward", "steps"]),
         };
 
@@ -237,7 +316,56 @@ mod tests {
         assert_ne!(env.food, (2, 1), "Food was moved after being eaten");
 
         let report = env.report.take();
-        assert_eq!(report["score"], 1.0, "Report score correct");
-        assert_eq!(report["steps"], 5.0, "Report steps correct");
+        assert_eq!(report["score"].value(), 1.0, "Report score correct");
+        assert_eq!(report["steps"].value(), 5.0, "Report steps correct");
+    }
+
+    #[test]
+    fn grassy_field_to_tensor() {
+        let mut state = [false; 12];
+        state[1] = true;
+        state[11] = true;
+        let states: Tensor<B, 2> = vec![Features(state), Features([true; 12])].to_tensor(&DEVICE);
+        assert_eq!(states.dims(), [2, 12], "States are batched");
+        assert_eq!(
+            states.into_data().value[..12],
+            [0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0],
+            "Features are converted to floats"
+        );
+
+        let actions: Tensor<B, 2, Int> = Dir::VARIANTS.to_vec().to_tensor(&DEVICE);
+        assert_eq!(
+            actions.into_data().value,
+            [0, 1, 2, 3],
+            "Actions are converted to their indices"
+        );
+        assert_eq!(
+            Dir::from(3usize),
+            Dir::Left,
+            "Actions are converted from indices"
+        );
+    }
+
+    #[test]
+    fn ppo_trains_on_grassy_field() {
+        let config = PPOAgentConfig {
+            n_steps: 32,
+            batch_size: 8,
+            seed: Some(0),
+            ..Default::default()
+        };
+        let mut agent = PPOAgent::new(ActorCritic::new(12, 4), config, &DEVICE);
+        let mut env = GrassyField::<6>::new();
+        env.seed(0);
+
+        for _ in 0..50 {
+            agent.go(&mut env);
+        }
+
+        let report = agent.report().unwrap().take();
+        assert!(
+            report["policy_loss"].count() > 0,
+            "Agent learned from full rollouts"
+        );
     }
 }
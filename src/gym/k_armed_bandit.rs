@@ -1,12 +1,20 @@
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use rand_distr::{Distribution, Normal};
 
-use crate::env::{DiscreteActionSpace, Environment, Report};
+use crate::{
+    env::{
+        DiscreteActionSpace, DiscreteStateSpace, Environment, MaskedActionSpace, Outcome, Report,
+        Spaces, StepResult, TransitionModel,
+    },
+    space::Space,
+};
 
 /// The K-armed bandit problem is a simple environment with 1 state and K actions. Each action has a reward
 /// that is sampled from a normal distribution with a standard deviation of 1. The means of the reward distributions are sampled
 /// from another normal distribution upon initialization. The goal is to find the action with the highest expected reward.
 ///
+/// Episodes have no terminal state and are instead truncated after a fixed number of steps.
+///
 /// The environment can be stationary or non-stationary. In the stationary case, the reward
 /// distribution of each action remains constant throughout the episode. In the non-stationary
 /// case, the reward distribution of each action changes slightly after each step.
@@ -16,22 +24,25 @@ pub struct KArmedBandit<const K: usize> {
     step_limit: usize,
     is_stationary: bool,
     rewards: Vec<f32>,
-    pub report: Report,
+    rng: StdRng,
+    report: Report,
 }
 
 impl<const K: usize> KArmedBandit<K> {
     /// Initialize a new K-armed bandit environment
     ///
     /// ### Arguments
-    /// - `step_limit` - The number of steps before the episode is terminated
+    /// - `step_limit` - The number of steps before the episode is truncated
     /// - `stationary` - Whether the environment is stationary or not
     pub fn new(step_limit: usize, stationary: bool) -> Self {
+        let mut rng = StdRng::from_entropy();
         Self {
-            arms: generate_arms(),
+            arms: generate_arms(&mut rng),
             steps: 0,
             step_limit,
             is_stationary: stationary,
             rewards: Vec::with_capacity(step_limit),
+            rng,
             report: Report::new(vec!["reward"]),
         }
     }
@@ -45,43 +56,48 @@ impl<const K: usize> Environment for KArmedBandit<K> {
     type State = ();
     type Action = usize;
 
-    fn step(&mut self, action: Self::Action) -> (Option<Self::State>, f32) {
+    fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
         assert!(action < K, "Invalid action: {}", action);
-        let reward = self.arms[action].sample(&mut rand::thread_rng());
-        self.report
-            .entry("reward")
-            .and_modify(|x| *x += reward as f64);
+        let reward = self.arms[action].sample(&mut self.rng);
+        self.report.record("reward", reward as f64);
         self.steps += 1;
         self.rewards.push(reward);
 
         if !self.is_stationary {
-            let mut rng = rand::thread_rng();
+            let rng = &mut self.rng;
             let dist = Normal::<f32>::new(0.0, 0.01).unwrap();
             self.arms = self.arms.map(|arm| {
-                let mean = arm.mean() + dist.sample(&mut rng);
+                let mean = arm.mean() + dist.sample(rng);
                 Normal::new(mean, 1.0).unwrap()
             });
         }
 
-        let next_state = if self.steps < self.step_limit {
-            Some(())
+        if self.steps < self.step_limit {
+            StepResult::new(Some(()), reward)
         } else {
-            None
-        };
-
-        (next_state, reward)
+            StepResult::truncated((), reward)
+        }
     }
 
     fn reset(&mut self) -> Self::State {
         self.steps = 0;
-        self.arms = generate_arms();
+        self.arms = generate_arms(&mut self.rng);
         self.rewards.clear();
+    }
 
-        ()
+    fn random_action(&mut self) -> Self::Action {
+        self.rng.gen_range(0..K)
     }
 
-    fn random_action(&self) -> Self::Action {
-        rand::thread_rng().gen_range(0..K)
+    /// Seed the environment
+    ///
+    /// New arms are generated from the seeded generator on the next call to [`reset`](Environment::reset)
+    fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn report(&mut self) -> Option<&mut Report> {
+        Some(&mut self.report)
     }
 }
 
@@ -91,12 +107,46 @@ impl<const K: usize> DiscreteActionSpace for KArmedBandit<K> {
     }
 }
 
-fn generate_arms<const K: usize>() -> [Normal<f32>; K] {
-    let mut rng = rand::thread_rng();
+impl<const K: usize> MaskedActionSpace for KArmedBandit<K> {
+    fn num_actions(&self) -> usize {
+        K
+    }
+}
+
+impl<const K: usize> DiscreteStateSpace for KArmedBandit<K> {
+    fn states(&self) -> Vec<Self::State> {
+        vec![()]
+    }
+}
+
+impl<const K: usize> TransitionModel for KArmedBandit<K> {
+    /// The bandit always stays in its single state, and the reward is the mean of the chosen arm's current
+    /// reward distribution
+    fn outcomes(&self, _state: Self::State, action: Self::Action) -> Vec<Outcome<Self::State>> {
+        vec![Outcome {
+            next_state: Some(()),
+            reward: self.arms[action].mean(),
+            prob: 1.0,
+        }]
+    }
+}
+
+impl<const K: usize> Spaces for KArmedBandit<K> {
+    /// The bandit has a single state
+    fn observation_space(&self) -> Space {
+        Space::discrete(1)
+    }
+
+    fn action_space(&self) -> Space {
+        Space::discrete(K)
+    }
+}
+
+fn generate_arms<const K: usize>(rng: &mut impl Rng) -> [Normal<f32>; K] {
     let dist = Normal::<f32>::new(0.0, 1.0).unwrap();
 
     std::array::from_fn(|_| {
-        let mean = dist.sample(&mut rng);
+        let mean = dist.sample(rng);
         Normal::new(mean, 1.0).unwrap()
     })
 }
@@ -113,20 +163,53 @@ mod tests {
         let action = env.random_action();
         assert!(action < 3, "Random action is valid");
 
-        let reward = env.step(action).1;
+        let reward = env.step(action).reward;
         assert!(reward.is_finite(), "Reward is finite");
 
         for _ in 0..9 {
-            env.step(env.random_action());
+            let action = env.random_action();
+            env.step(action);
         }
 
-        assert_eq!(
-            env.step(env.random_action()).0,
-            None,
-            "Step limit is reached"
+        let action = env.random_action();
+        let step = env.step(action);
+        assert!(
+            step.truncated && !step.is_terminated(),
+            "Step limit truncates the episode"
         );
 
         let state = env.reset();
         assert_eq!(state, (), "Reset returns unit");
     }
+
+    #[test]
+    fn k_armed_bandit_outcome_probabilities() {
+        let env = KArmedBandit::<3>::new(10, false);
+        for state in env.states() {
+            for action in env.available_actions(state) {
+                let total = env
+                    .outcomes(state, action)
+                    .iter()
+                    .map(|outcome| outcome.prob)
+                    .sum::<f32>();
+                assert!((total - 1.0).abs() < 1e-6, "Outcome probabilities sum to 1");
+            }
+        }
+    }
+
+    #[test]
+    fn k_armed_bandit_seeded() {
+        let mut env1 = KArmedBandit::<3>::new(10, false);
+        let mut env2 = KArmedBandit::<3>::new(10, false);
+        env1.seed(3);
+        env2.seed(3);
+        env1.reset();
+        env2.reset();
+
+        for _ in 0..10 {
+            let action = env1.random_action();
+            assert_eq!(action, env2.random_action(), "Random actions match");
+            assert_eq!(env1.step(action), env2.step(action), "Steps match");
+        }
+    }
 }
@@ -2,10 +2,12 @@ pub mod cart_pole;
 pub mod frozen_lake;
 pub mod grassy_field;
 pub mod k_armed_bandit;
+pub mod pendulum;
 pub mod windy_gridworld;
 
 pub use cart_pole::CartPole;
 pub use frozen_lake::FrozenLake;
 pub use grassy_field::GrassyField;
 pub use k_armed_bandit::KArmedBandit;
+pub use pendulum::Pendulum;
 pub use windy_gridworld::WindyGridworld;
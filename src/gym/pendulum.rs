@@ -0,0 +1,167 @@
+use std::f32::consts::PI;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    env::{Environment, Report, Spaces, StepResult},
+    space::Space,
+};
+
+const MAX_SPEED: f32 = 8.0;
+const MAX_TORQUE: f32 = 2.0;
+const DT: f32 = 0.05;
+const G: f32 = 10.0;
+const MASS: f32 = 1.0;
+const LENGTH: f32 = 1.0;
+
+/// Wrap an angle to `[-π, π)`
+fn angle_normalize(theta: f32) -> f32 {
+    (theta + PI).rem_euclid(2.0 * PI) - PI
+}
+
+/// The classic inverted pendulum swing-up problem, a continuous control environment taken from Python
+/// [gymnasium](https://gymnasium.farama.org/)
+///
+/// The pendulum starts at a random angle and the agent applies a torque in `[-2, 2]` to its free end in order to
+/// swing it upright and keep it there. Each step costs θ<sup>2</sup> + 0.1θ̇<sup>2</sup> + 0.001u<sup>2</sup>, where
+/// θ is the angle from upright, so rewards are at most `0.0`. Episodes never terminate and are truncated after
+/// `max_steps` steps.
+///
+/// Intended for use with continuous control agents such as the [SACAgent](crate::algo::sac::SACAgent)
+pub struct Pendulum {
+    theta: f32,
+    theta_dot: f32,
+    steps: usize,
+    max_steps: usize,
+    rng: StdRng,
+    report: Report,
+}
+
+impl Default for Pendulum {
+    fn default() -> Self {
+        Self::new(200)
+    }
+}
+
+impl Pendulum {
+    /// Create a new `Pendulum` whose episodes are truncated after `max_steps` steps
+    pub fn new(max_steps: usize) -> Self {
+        Self {
+            theta: 0.0,
+            theta_dot: 0.0,
+            steps: 0,
+            max_steps,
+            rng: StdRng::from_entropy(),
+            report: Report::new(vec!["reward", "steps"]),
+        }
+    }
+
+    /// The cosine and sine of the angle, and the angular velocity
+    fn observation(&self) -> [f32; 3] {
+        [self.theta.cos(), self.theta.sin(), self.theta_dot]
+    }
+}
+
+impl Environment for Pendulum {
+    /// `[cos θ, sin θ, θ̇]`
+    type State = [f32; 3];
+    /// The torque applied to the pendulum, which is clamped to `[-2, 2]`
+    type Action = [f32; 1];
+
+    fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
+        let torque = action[0].clamp(-MAX_TORQUE, MAX_TORQUE);
+        let cost = angle_normalize(self.theta).powi(2)
+            + 0.1 * self.theta_dot.powi(2)
+            + 0.001 * torque.powi(2);
+
+        self.theta_dot = (self.theta_dot
+            + (3.0 * G / (2.0 * LENGTH) * self.theta.sin()
+                + 3.0 / (MASS * LENGTH.powi(2)) * torque)
+                * DT)
+            .clamp(-MAX_SPEED, MAX_SPEED);
+        self.theta += self.theta_dot * DT;
+        self.steps += 1;
+
+        self.report.record("reward", -cost as f64);
+        self.report.record("steps", 1.0);
+
+        if self.steps >= self.max_steps {
+            StepResult::truncated(self.observation(), -cost)
+        } else {
+            StepResult::new(Some(self.observation()), -cost)
+        }
+    }
+
+    fn reset(&mut self) -> Self::State {
+        self.theta = self.rng.gen_range(-PI..PI);
+        self.theta_dot = self.rng.gen_range(-1.0..1.0);
+        self.steps = 0;
+        self.observation()
+    }
+
+    fn random_action(&mut self) -> Self::Action {
+        [self.rng.gen_range(-MAX_TORQUE..=MAX_TORQUE)]
+    }
+
+    fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn report(&mut self) -> Option<&mut Report> {
+        Some(&mut self.report)
+    }
+}
+
+impl Spaces for Pendulum {
+    fn observation_space(&self) -> Space {
+        Space::bounded_elementwise(
+            &[3],
+            vec![-1.0, -1.0, -MAX_SPEED],
+            vec![1.0, 1.0, MAX_SPEED],
+        )
+    }
+
+    fn action_space(&self) -> Space {
+        Space::bounded(&[1], -MAX_TORQUE, MAX_TORQUE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::space::Point;
+
+    use super::*;
+
+    #[test]
+    fn pendulum_functional() {
+        let mut env = Pendulum::new(3);
+        env.seed(0);
+        let state = env.reset();
+        assert!(
+            env.observation_space()
+                .contains(&Point::Box(state.to_vec())),
+            "Initial state is in the observation space"
+        );
+
+        env.theta = 0.0;
+        env.theta_dot = 0.0;
+        let step = env.step([0.0]);
+        assert_eq!(
+            (step.next_state, step.reward),
+            (Some([1.0, 0.0, 0.0]), 0.0),
+            "Upright pendulum is balanced at no cost"
+        );
+
+        let step = env.step([5.0]);
+        assert_eq!(
+            step.next_state.unwrap()[2],
+            3.0 * MAX_TORQUE * DT,
+            "Torque is clamped"
+        );
+        assert!(step.reward < 0.0, "Torque is costly");
+
+        let action = env.random_action();
+        let step = env.step(action);
+        assert!(step.truncated, "Episode is truncated after max steps");
+    }
+}
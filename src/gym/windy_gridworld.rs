@@ -1,11 +1,18 @@
-use rand::seq::IteratorRandom;
+use rand::{rngs::StdRng, seq::IteratorRandom, SeedableRng};
 use strum::{EnumIter, VariantArray};
 
-use crate::env::{DiscreteActionSpace, Environment, Report};
+use crate::{
+    env::{
+        DiscreteActionSpace, DiscreteStateSpace, Environment, MaskedActionSpace, Outcome, Report,
+        Spaces, StepResult, TransitionModel,
+    },
+    space::Space,
+};
 
 pub type Pos = (i32, i32);
 
 #[derive(EnumIter, VariantArray, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Action {
     Up,
     Left,
@@ -18,11 +25,18 @@ pub enum Action {
     Stay,
 }
 
+impl From<Action> for usize {
+    fn from(value: Action) -> Self {
+        value as usize
+    }
+}
+
 pub struct WindyGridworld {
     pos: Pos,
     goal: Pos,
     currents: [i32; 10],
-    pub report: Report,
+    rng: StdRng,
+    report: Report,
 }
 
 impl WindyGridworld {
@@ -31,20 +45,17 @@ impl WindyGridworld {
             pos: (3, 0),
             goal: (3, 7),
             currents: [0, 0, 0, 1, 1, 1, 2, 2, 1, 0],
+            rng: StdRng::from_entropy(),
             report: Report::new(vec!["steps"]),
         }
     }
 }
 
-impl Environment for WindyGridworld {
-    type State = Pos;
-    type Action = Action;
-
-    fn step(&mut self, action: Self::Action) -> (Option<Self::State>, f32) {
-        self.report.entry("steps").and_modify(|x| *x += 1.0);
-
-        let wind = self.currents[self.pos.0 as usize];
-        self.pos.1 += wind;
+impl WindyGridworld {
+    /// Get the position reached by taking `action` from `pos`
+    fn move_from(&self, mut pos: Pos, action: Action) -> Pos {
+        let wind = self.currents[pos.0 as usize];
+        pos.1 += wind;
 
         let change = match action {
             Action::Up => (0, -1),
@@ -58,28 +69,52 @@ impl Environment for WindyGridworld {
             Action::Stay => (0, 0),
         };
 
-        self.pos.0 += change.0;
-        self.pos.1 += change.1;
-        self.pos = (self.pos.0.clamp(0, 9), self.pos.1.clamp(0, 7));
+        pos.0 += change.0;
+        pos.1 += change.1;
+        (pos.0.clamp(0, 9), pos.1.clamp(0, 7))
+    }
 
-        if self.pos == self.goal {
+    /// Get the next state and reward for arriving at `pos`, where the next state is `None` if `pos` is the goal
+    fn arrive(&self, pos: Pos) -> (Option<Pos>, f32) {
+        if pos == self.goal {
             (None, 0.0)
         } else {
-            (Some(self.pos), -1.0)
+            (Some(pos), -1.0)
         }
     }
+}
+
+impl Environment for WindyGridworld {
+    type State = Pos;
+    type Action = Action;
+
+    fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
+        self.report.record("steps", 1.0);
+
+        self.pos = self.move_from(self.pos, action);
+        let (next_state, reward) = self.arrive(self.pos);
+        StepResult::new(next_state, reward)
+    }
 
     fn reset(&mut self) -> Self::State {
         self.pos = (3, 0);
         self.pos
     }
 
-    fn random_action(&self) -> Self::Action {
+    fn random_action(&mut self) -> Self::Action {
         self.actions()
             .into_iter()
-            .choose(&mut rand::thread_rng())
+            .choose(&mut self.rng)
             .expect("Iterator is not empty")
     }
+
+    fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn report(&mut self) -> Option<&mut Report> {
+        Some(&mut self.report)
+    }
 }
 
 impl DiscreteActionSpace for WindyGridworld {
@@ -87,3 +122,67 @@ impl DiscreteActionSpace for WindyGridworld {
         Action::VARIANTS.to_vec()
     }
 }
+
+impl MaskedActionSpace for WindyGridworld {
+    fn num_actions(&self) -> usize {
+        Action::VARIANTS.len()
+    }
+}
+
+impl DiscreteStateSpace for WindyGridworld {
+    fn states(&self) -> Vec<Self::State> {
+        (0..10).flat_map(|x| (0..8).map(move |y| (x, y))).collect()
+    }
+}
+
+impl TransitionModel for WindyGridworld {
+    /// Moves are deterministic, and the goal is terminal
+    fn outcomes(&self, state: Self::State, action: Self::Action) -> Vec<Outcome<Self::State>> {
+        if state == self.goal {
+            return Vec::new();
+        }
+
+        let (next_state, reward) = self.arrive(self.move_from(state, action));
+        vec![Outcome {
+            next_state,
+            reward,
+            prob: 1.0,
+        }]
+    }
+}
+
+impl Spaces for WindyGridworld {
+    /// The `(x, y)` position in the 10x8 grid
+    fn observation_space(&self) -> Space {
+        Space::multi_discrete(vec![10, 8])
+    }
+
+    fn action_space(&self) -> Space {
+        Space::discrete(Action::VARIANTS.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windy_gridworld_outcome_probabilities() {
+        let env = WindyGridworld::new();
+        for state in env.states() {
+            for action in env.available_actions(state) {
+                let outcomes = env.outcomes(state, action);
+                if state == env.goal {
+                    assert!(outcomes.is_empty(), "Goal is terminal");
+                    continue;
+                }
+
+                let total = outcomes.iter().map(|outcome| outcome.prob).sum::<f32>();
+                assert!(
+                    (total - 1.0).abs() < 1e-6,
+                    "Outcome probabilities of {state:?} sum to 1"
+                );
+            }
+        }
+    }
+}
@@ -16,9 +16,21 @@ pub mod exploration;
 /// Experience replay
 pub mod memory;
 
+/// Saving and loading agents
+pub mod persist;
+
+/// Action and observation space descriptions
+pub mod space;
+
 /// Library traits
 pub mod traits;
 
+/// Multi-agent environments
+pub mod multi_agent;
+
+/// Neural network utilities
+pub mod nn;
+
 /// Probabilistic models
 mod prob;
 
@@ -30,4 +42,13 @@ pub mod viz;
 #[cfg(feature = "gym")]
 pub mod gym;
 
+/// Generic training loop
+pub mod trainer;
+
+/// Vectorized environments
+pub mod vec_env;
+
+/// Composable environment wrappers
+pub mod wrappers;
+
 mod util;
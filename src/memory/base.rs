@@ -1,4 +1,4 @@
-use rand::{seq::SliceRandom, thread_rng};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 
 use crate::{ds::RingBuffer, env::Environment};
 
@@ -15,8 +15,18 @@ use super::{Exp, ExpBatch};
 /// ### Fields:
 /// - `memory` - A `RingBuffer` that stores the experiences
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "Exp<E>: serde::Serialize",
+        deserialize = "Exp<E>: serde::Deserialize<'de>"
+    ))
+)]
 pub struct ReplayMemory<E: Environment> {
     memory: RingBuffer<Exp<E>>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "StdRng::from_entropy"))]
+    rng: StdRng,
     pub batch_size: usize,
 }
 
@@ -25,10 +35,16 @@ impl<E: Environment> ReplayMemory<E> {
     pub fn new(capacity: usize, batch_size: usize) -> Self {
         Self {
             memory: RingBuffer::<Exp<E>>::new(capacity),
+            rng: StdRng::from_entropy(),
             batch_size,
         }
     }
 
+    /// Seed the random number generator used for sampling
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
     /// Add a new experience to the memory
     pub fn push(&mut self, exp: Exp<E>) {
         self.memory.push(exp);
@@ -39,12 +55,12 @@ impl<E: Environment> ReplayMemory<E> {
     /// ### Returns
     /// - `None` if there are less experiences stored than can fill a batch
     /// - `Some(experiences)` otherwise
-    pub fn sample(&self) -> Option<Vec<&Exp<E>>> {
+    pub fn sample(&mut self) -> Option<Vec<&Exp<E>>> {
         if self.batch_size <= self.memory.len() {
             Some(
                 self.memory
                     .view()
-                    .choose_multiple(&mut thread_rng(), self.batch_size)
+                    .choose_multiple(&mut self.rng, self.batch_size)
                     .collect(),
             )
         } else {
@@ -57,12 +73,12 @@ impl<E: Environment> ReplayMemory<E> {
     /// ### Returns
     /// - `None` if there are less experiences stored than can fill a batch
     /// - `Some(experiences)` otherwise
-    pub fn sample_zipped(&self) -> Option<ExpBatch<E>> {
+    pub fn sample_zipped(&mut self) -> Option<ExpBatch<E>> {
         if self.batch_size <= self.memory.len() {
             let experiences = self
                 .memory
                 .view()
-                .choose_multiple(&mut thread_rng(), self.batch_size)
+                .choose_multiple(&mut self.rng, self.batch_size)
                 .cloned();
             let batch = ExpBatch::from_iter(experiences, self.batch_size);
             Some(batch)
@@ -105,4 +121,47 @@ mod tests {
             "sample_zipped works"
         );
     }
+
+    #[test]
+    fn replay_memory_seeded() {
+        let mut memory1 = ReplayMemory::new(16, 4);
+        let mut memory2 = ReplayMemory::new(16, 4);
+        memory1.seed(42);
+        memory2.seed(42);
+
+        for exp in create_mock_exp_vec(16) {
+            memory1.push(exp.clone());
+            memory2.push(exp);
+        }
+
+        for _ in 0..4 {
+            assert_eq!(
+                memory1.sample_zipped().unwrap().states,
+                memory2.sample_zipped().unwrap().states,
+                "samples are identical given the same seed"
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn replay_memory_serde() {
+        let mut memory = ReplayMemory::new(16, 4);
+        for exp in create_mock_exp_vec(12) {
+            memory.push(exp);
+        }
+
+        let bytes = bincode::serialize(&memory).unwrap();
+        let mut loaded: ReplayMemory<crate::env::tests::MockEnv> =
+            bincode::deserialize(&bytes).unwrap();
+        memory.seed(0);
+        loaded.seed(0);
+
+        assert_eq!(loaded.batch_size, 4, "Batch size restored");
+        assert_eq!(
+            loaded.sample_zipped().unwrap().states,
+            memory.sample_zipped().unwrap().states,
+            "Experiences restored"
+        );
+    }
 }
@@ -0,0 +1,108 @@
+use burn::prelude::*;
+
+use super::ExpBatch;
+use crate::{env::Environment, traits::ToTensor};
+
+/// Compute the bootstrapped Bellman targets r + γ<sup>k</sup>V(s′) of a batch of experiences, where k is the number
+/// of steps between each state and next state
+///
+/// `next_values` is called with the non-terminal next states and their action masks, in batch order, and returns
+/// their values as a tensor of shape `[next_states, 1]`. Terminal experiences are not bootstrapped, while truncated
+/// experiences keep their final state and are bootstrapped like any other.
+///
+/// **Returns** the targets as a tensor of shape `[batch_size, 1]`
+pub(crate) fn bellman_targets<B, E, const D: usize>(
+    batch: &ExpBatch<E>,
+    gamma: f32,
+    device: &B::Device,
+    next_values: impl FnOnce(Tensor<B, D>, &[Option<Vec<bool>>]) -> Tensor<B, 2>,
+) -> Tensor<B, 2>
+where
+    B: Backend<FloatElem = f32>,
+    E: Environment,
+    Vec<E::State>: ToTensor<B, D, Float>,
+{
+    let batch_size = batch.next_states.len();
+    let mut next_states = Vec::with_capacity(batch_size);
+    let mut next_masks = Vec::with_capacity(batch_size);
+    let mut rows = Vec::with_capacity(batch_size);
+    let mut discounts = Vec::with_capacity(batch_size);
+    for ((next_state, mask), &steps) in batch
+        .next_states
+        .iter()
+        .zip(&batch.next_masks)
+        .zip(&batch.steps)
+    {
+        // Terminal experiences have a discount of zero, so the row they select is irrelevant
+        match next_state {
+            Some(next_state) => {
+                rows.push(next_states.len() as i32);
+                next_states.push(next_state.clone());
+                next_masks.push(mask.clone());
+                discounts.push(gamma.powi(steps as i32));
+            }
+            None => {
+                rows.push(0);
+                discounts.push(0.0);
+            }
+        }
+    }
+
+    let rewards: Tensor<B, 1> = batch.rewards.clone().to_tensor(device);
+    let rewards = rewards.unsqueeze_dim(1);
+    if next_states.is_empty() {
+        return rewards;
+    }
+
+    // Line the values of the non-terminal next states up with the experiences they belong to
+    let next_values = next_values(next_states.to_tensor(device), &next_masks)
+        .select(0, Tensor::from_ints(rows.as_slice(), device));
+    let discounts: Tensor<B, 1> = discounts.to_tensor(device);
+
+    rewards + next_values * discounts.unsqueeze_dim(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use burn::backend::{ndarray::NdArrayDevice, NdArray as B};
+
+    use crate::{memory::Exp, wrappers::tests::CounterEnv};
+
+    use super::*;
+
+    fn exp(reward: f32, next_state: Option<f32>, steps: usize) -> Exp<CounterEnv> {
+        Exp {
+            state: [0.0],
+            action: 0.0,
+            reward,
+            next_state: next_state.map(|x| [x]),
+            next_mask: None,
+            steps,
+        }
+    }
+
+    #[test]
+    fn bellman_targets_functional() {
+        let device = NdArrayDevice::Cpu;
+        let batch = ExpBatch::from_iter(
+            [
+                exp(1.0, None, 1),
+                exp(1.0, Some(2.0), 1),
+                exp(0.0, Some(3.0), 2),
+            ],
+            3,
+        );
+
+        let targets: Tensor<B, 2> =
+            bellman_targets(&batch, 0.5, &device, |next_states: Tensor<B, 2>, masks| {
+                assert_eq!(masks.len(), 2, "Only non-terminal next states are valued");
+                next_states * 10.0
+            });
+
+        assert_eq!(
+            targets.into_data().value,
+            [1.0, 11.0, 7.5],
+            "Terminal experiences are not bootstrapped, and n-step experiences are discounted by γⁿ"
+        );
+    }
+}
@@ -3,15 +3,35 @@ use std::fmt::Debug;
 use crate::env::Environment;
 
 /// Represents a single experience or transition in the environment
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "E::State: serde::Serialize, E::Action: serde::Serialize",
+        deserialize = "E::State: serde::Deserialize<'de>, E::Action: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Exp<E: Environment> {
     /// The state of the environment before taking the action
     pub state: E::State,
     /// The action taken in the given state
     pub action: E::Action,
-    /// The reward received after taking the action
+    /// The reward received after taking the action, or the discounted return over `steps` steps if aggregated by
+    /// [NStep](super::NStep)
     pub reward: f32,
     /// The state of the environment after the action is taken, or if terminal, `None`
+    ///
+    /// This is only `None` if a terminal state was reached. If the episode was [truncated](crate::env::StepResult),
+    /// the final state is kept so that its value can still be bootstrapped.
     pub next_state: Option<E::State>,
+    /// The legal actions in the next state as a mask over action indices (see [MaskedActionSpace](crate::env::MaskedActionSpace)),
+    /// or `None` if every action is legal or there is no next state
+    pub next_mask: Option<Vec<bool>>,
+    /// The number of environment steps between `state` and `next_state`, so the next state's value is discounted
+    /// by γ<sup>steps</sup>
+    ///
+    /// This is `1` unless the experience was aggregated by [NStep](super::NStep)
+    pub steps: usize,
 }
 
 impl<E: Environment> Clone for Exp<E> {
@@ -21,6 +41,8 @@ impl<E: Environment> Clone for Exp<E> {
             action: self.action.clone(),
             reward: self.reward,
             next_state: self.next_state.clone(),
+            next_mask: self.next_mask.clone(),
+            steps: self.steps,
         }
     }
 }
@@ -32,6 +54,8 @@ impl<E: Environment> Debug for Exp<E> {
             .field("action", &self.action)
             .field("reward", &self.reward)
             .field("next_state", &self.next_state)
+            .field("next_mask", &self.next_mask)
+            .field("steps", &self.steps)
             .finish()
     }
 }
@@ -46,7 +70,14 @@ pub struct ExpBatch<E: Environment> {
     /// The reward received after taking the action
     pub rewards: Vec<f32>,
     /// The state of the environment after the action is taken, or if terminal, `None`
+    ///
+    /// Truncated experiences keep their final state, so only terminal experiences are excluded from bootstrapping
     pub next_states: Vec<Option<E::State>>,
+    /// The legal actions in the next state as a mask over action indices, or `None` if every action is legal
+    /// or there is no next state
+    pub next_masks: Vec<Option<Vec<bool>>>,
+    /// The number of environment steps between each state and next state
+    pub steps: Vec<usize>,
 }
 
 impl<E: Environment> ExpBatch<E> {
@@ -57,6 +88,8 @@ impl<E: Environment> ExpBatch<E> {
             actions: Vec::with_capacity(batch_size),
             rewards: Vec::with_capacity(batch_size),
             next_states: Vec::with_capacity(batch_size),
+            next_masks: Vec::with_capacity(batch_size),
+            steps: Vec::with_capacity(batch_size),
         };
 
         iter.into_iter().fold(batch, |mut b, e| {
@@ -64,6 +97,8 @@ impl<E: Environment> ExpBatch<E> {
             b.actions.push(e.action.clone());
             b.rewards.push(e.reward);
             b.next_states.push(e.next_state.clone());
+            b.next_masks.push(e.next_mask.clone());
+            b.steps.push(e.steps);
             b
         })
     }
@@ -83,12 +118,16 @@ pub(crate) mod tests {
             action: 1,
             reward: 1.0,
             next_state: Some(1),
+            next_mask: Some(vec![true, false]),
+            steps: 1,
         };
         let exp2 = Exp {
             state: 1,
             action: 2,
             reward: 0.0,
             next_state: None,
+            next_mask: None,
+            steps: 3,
         };
         [exp1, exp2]
     }
@@ -99,7 +138,9 @@ pub(crate) mod tests {
                 state: i,
                 action: i + 1,
                 next_state: Some(i + 1),
+                next_mask: None,
                 reward: 1.0,
+                steps: 1,
             })
             .collect()
     }
@@ -117,5 +158,11 @@ pub(crate) mod tests {
             [Some(1), None],
             "Next states constructed correctly"
         );
+        assert_eq!(
+            batch.next_masks,
+            [Some(vec![true, false]), None],
+            "Next masks constructed correctly"
+        );
+        assert_eq!(batch.steps, [1, 3], "Steps constructed correctly");
     }
 }
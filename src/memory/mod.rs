@@ -1,15 +1,37 @@
 mod base;
+mod bellman;
 mod exp;
+mod n_step;
 mod prioritized;
 
 pub use base::ReplayMemory;
+pub(crate) use bellman::bellman_targets;
 pub use exp::*;
+pub use n_step::NStep;
 pub use prioritized::PrioritizedReplayMemory;
 
 use crate::env::Environment;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "Exp<E>: serde::Serialize",
+        deserialize = "Exp<E>: serde::Deserialize<'de>"
+    ))
+)]
 pub(crate) enum Memory<E: Environment> {
     Base(ReplayMemory<E>),
     Prioritized(PrioritizedReplayMemory<E>),
 }
+
+impl<E: Environment> Memory<E> {
+    /// Add a new experience to the underlying memory
+    pub(crate) fn push(&mut self, exp: Exp<E>) {
+        match self {
+            Memory::Base(memory) => memory.push(exp),
+            Memory::Prioritized(memory) => memory.push(exp),
+        }
+    }
+}
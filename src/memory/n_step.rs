@@ -0,0 +1,150 @@
+use std::collections::VecDeque;
+
+use crate::env::Environment;
+
+use super::Exp;
+
+/// Aggregates consecutive experiences of an episode into n-step experiences
+///
+/// Each emitted experience starts at a state `s`<sub>`t`</sub>, holds the discounted return
+/// r<sub>t</sub> + γr<sub>t+1</sub> + ... + γ<sup>n-1</sup>r<sub>t+n-1</sub>, and ends at `s`<sub>`t+n`</sub>, so the
+/// learner bootstraps from its value with a discount of γ<sup>n</sup> (see [Exp::steps]). Near the end of an episode,
+/// experiences span fewer than `n` steps.
+///
+/// Push experiences in the order they occur and call [flush](NStep::flush) when an episode is truncated or otherwise
+/// cut short. Terminal experiences flush automatically.
+///
+/// ```ignore
+/// let mut n_step = NStep::new(3, 0.99);
+/// for exp in n_step.push(exp) {
+///     memory.push(exp);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "Exp<E>: serde::Serialize",
+        deserialize = "Exp<E>: serde::Deserialize<'de>"
+    ))
+)]
+pub struct NStep<E: Environment> {
+    n: usize,
+    gamma: f32,
+    pending: VecDeque<Exp<E>>,
+}
+
+impl<E: Environment> NStep<E> {
+    /// Create a new aggregator over `n` steps with discount factor `gamma`
+    ///
+    /// With `n = 1`, experiences are passed through unchanged.
+    ///
+    /// **Panics** if `n` is zero
+    pub fn new(n: usize, gamma: f32) -> Self {
+        assert!(n > 0, "n must be at least 1");
+        Self {
+            n,
+            gamma,
+            pending: VecDeque::with_capacity(n),
+        }
+    }
+
+    /// The number of steps each experience is aggregated over
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Add the next experience of the current episode, returning the n-step experiences it completes
+    pub fn push(&mut self, exp: Exp<E>) -> Vec<Exp<E>> {
+        let terminal = exp.next_state.is_none();
+        self.pending.push_back(exp);
+
+        if terminal {
+            self.flush()
+        } else if self.pending.len() == self.n {
+            vec![self.pop()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// End the current episode, returning its remaining experiences aggregated over fewer than `n` steps
+    pub fn flush(&mut self) -> Vec<Exp<E>> {
+        let mut experiences = Vec::with_capacity(self.pending.len());
+        while !self.pending.is_empty() {
+            experiences.push(self.pop());
+        }
+
+        experiences
+    }
+
+    /// Aggregate every pending experience into one starting at the oldest, then discard the oldest
+    fn pop(&mut self) -> Exp<E> {
+        let (reward, steps) = self
+            .pending
+            .iter()
+            .rev()
+            .fold((0.0, 0), |(reward, steps), exp| {
+                (
+                    exp.reward + self.gamma.powi(exp.steps as i32) * reward,
+                    exp.steps + steps,
+                )
+            });
+        let last = self.pending.back().unwrap();
+        let next_state = last.next_state.clone();
+        let next_mask = last.next_mask.clone();
+        let first = self.pending.pop_front().unwrap();
+
+        Exp {
+            state: first.state,
+            action: first.action,
+            reward,
+            next_state,
+            next_mask,
+            steps,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{env::tests::MockEnv, memory::tests::create_mock_exp_vec};
+
+    use super::*;
+
+    #[test]
+    fn n_step_functional() {
+        let mut n_step = NStep::<MockEnv>::new(3, 0.5);
+        let mut experiences = create_mock_exp_vec(4).into_iter();
+
+        assert!(n_step.push(experiences.next().unwrap()).is_empty());
+        assert!(n_step.push(experiences.next().unwrap()).is_empty());
+
+        let completed = n_step.push(experiences.next().unwrap());
+        assert_eq!(completed.len(), 1, "Experience completed after n steps");
+        assert_eq!(
+            (
+                completed[0].state,
+                completed[0].next_state,
+                completed[0].steps
+            ),
+            (0, Some(3), 3),
+            "Experience spans n steps"
+        );
+        assert_eq!(completed[0].reward, 1.75, "Rewards discounted and summed");
+
+        let mut terminal = experiences.next().unwrap();
+        terminal.next_state = None;
+        let completed = n_step.push(terminal);
+        assert_eq!(
+            completed
+                .iter()
+                .map(|exp| (exp.state, exp.next_state, exp.steps, exp.reward))
+                .collect::<Vec<_>>(),
+            [(1, None, 3, 1.75), (2, None, 2, 1.5), (3, None, 1, 1.0)],
+            "Terminal experience flushes the episode"
+        );
+        assert!(n_step.flush().is_empty(), "Nothing left after flushing");
+    }
+}
@@ -1,6 +1,7 @@
 use rand::{
     distributions::{Distribution, Uniform},
-    thread_rng,
+    rngs::StdRng,
+    SeedableRng,
 };
 
 use crate::{
@@ -23,11 +24,21 @@ use super::{Exp, ExpBatch};
 /// - `beta_0` - the initial value for beta, the importance sampling exponent, which is annealed from β<sub>0</sub> to 1 to apply
 ///   IS weights to the temporal difference errors
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "Exp<E>: serde::Serialize",
+        deserialize = "Exp<E>: serde::Deserialize<'de>"
+    ))
+)]
 pub struct PrioritizedReplayMemory<E: Environment> {
     memory: RingBuffer<Exp<E>>,
     priorities: SumTree,
     alpha: f32,
     beta: decay::Linear,
+    #[cfg_attr(feature = "serde", serde(skip, default = "StdRng::from_entropy"))]
+    rng: StdRng,
     pub batch_size: usize,
 }
 
@@ -55,10 +66,16 @@ impl<E: Environment> PrioritizedReplayMemory<E> {
             priorities: SumTree::new(capacity),
             alpha,
             beta: decay::Linear::new((beta_0 - 1.0) / num_episodes as f32, beta_0, 1.0).unwrap(),
+            rng: StdRng::from_entropy(),
             batch_size,
         }
     }
 
+    /// Seed the random number generator used for sampling
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
     /// Add a new experience to the memory
     pub fn push(&mut self, exp: Exp<E>) {
         let ix = self.memory.push(exp);
@@ -89,21 +106,20 @@ impl<E: Environment> PrioritizedReplayMemory<E> {
     ///   - `indices` - the indices of the sampled experiences - hold on to this and pass it back to the
     ///     [`update_priorities`](PrioritizedReplayMemory::update_priorities) function along with the computed
     ///     TD errors
-    pub fn sample(&self, episode: usize) -> Option<(Vec<Exp<E>>, Vec<f32>, Vec<usize>)> {
+    pub fn sample(&mut self, episode: usize) -> Option<(Vec<Exp<E>>, Vec<f32>, Vec<usize>)> {
         if self.batch_size > self.memory.len() {
             return None;
         }
 
         let total_priority = self.priorities.sum();
 
-        let mut rng = thread_rng();
         let dist = Uniform::new(0.0, total_priority);
 
         let mut batch = Vec::with_capacity(self.batch_size);
         let mut probs = Vec::with_capacity(self.batch_size);
         let mut indices = Vec::with_capacity(self.batch_size);
         for _ in 0..self.batch_size {
-            let priority = dist.sample(&mut rng);
+            let priority = dist.sample(&mut self.rng);
             let ix = self.priorities.find(priority).min(self.memory.len() - 1);
             let val = self.priorities[ix];
 
@@ -132,7 +148,7 @@ impl<E: Environment> PrioritizedReplayMemory<E> {
     ///   - `indices` - the indices of the sampled experiences - hold on to this and pass it back to the
     ///     [`update_priorities`](PrioritizedReplayMemory::update_priorities) function along with the computed
     ///     TD errors
-    pub fn sample_zipped(&self, episode: usize) -> Option<(ExpBatch<E>, Vec<f32>, Vec<usize>)> {
+    pub fn sample_zipped(&mut self, episode: usize) -> Option<(ExpBatch<E>, Vec<f32>, Vec<usize>)> {
         let (experiences, weights, indices) = self.sample(episode)?;
         let batch = ExpBatch::from_iter(experiences, self.batch_size);
         Some((batch, weights, indices))
@@ -0,0 +1,415 @@
+use std::fmt::Debug;
+
+use crate::env::{DiscreteActionSpace, Environment, Info, MaskedActionSpace, StepResult};
+
+/// Identifies an agent in a [MultiAgentEnvironment] by its index in `0..num_agents`
+pub type AgentId = usize;
+
+/// Represents a Markov game, the multi-agent counterpart of an [Environment]
+///
+/// Both turn-based and simultaneous-move games are supported: in each step, the agents returned by
+/// [to_act](MultiAgentEnvironment::to_act) choose an action, and every agent receives a reward.
+/// In a turn-based game, `to_act` returns a single agent. In a simultaneous-move game, it returns all of them.
+pub trait MultiAgentEnvironment {
+    /// A representation of what an agent can observe about the state of the environment
+    ///
+    /// ### Trait bounds
+    /// - `Clone` - When sampling batches of experiences, cloning is necessary
+    type Observation: Clone + Debug;
+
+    /// A representation of an action that an agent can take to affect the environment
+    ///
+    /// ### Trait bounds
+    /// - `Clone` - When sampling batches of experiences, cloning is necessary
+    type Action: Clone + Debug;
+
+    /// The number of agents in the environment
+    fn num_agents(&self) -> usize;
+
+    /// Get the agents that must act in the current step
+    fn to_act(&self) -> Vec<AgentId>;
+
+    /// Get the given agent's observation of the current state
+    fn observe(&self, agent: AgentId) -> Self::Observation;
+
+    /// Update the environment in response to the actions of the agents that must act
+    ///
+    /// `actions` contains exactly one action for each agent returned by [to_act](MultiAgentEnvironment::to_act)
+    fn step(&mut self, actions: Vec<(AgentId, Self::Action)>) -> MultiAgentStepResult;
+
+    /// Reset the environment to an initial state
+    fn reset(&mut self);
+
+    /// Select a random action for the given agent
+    fn random_action(&mut self, agent: AgentId) -> Self::Action;
+
+    /// Seed the environment's random number generator
+    ///
+    /// See [Environment::seed]
+    fn seed(&mut self, _seed: u64) {}
+}
+
+/// The result of taking a [step](MultiAgentEnvironment::step) in a [MultiAgentEnvironment]
+///
+/// Unlike a [StepResult], the next observations are not included since each agent observes the
+/// environment separately through [observe](MultiAgentEnvironment::observe).
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiAgentStepResult {
+    /// The reward received by each agent, indexed by [AgentId]
+    pub rewards: Vec<f32>,
+    /// Whether a terminal state was reached
+    pub terminated: bool,
+    /// Whether the episode was cut short without reaching a terminal state
+    pub truncated: bool,
+    /// Additional information about the step
+    pub info: Info,
+}
+
+impl MultiAgentStepResult {
+    /// Create a step result for a game that continues
+    pub fn new(rewards: Vec<f32>) -> Self {
+        Self {
+            rewards,
+            terminated: false,
+            truncated: false,
+            info: Info::new(),
+        }
+    }
+
+    /// Create a step result for a game that reached a terminal state
+    pub fn terminated(rewards: Vec<f32>) -> Self {
+        Self {
+            terminated: true,
+            ..Self::new(rewards)
+        }
+    }
+
+    /// Create a step result for a game that was truncated
+    pub fn truncated(rewards: Vec<f32>) -> Self {
+        Self {
+            truncated: true,
+            ..Self::new(rewards)
+        }
+    }
+
+    /// Attach a piece of additional information to the step result
+    pub fn with_info(mut self, key: &'static str, value: f64) -> Self {
+        self.info.insert(key, value);
+        self
+    }
+}
+
+/// A [MultiAgentEnvironment] with discrete action spaces
+pub trait MultiAgentDiscreteActionSpace: MultiAgentEnvironment {
+    /// Get the available actions for the given agent in the current state
+    ///
+    /// The returned vector should never be empty for an agent that must act
+    fn actions(&self, agent: AgentId) -> Vec<Self::Action>;
+}
+
+/// A [MultiAgentDiscreteActionSpace] with a fixed set of actions indexed `0..num_actions`
+///
+/// See [MaskedActionSpace]
+pub trait MultiAgentMaskedActionSpace: MultiAgentDiscreteActionSpace {
+    /// The total number of actions, legal or not
+    fn num_actions(&self) -> usize;
+}
+
+/// A fixed policy that chooses actions for the opponents in a [SelfPlay] adapter
+///
+/// Implemented for closures of the form `FnMut(&mut G, AgentId, G::Observation) -> G::Action`, which can wrap a
+/// scripted strategy or a frozen agent.
+pub trait OpponentPolicy<G: MultiAgentEnvironment> {
+    /// Choose an action for `agent` given its observation
+    fn act(&mut self, game: &mut G, agent: AgentId, observation: G::Observation) -> G::Action;
+}
+
+impl<G, F> OpponentPolicy<G> for F
+where
+    G: MultiAgentEnvironment,
+    F: FnMut(&mut G, AgentId, G::Observation) -> G::Action,
+{
+    fn act(&mut self, game: &mut G, agent: AgentId, observation: G::Observation) -> G::Action {
+        self(game, agent, observation)
+    }
+}
+
+/// An [OpponentPolicy] that chooses random actions
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomOpponent;
+
+impl<G: MultiAgentEnvironment> OpponentPolicy<G> for RandomOpponent {
+    fn act(&mut self, game: &mut G, agent: AgentId, _observation: G::Observation) -> G::Action {
+        game.random_action(agent)
+    }
+}
+
+/// Adapts a [MultiAgentEnvironment] into a single-agent [Environment] by controlling every agent except one,
+/// the learner, with a fixed [OpponentPolicy]
+///
+/// The opponents act until it is the learner's turn, so each step of the adapter covers the learner's action and all
+/// opponent actions until the learner must act again. The learner's rewards over those moves are summed. Rewards the
+/// learner receives from opponent moves made on reset are added to its first step.
+///
+/// This allows any single-agent algorithm, such as a [QTableAgent](crate::algo::tabular::q_table::QTableAgent) or a
+/// [DQNAgent](crate::algo::dqn::DQNAgent), to be trained against scripted or frozen opponents.
+pub struct SelfPlay<G: MultiAgentEnvironment, P: OpponentPolicy<G>> {
+    game: G,
+    opponent: P,
+    learner: AgentId,
+    pending_reward: f32,
+    active: bool,
+}
+
+impl<G, P> SelfPlay<G, P>
+where
+    G: MultiAgentEnvironment,
+    P: OpponentPolicy<G>,
+{
+    /// Adapt a game into an environment for the `learner` agent, with all other agents controlled by `opponent`
+    ///
+    /// **Panics** if `learner` is not an agent of the game
+    pub fn new(game: G, learner: AgentId, opponent: P) -> Self {
+        assert!(
+            learner < game.num_agents(),
+            "Learner {learner} is not an agent of the game"
+        );
+        Self {
+            game,
+            opponent,
+            learner,
+            pending_reward: 0.0,
+            active: false,
+        }
+    }
+
+    /// Get a reference to the wrapped game
+    pub fn game(&self) -> &G {
+        &self.game
+    }
+
+    /// Get a mutable reference to the opponent policy
+    pub fn opponent_mut(&mut self) -> &mut P {
+        &mut self.opponent
+    }
+
+    /// Play one step of the game, with the learner taking `action` if it must act
+    fn play(&mut self, mut action: Option<G::Action>) -> MultiAgentStepResult {
+        let actions = self
+            .game
+            .to_act()
+            .into_iter()
+            .map(|agent| {
+                let action = if agent == self.learner {
+                    action.take().expect("Learner must act")
+                } else {
+                    let observation = self.game.observe(agent);
+                    self.opponent.act(&mut self.game, agent, observation)
+                };
+                (agent, action)
+            })
+            .collect();
+
+        self.game.step(actions)
+    }
+
+    /// Let the opponents play until the learner must act or the episode ends
+    ///
+    /// **Returns** the learner's summed reward and the last step result if the episode ended
+    fn play_opponents(&mut self, mut reward: f32) -> (f32, Option<MultiAgentStepResult>) {
+        while !self.game.to_act().contains(&self.learner) {
+            let result = self.play(None);
+            reward += result.rewards[self.learner];
+            if result.terminated || result.truncated {
+                return (reward, Some(result));
+            }
+        }
+
+        (reward, None)
+    }
+}
+
+impl<G, P> Environment for SelfPlay<G, P>
+where
+    G: MultiAgentEnvironment,
+    P: OpponentPolicy<G>,
+{
+    type State = G::Observation;
+    type Action = G::Action;
+
+    fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
+        let mut result = self.play(Some(action));
+        let mut reward = std::mem::take(&mut self.pending_reward) + result.rewards[self.learner];
+
+        if !(result.terminated || result.truncated) {
+            let (total, end) = self.play_opponents(reward);
+            reward = total;
+            result = end.unwrap_or(result);
+        }
+
+        let mut step = if result.terminated {
+            self.active = false;
+            StepResult::new(None, reward)
+        } else if result.truncated {
+            self.active = false;
+            StepResult::truncated(self.game.observe(self.learner), reward)
+        } else {
+            StepResult::new(Some(self.game.observe(self.learner)), reward)
+        };
+        step.info = result.info;
+        step
+    }
+
+    /// Reset the game and let the opponents play until the learner must act
+    ///
+    /// **Panics** if the episode ends before the learner acts
+    fn reset(&mut self) -> Self::State {
+        self.game.reset();
+        self.active = true;
+        let (reward, end) = self.play_opponents(0.0);
+        assert!(end.is_none(), "Episode ended before the learner could act");
+        self.pending_reward = reward;
+        self.game.observe(self.learner)
+    }
+
+    fn random_action(&mut self) -> Self::Action {
+        self.game.random_action(self.learner)
+    }
+
+    fn seed(&mut self, seed: u64) {
+        self.game.seed(seed)
+    }
+
+    fn is_active(&self) -> bool {
+        self.active
+    }
+}
+
+impl<G, P> DiscreteActionSpace for SelfPlay<G, P>
+where
+    G: MultiAgentDiscreteActionSpace,
+    P: OpponentPolicy<G>,
+{
+    fn actions(&self) -> Vec<Self::Action> {
+        self.game.actions(self.learner)
+    }
+}
+
+impl<G, P> MaskedActionSpace for SelfPlay<G, P>
+where
+    G: MultiAgentMaskedActionSpace,
+    P: OpponentPolicy<G>,
+{
+    fn num_actions(&self) -> usize {
+        self.game.num_actions()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        algo::tabular::q_table::{QTableAgent, QTableAgentConfig},
+        traits::Agent,
+    };
+
+    use super::*;
+
+    /// Players take turns adding 1 or 2 to a counter, and the player who reaches 10 wins
+    struct CountToTen {
+        count: u32,
+        turn: AgentId,
+    }
+
+    impl MultiAgentEnvironment for CountToTen {
+        type Observation = u32;
+        type Action = u32;
+
+        fn num_agents(&self) -> usize {
+            2
+        }
+
+        fn to_act(&self) -> Vec<AgentId> {
+            vec![self.turn]
+        }
+
+        fn observe(&self, _agent: AgentId) -> Self::Observation {
+            self.count
+        }
+
+        fn step(&mut self, actions: Vec<(AgentId, Self::Action)>) -> MultiAgentStepResult {
+            let (agent, action) = actions[0];
+            self.count += action;
+            self.turn = 1 - agent;
+            if self.count >= 10 {
+                let mut rewards = vec![-1.0; 2];
+                rewards[agent] = 1.0;
+                MultiAgentStepResult::terminated(rewards)
+            } else {
+                MultiAgentStepResult::new(vec![0.0; 2])
+            }
+        }
+
+        fn reset(&mut self) {
+            self.count = 0;
+            self.turn = 0;
+        }
+
+        fn random_action(&mut self, _agent: AgentId) -> Self::Action {
+            1
+        }
+    }
+
+    impl MultiAgentDiscreteActionSpace for CountToTen {
+        fn actions(&self, _agent: AgentId) -> Vec<Self::Action> {
+            vec![1, 2]
+        }
+    }
+
+    fn always_one(_: &mut CountToTen, _: AgentId, _: u32) -> u32 {
+        1
+    }
+
+    #[test]
+    fn self_play_turn_based() {
+        let game = CountToTen { count: 0, turn: 0 };
+        let mut env = SelfPlay::new(game, 1, always_one);
+
+        assert_eq!(env.reset(), 1, "Opponent moves first");
+        let step = env.step(2);
+        assert_eq!(
+            step.next_state,
+            Some(4),
+            "Opponent replies within the learner's step"
+        );
+        env.step(1);
+        env.step(2);
+        let step = env.step(1);
+        assert_eq!(step.next_state, None, "Learner reached 10");
+        assert_eq!(step.reward, 1.0, "Learner won");
+
+        env.reset();
+        env.step(2);
+        env.step(2);
+        let step = env.step(2);
+        assert!(step.is_terminated(), "Opponent reached 10");
+        assert_eq!(step.reward, -1.0, "Learner lost");
+    }
+
+    #[test]
+    fn self_play_q_table() {
+        let game = CountToTen { count: 0, turn: 0 };
+        let mut env = SelfPlay::new(game, 0, always_one);
+        let mut agent = QTableAgent::new(QTableAgentConfig {
+            seed: Some(0),
+            ..Default::default()
+        });
+
+        for _ in 0..10 {
+            agent.go(&mut env);
+        }
+        assert!(
+            !agent.get_q_table().is_empty(),
+            "Tabular agent trains against a scripted opponent"
+        );
+    }
+}
@@ -0,0 +1,424 @@
+#[cfg(feature = "serde")]
+use std::path::PathBuf;
+
+#[cfg(feature = "serde")]
+use burn::record::{BinFileRecorder, FullPrecisionSettings, Recorder, RecorderError};
+use burn::{
+    grad_clipping::GradientClippingConfig,
+    module::{AutodiffModule, Module, ModuleMapper, ModuleVisitor, ParamId},
+    optim::{
+        adaptor::OptimizerAdaptor, momentum::MomentumConfig, Adam, AdamConfig, AdamW, AdamWConfig,
+        GradientsParams, Optimizer, RmsProp, RmsPropConfig, Sgd, SgdConfig,
+    },
+    prelude::*,
+    tensor::backend::AutodiffBackend,
+};
+
+use crate::{env::MaskedActionSpace, traits::ToTensor};
+
+/// Soft update the parameters of a module toward those of another module with the same architecture
+///
+/// θ′ ← τθ + (1 − τ)θ′
+///
+/// Parameters are matched in the order they are visited, so `target` and `source` must have been built from the
+/// same configuration. Whether each target parameter requires gradients is preserved.
+///
+/// ```ignore
+/// target_net = soft_update(target_net, &policy_net, tau);
+/// ```
+///
+/// **Panics** if `source` has fewer float parameters than `target`
+pub fn soft_update<B: Backend, M: Module<B>>(target: M, source: &M, tau: f32) -> M {
+    let mut collector = Collector {
+        tensors: Vec::new(),
+    };
+    source.visit(&mut collector);
+
+    target.map(&mut Polyak {
+        source: collector.tensors.into_iter(),
+        tau,
+    })
+}
+
+/// Collects the float parameters of a module as flattened tensors
+struct Collector<B: Backend> {
+    tensors: Vec<Tensor<B, 1>>,
+}
+
+impl<B: Backend> ModuleVisitor<B> for Collector<B> {
+    fn visit_float<const D: usize>(&mut self, _id: &ParamId, tensor: &Tensor<B, D>) {
+        let num_elements = tensor.shape().num_elements();
+        self.tensors.push(tensor.clone().reshape([num_elements]));
+    }
+}
+
+/// Averages each float parameter of a module with the next collected source parameter
+struct Polyak<B: Backend> {
+    source: std::vec::IntoIter<Tensor<B, 1>>,
+    tau: f32,
+}
+
+impl<B: Backend> ModuleMapper<B> for Polyak<B> {
+    fn map_float<const D: usize>(&mut self, _id: &ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        let source = self
+            .source
+            .next()
+            .expect("Source module has the same parameters as the target module")
+            .reshape(tensor.shape());
+        let require_grad = tensor.is_require_grad();
+
+        (tensor * (1.0 - self.tau) + source * self.tau)
+            .detach()
+            .set_require_grad(require_grad)
+    }
+}
+
+/// Get the action mask of the environment's current state, or `None` if every action is legal
+pub(crate) fn legal_mask<E>(env: &E) -> Option<Vec<bool>>
+where
+    E: MaskedActionSpace,
+    E::Action: Into<usize>,
+{
+    let mask = env.action_mask();
+    (!mask.iter().all(|&legal| legal)).then_some(mask)
+}
+
+/// Build a boolean tensor of shape `[masks.len(), num_actions]` marking illegal actions, where a missing mask means
+/// every action is legal
+pub(crate) fn illegal_action_tensor<B: Backend>(
+    masks: &[Option<Vec<bool>>],
+    num_actions: usize,
+    device: &B::Device,
+) -> Tensor<B, 2, Bool> {
+    let illegal = masks
+        .iter()
+        .flat_map(|mask| match mask {
+            Some(mask) => mask.iter().map(|&legal| !legal).collect(),
+            None => vec![false; num_actions],
+        })
+        .collect::<Vec<_>>();
+    let tensor: Tensor<B, 1, Bool> = illegal.to_tensor(device);
+    tensor.reshape([masks.len(), num_actions])
+}
+
+/// The optimizer used to train the networks of an agent
+///
+/// Use the constructors for burn's default hyperparameters, e.g. `OptimizerConfig::sgd(0.9)`
+#[derive(Debug, Clone, PartialEq)]
+pub enum OptimizerConfig {
+    /// The [Adam](burn::optim::Adam) optimizer
+    Adam {
+        beta_1: f32,
+        beta_2: f32,
+        epsilon: f32,
+    },
+    /// The [AdamW](burn::optim::AdamW) optimizer, Adam with decoupled weight decay
+    AdamW {
+        beta_1: f32,
+        beta_2: f32,
+        epsilon: f32,
+        weight_decay: f32,
+    },
+    /// Stochastic gradient descent with momentum
+    Sgd {
+        momentum: f32,
+        dampening: f32,
+        nesterov: bool,
+    },
+    /// The [RMSProp](burn::optim::RmsProp) optimizer
+    RmsProp {
+        alpha: f32,
+        epsilon: f32,
+        momentum: f32,
+        centered: bool,
+    },
+}
+
+impl OptimizerConfig {
+    /// Adam with β<sub>1</sub> = `0.9`, β<sub>2</sub> = `0.999`, and ε = `1e-5`
+    pub fn adam() -> Self {
+        Self::Adam {
+            beta_1: 0.9,
+            beta_2: 0.999,
+            epsilon: 1e-5,
+        }
+    }
+
+    /// AdamW with β<sub>1</sub> = `0.9`, β<sub>2</sub> = `0.999`, ε = `1e-5`, and weight decay `1e-4`
+    pub fn adamw() -> Self {
+        Self::AdamW {
+            beta_1: 0.9,
+            beta_2: 0.999,
+            epsilon: 1e-5,
+            weight_decay: 1e-4,
+        }
+    }
+
+    /// SGD with the given momentum, dampening `0.1`, and without Nesterov momentum
+    pub fn sgd(momentum: f32) -> Self {
+        Self::Sgd {
+            momentum,
+            dampening: 0.1,
+            nesterov: false,
+        }
+    }
+
+    /// RMSProp with α = `0.99`, ε = `1e-5`, no momentum, and without centering
+    pub fn rms_prop() -> Self {
+        Self::RmsProp {
+            alpha: 0.99,
+            epsilon: 1e-5,
+            momentum: 0.0,
+            centered: false,
+        }
+    }
+}
+
+impl Default for OptimizerConfig {
+    fn default() -> Self {
+        Self::adamw()
+    }
+}
+
+/// A limit on the gradients of a network, applied before each optimizer step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientClipping {
+    /// Clamp each gradient element to `[-value, value]`
+    Value(f32),
+    /// Rescale each parameter's gradient so that its L2 norm is at most the given value
+    Norm(f32),
+}
+
+impl From<GradientClipping> for GradientClippingConfig {
+    fn from(value: GradientClipping) -> Self {
+        match value {
+            GradientClipping::Value(value) => GradientClippingConfig::Value(value),
+            GradientClipping::Norm(norm) => GradientClippingConfig::Norm(norm),
+        }
+    }
+}
+
+/// The optimizer selected by an [`OptimizerConfig`] for a module `M`, whose state persists across episodes
+#[derive(Clone)]
+pub(crate) enum ModelOptimizer<M, B>
+where
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    Adam(OptimizerAdaptor<Adam<B::InnerBackend>, M, B>),
+    AdamW(OptimizerAdaptor<AdamW<B::InnerBackend>, M, B>),
+    Sgd(OptimizerAdaptor<Sgd<B::InnerBackend>, M, B>),
+    RmsProp(OptimizerAdaptor<RmsProp<B::InnerBackend>, M, B>),
+}
+
+impl<M, B> ModelOptimizer<M, B>
+where
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    pub(crate) fn new(config: &OptimizerConfig, clipping: Option<GradientClipping>) -> Self {
+        let clipping = clipping.map(GradientClippingConfig::from);
+        match *config {
+            OptimizerConfig::Adam {
+                beta_1,
+                beta_2,
+                epsilon,
+            } => Self::Adam(
+                AdamConfig::new()
+                    .with_beta_1(beta_1)
+                    .with_beta_2(beta_2)
+                    .with_epsilon(epsilon)
+                    .with_grad_clipping(clipping)
+                    .init(),
+            ),
+            OptimizerConfig::AdamW {
+                beta_1,
+                beta_2,
+                epsilon,
+                weight_decay,
+            } => Self::AdamW(
+                AdamWConfig::new()
+                    .with_beta_1(beta_1)
+                    .with_beta_2(beta_2)
+                    .with_epsilon(epsilon)
+                    .with_weight_decay(weight_decay)
+                    .with_grad_clipping(clipping)
+                    .init(),
+            ),
+            OptimizerConfig::Sgd {
+                momentum,
+                dampening,
+                nesterov,
+            } => Self::Sgd(
+                SgdConfig::new()
+                    .with_momentum(Some(
+                        MomentumConfig::new()
+                            .with_momentum(momentum as f64)
+                            .with_dampening(dampening as f64)
+                            .with_nesterov(nesterov),
+                    ))
+                    .with_gradient_clipping(clipping)
+                    .init(),
+            ),
+            OptimizerConfig::RmsProp {
+                alpha,
+                epsilon,
+                momentum,
+                centered,
+            } => Self::RmsProp(
+                RmsPropConfig::new()
+                    .with_alpha(alpha)
+                    .with_epsilon(epsilon)
+                    .with_momentum(momentum)
+                    .with_centered(centered)
+                    .with_grad_clipping(clipping)
+                    .init(),
+            ),
+        }
+    }
+
+    pub(crate) fn step(&mut self, lr: f64, module: M, grads: GradientsParams) -> M {
+        match self {
+            Self::Adam(optimizer) => optimizer.step(lr, module, grads),
+            Self::AdamW(optimizer) => optimizer.step(lr, module, grads),
+            Self::Sgd(optimizer) => optimizer.step(lr, module, grads),
+            Self::RmsProp(optimizer) => optimizer.step(lr, module, grads),
+        }
+    }
+
+    /// Record the optimizer state to a file
+    #[cfg(feature = "serde")]
+    pub(crate) fn save_file(
+        &self,
+        recorder: &BinFileRecorder<FullPrecisionSettings>,
+        path: PathBuf,
+    ) -> Result<(), RecorderError> {
+        match self {
+            Self::Adam(optimizer) => Recorder::<B>::record(recorder, optimizer.to_record(), path),
+            Self::AdamW(optimizer) => Recorder::<B>::record(recorder, optimizer.to_record(), path),
+            Self::Sgd(optimizer) => Recorder::<B>::record(recorder, optimizer.to_record(), path),
+            Self::RmsProp(optimizer) => {
+                Recorder::<B>::record(recorder, optimizer.to_record(), path)
+            }
+        }
+    }
+
+    /// Load the optimizer state from a file written by an optimizer of the same kind
+    #[cfg(feature = "serde")]
+    pub(crate) fn load_file(
+        self,
+        recorder: &BinFileRecorder<FullPrecisionSettings>,
+        path: PathBuf,
+        device: &B::Device,
+    ) -> Result<Self, RecorderError> {
+        Ok(match self {
+            Self::Adam(optimizer) => {
+                Self::Adam(optimizer.load_record(Recorder::<B>::load(recorder, path, device)?))
+            }
+            Self::AdamW(optimizer) => {
+                Self::AdamW(optimizer.load_record(Recorder::<B>::load(recorder, path, device)?))
+            }
+            Self::Sgd(optimizer) => {
+                Self::Sgd(optimizer.load_record(Recorder::<B>::load(recorder, path, device)?))
+            }
+            Self::RmsProp(optimizer) => {
+                Self::RmsProp(optimizer.load_record(Recorder::<B>::load(recorder, path, device)?))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn::{
+        backend::{ndarray::NdArrayDevice, Autodiff, NdArray as B},
+        nn::{Linear, LinearConfig},
+    };
+
+    use super::*;
+
+    type AB = Autodiff<B>;
+
+    #[test]
+    fn soft_update_linear() {
+        let device = NdArrayDevice::Cpu;
+        let target: Linear<B> = LinearConfig::new(3, 2).init(&device);
+        let source: Linear<B> = LinearConfig::new(3, 2).init(&device);
+
+        let expected_weight = target.weight.val() * 0.75 + source.weight.val() * 0.25;
+        let expected_bias =
+            target.bias.as_ref().unwrap().val() * 0.75 + source.bias.as_ref().unwrap().val() * 0.25;
+        let updated = soft_update(target, &source, 0.25);
+
+        assert!(
+            (updated.weight.val() - expected_weight)
+                .abs()
+                .max()
+                .into_scalar()
+                < 1e-6,
+            "Weights averaged"
+        );
+        assert!(
+            (updated.bias.unwrap().val() - expected_bias)
+                .abs()
+                .max()
+                .into_scalar()
+                < 1e-6,
+            "Biases averaged"
+        );
+    }
+
+    /// Take one optimizer step with learning rate `0.1` on a linear layer, returning the change in its weights
+    fn weight_update(
+        config: &OptimizerConfig,
+        clipping: Option<GradientClipping>,
+    ) -> Tensor<AB, 2> {
+        let device = NdArrayDevice::Cpu;
+        let linear: Linear<AB> = LinearConfig::new(3, 2).init(&device);
+        let before = linear.weight.val();
+
+        let input = Tensor::<AB, 2>::from_floats([[10.0, 20.0, 30.0]], &device);
+        let grads = linear.forward(input).sum().backward();
+        let grads = GradientsParams::from_grads(grads, &linear);
+        let mut optimizer = ModelOptimizer::new(config, clipping);
+        let linear = optimizer.step(0.1, linear, grads);
+
+        linear.weight.val() - before
+    }
+
+    fn norm(tensor: Tensor<AB, 2>) -> f32 {
+        tensor.powf_scalar(2.0).sum().sqrt().into_scalar()
+    }
+
+    #[test]
+    fn model_optimizer_step() {
+        for config in [
+            OptimizerConfig::adam(),
+            OptimizerConfig::adamw(),
+            OptimizerConfig::sgd(0.9),
+            OptimizerConfig::rms_prop(),
+        ] {
+            assert!(
+                norm(weight_update(&config, None)) > 0.0,
+                "{config:?} moves the parameters"
+            );
+        }
+    }
+
+    #[test]
+    fn model_optimizer_norm_clipping() {
+        let sgd = OptimizerConfig::Sgd {
+            momentum: 0.0,
+            dampening: 0.0,
+            nesterov: false,
+        };
+        let unclipped = norm(weight_update(&sgd, None));
+        let clipped = norm(weight_update(&sgd, Some(GradientClipping::Norm(1.0))));
+
+        assert!(unclipped > 1.0, "Large gradient takes a large step");
+        assert!(
+            (clipped - 0.1).abs() < 1e-3,
+            "Clipped gradient norm limits the step to the learning rate"
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use std::{error::Error, fmt::Display, io};
+#[cfg(feature = "serde")]
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use burn::record::RecorderError;
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
+
+/// A file format for saving agents
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Human-readable JSON
+    #[default]
+    Json,
+    /// Compact binary encoding with [bincode]
+    Binary,
+}
+
+/// An error encountered while saving or loading an agent
+#[derive(Debug)]
+pub enum PersistError {
+    /// A file or directory could not be read or written
+    Io(io::Error),
+    /// A burn record, such as a network or optimizer state, could not be saved or loaded
+    Recorder(RecorderError),
+    /// Agent data could not be encoded or decoded in the [binary](Format::Binary) format
+    #[cfg(feature = "serde")]
+    Encoding(bincode::Error),
+    /// Agent data could not be encoded or decoded in the [JSON](Format::Json) format
+    #[cfg(feature = "serde")]
+    Json(serde_json::Error),
+}
+
+impl Display for PersistError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PersistError::Io(err) => write!(f, "I/O error: {err}"),
+            PersistError::Recorder(err) => write!(f, "Recorder error: {err:?}"),
+            #[cfg(feature = "serde")]
+            PersistError::Encoding(err) => write!(f, "Encoding error: {err}"),
+            #[cfg(feature = "serde")]
+            PersistError::Json(err) => write!(f, "JSON error: {err}"),
+        }
+    }
+}
+
+impl Error for PersistError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PersistError::Io(err) => Some(err),
+            PersistError::Recorder(_) => None,
+            #[cfg(feature = "serde")]
+            PersistError::Encoding(err) => Some(err),
+            #[cfg(feature = "serde")]
+            PersistError::Json(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for PersistError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<RecorderError> for PersistError {
+    fn from(value: RecorderError) -> Self {
+        Self::Recorder(value)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<bincode::Error> for PersistError {
+    fn from(value: bincode::Error) -> Self {
+        Self::Encoding(value)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for PersistError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+/// Write a value to a file in the given format
+#[cfg(feature = "serde")]
+pub(crate) fn save<T: Serialize>(
+    value: &T,
+    path: &Path,
+    format: Format,
+) -> Result<(), PersistError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    match format {
+        Format::Json => serde_json::to_writer(&mut writer, value)?,
+        Format::Binary => bincode::serialize_into(&mut writer, value)?,
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Read a value from a file in the given format
+#[cfg(feature = "serde")]
+pub(crate) fn load<T: DeserializeOwned>(path: &Path, format: Format) -> Result<T, PersistError> {
+    let reader = BufReader::new(File::open(path)?);
+    let value = match format {
+        Format::Json => serde_json::from_reader(reader)?,
+        Format::Binary => bincode::deserialize_from(reader)?,
+    };
+
+    Ok(value)
+}
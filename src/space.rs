@@ -0,0 +1,314 @@
+use burn::prelude::*;
+use rand::Rng;
+use rand_distr::{Exp1, StandardNormal};
+
+use crate::traits::ToTensor;
+
+/// A description of a set of valid states or actions
+///
+/// Spaces allow algorithms and tooling to inspect the structure of an [Environment](crate::env::Environment)
+/// without knowing its concrete state and action types (see [Spaces](crate::env::Spaces)).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Space {
+    /// The integers `0..n`
+    Discrete(usize),
+    /// A box in ℝ<sup>n</sup> with a given shape, where each element is bounded by the corresponding elements
+    /// of `low` and `high`
+    ///
+    /// Bounds may be infinite. `low` and `high` are stored flattened in row-major order.
+    Box {
+        shape: Vec<usize>,
+        low: Vec<f32>,
+        high: Vec<f32>,
+    },
+    /// A vector of discrete values, where element `i` is in `0..nvec[i]`
+    MultiDiscrete(Vec<usize>),
+    /// The cartesian product of several spaces
+    Tuple(Vec<Space>),
+}
+
+/// A point in a [Space]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Point {
+    Discrete(usize),
+    Box(Vec<f32>),
+    MultiDiscrete(Vec<usize>),
+    Tuple(Vec<Point>),
+}
+
+impl Space {
+    /// Create a discrete space of `n` elements
+    ///
+    /// **Panics** if `n` is zero
+    pub fn discrete(n: usize) -> Self {
+        assert!(n > 0, "Discrete spaces must have at least one element");
+        Self::Discrete(n)
+    }
+
+    /// Create a box with the given shape where every element has the same bounds
+    ///
+    /// **Panics** if `low > high`
+    pub fn bounded(shape: &[usize], low: f32, high: f32) -> Self {
+        let len = shape.iter().product();
+        Self::bounded_elementwise(shape, vec![low; len], vec![high; len])
+    }
+
+    /// Create a box with the given shape and per-element bounds
+    ///
+    /// **Panics** if the lengths of `low` and `high` do not match the shape, or if any `low` element is greater than
+    /// its corresponding `high` element
+    pub fn bounded_elementwise(shape: &[usize], low: Vec<f32>, high: Vec<f32>) -> Self {
+        let len: usize = shape.iter().product();
+        assert!(
+            low.len() == len && high.len() == len,
+            "Bounds must have {len} elements to match shape {shape:?}"
+        );
+        assert!(
+            low.iter().zip(&high).all(|(l, h)| l <= h),
+            "Lower bounds must not exceed upper bounds"
+        );
+
+        Self::Box {
+            shape: shape.to_vec(),
+            low,
+            high,
+        }
+    }
+
+    /// Create a multi-discrete space where element `i` is in `0..nvec[i]`
+    ///
+    /// **Panics** if any element of `nvec` is zero
+    pub fn multi_discrete(nvec: Vec<usize>) -> Self {
+        assert!(
+            nvec.iter().all(|&n| n > 0),
+            "Multi-discrete elements must each have at least one value"
+        );
+        Self::MultiDiscrete(nvec)
+    }
+
+    /// Create the cartesian product of several spaces
+    pub fn tuple(spaces: Vec<Space>) -> Self {
+        Self::Tuple(spaces)
+    }
+
+    /// Sample a random point from the space
+    ///
+    /// Bounded box elements are sampled uniformly. Elements that are unbounded on one side are sampled from a shifted
+    /// exponential distribution, and elements that are unbounded on both sides are sampled from a standard normal distribution.
+    ///
+    /// **Panics** if the space is empty, i.e. a [Space::Discrete] or [Space::MultiDiscrete] element built directly with
+    /// zero values
+    pub fn sample(&self, rng: &mut impl Rng) -> Point {
+        match self {
+            Space::Discrete(n) => Point::Discrete(rng.gen_range(0..*n)),
+            Space::Box { low, high, .. } => Point::Box(
+                low.iter()
+                    .zip(high)
+                    .map(|(&l, &h)| sample_interval(l, h, rng))
+                    .collect(),
+            ),
+            Space::MultiDiscrete(nvec) => {
+                Point::MultiDiscrete(nvec.iter().map(|&n| rng.gen_range(0..n)).collect())
+            }
+            Space::Tuple(spaces) => Point::Tuple(spaces.iter().map(|s| s.sample(rng)).collect()),
+        }
+    }
+
+    /// Check if a point is a member of the space
+    pub fn contains(&self, point: &Point) -> bool {
+        match (self, point) {
+            (Space::Discrete(n), Point::Discrete(x)) => x < n,
+            (Space::Box { low, high, .. }, Point::Box(x)) => {
+                x.len() == low.len()
+                    && x.iter()
+                        .zip(low.iter().zip(high))
+                        .all(|(x, (l, h))| (l..=h).contains(&x))
+            }
+            (Space::MultiDiscrete(nvec), Point::MultiDiscrete(x)) => {
+                x.len() == nvec.len() && x.iter().zip(nvec).all(|(x, n)| x < n)
+            }
+            (Space::Tuple(spaces), Point::Tuple(x)) => {
+                x.len() == spaces.len() && spaces.iter().zip(x).all(|(s, x)| s.contains(x))
+            }
+            _ => false,
+        }
+    }
+
+    /// The number of elements in a [flattened](Point::flatten) point of this space
+    pub fn flat_dim(&self) -> usize {
+        match self {
+            Space::Discrete(_) => 1,
+            Space::Box { low, .. } => low.len(),
+            Space::MultiDiscrete(nvec) => nvec.len(),
+            Space::Tuple(spaces) => spaces.iter().map(Space::flat_dim).sum(),
+        }
+    }
+}
+
+impl Point {
+    /// Flatten the point into a vector of floats
+    ///
+    /// Discrete values are represented by their index, and tuples are concatenated in order
+    pub fn flatten(&self) -> Vec<f32> {
+        let mut out = Vec::new();
+        self.flatten_into(&mut out);
+        out
+    }
+
+    fn flatten_into(&self, out: &mut Vec<f32>) {
+        match self {
+            Point::Discrete(x) => out.push(*x as f32),
+            Point::Box(x) => out.extend_from_slice(x),
+            Point::MultiDiscrete(x) => out.extend(x.iter().map(|&x| x as f32)),
+            Point::Tuple(points) => points.iter().for_each(|p| p.flatten_into(out)),
+        }
+    }
+}
+
+fn sample_interval(low: f32, high: f32, rng: &mut impl Rng) -> f32 {
+    match (low.is_finite(), high.is_finite()) {
+        (true, true) => rng.gen_range(low..=high),
+        (true, false) => low + rng.sample::<f32, _>(Exp1),
+        (false, true) => high - rng.sample::<f32, _>(Exp1),
+        (false, false) => rng.sample(StandardNormal),
+    }
+}
+
+// Conversions
+
+impl From<usize> for Point {
+    fn from(value: usize) -> Self {
+        Self::Discrete(value)
+    }
+}
+
+impl From<Vec<f32>> for Point {
+    fn from(value: Vec<f32>) -> Self {
+        Self::Box(value)
+    }
+}
+
+impl<const N: usize> From<[f32; N]> for Point {
+    fn from(value: [f32; N]) -> Self {
+        Self::Box(value.to_vec())
+    }
+}
+
+impl TryFrom<Point> for usize {
+    type Error = Point;
+
+    fn try_from(value: Point) -> Result<Self, Self::Error> {
+        match value {
+            Point::Discrete(x) => Ok(x),
+            _ => Err(value),
+        }
+    }
+}
+
+impl<const N: usize> TryFrom<Point> for [f32; N] {
+    type Error = Point;
+
+    fn try_from(value: Point) -> Result<Self, Self::Error> {
+        match value {
+            Point::Box(x) => x.try_into().map_err(Point::Box),
+            _ => Err(value),
+        }
+    }
+}
+
+impl<B: Backend> ToTensor<B, 2, Float> for Vec<Point> {
+    /// Convert a batch of points into a 2D tensor of shape `[batch_size, flat_dim]`
+    ///
+    /// **Panics** if the points do not all flatten to the same length
+    fn to_tensor(self, device: &B::Device) -> Tensor<B, 2> {
+        let len = self.len();
+        let data = self.iter().flat_map(Point::flatten).collect::<Vec<_>>();
+        Tensor::<B, 1>::from_floats(data.as_slice(), device).reshape([len as i32, -1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use burn::backend::{ndarray::NdArrayDevice, NdArray};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn space_sample_contains() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let space = Space::tuple(vec![
+            Space::discrete(3),
+            Space::bounded_elementwise(&[2], vec![-1.0, 0.0], vec![1.0, f32::INFINITY]),
+            Space::multi_discrete(vec![2, 5]),
+            Space::bounded(&[2, 2], f32::NEG_INFINITY, f32::INFINITY),
+        ]);
+
+        for _ in 0..100 {
+            let point = space.sample(&mut rng);
+            assert!(space.contains(&point), "Sampled points are in the space");
+        }
+
+        assert!(
+            !Space::discrete(3).contains(&Point::Discrete(3)),
+            "Discrete bounds checked"
+        );
+        assert!(
+            !Space::bounded(&[2], 0.0, 1.0).contains(&Point::Box(vec![0.5, 1.5])),
+            "Box bounds checked"
+        );
+        assert!(
+            !Space::bounded(&[2], 0.0, 1.0).contains(&Point::Box(vec![0.5])),
+            "Box shape checked"
+        );
+        assert!(
+            !Space::discrete(3).contains(&Point::Box(vec![0.0])),
+            "Mismatched kinds are rejected"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one element")]
+    fn empty_discrete_space_rejected() {
+        Space::discrete(0);
+    }
+
+    #[test]
+    fn space_flatten() {
+        let space = Space::tuple(vec![
+            Space::discrete(3),
+            Space::bounded(&[2], 0.0, 1.0),
+            Space::multi_discrete(vec![2, 5]),
+        ]);
+        let point = Point::Tuple(vec![
+            Point::Discrete(2),
+            Point::Box(vec![0.25, 0.5]),
+            Point::MultiDiscrete(vec![1, 4]),
+        ]);
+
+        assert_eq!(space.flat_dim(), 5, "Flat dimension correct");
+        assert_eq!(
+            point.flatten(),
+            [2.0, 0.25, 0.5, 1.0, 4.0],
+            "Points are flattened in order"
+        );
+
+        let device = NdArrayDevice::Cpu;
+        let tensor: Tensor<NdArray, 2> = vec![point.clone(), point].to_tensor(&device);
+        assert_eq!(
+            tensor.dims(),
+            [2, 5],
+            "Batch of points converts to a tensor"
+        );
+    }
+
+    #[test]
+    fn point_conversions() {
+        assert_eq!(usize::try_from(Point::from(2)), Ok(2));
+        assert_eq!(
+            <[f32; 2]>::try_from(Point::from([1.0, 2.0])),
+            Ok([1.0, 2.0])
+        );
+        assert!(<[f32; 3]>::try_from(Point::from([1.0, 2.0])).is_err());
+    }
+}
@@ -0,0 +1,401 @@
+use std::collections::BTreeMap;
+#[cfg(feature = "viz")]
+use std::sync::mpsc::Sender;
+
+use crate::{
+    env::{Aggregation, Environment, Metric, Report},
+    traits::{Agent, EpisodeStats, Evaluation},
+};
+
+/// Metrics published at the end of each training episode, keyed by name
+pub type Metrics = BTreeMap<String, Metric>;
+
+/// A destination for the metrics produced by a [Trainer]
+///
+/// Implemented for closures taking the episode index and its metrics, and for a
+/// [viz](crate::viz) update sender when the `viz` feature is enabled.
+pub trait Sink {
+    /// Publish the metrics of a finished episode
+    fn publish(&mut self, episode: usize, metrics: &Metrics);
+}
+
+impl<F> Sink for F
+where
+    F: FnMut(usize, &Metrics),
+{
+    fn publish(&mut self, episode: usize, metrics: &Metrics) {
+        self(episode, metrics)
+    }
+}
+
+#[cfg(feature = "viz")]
+impl Sink for Sender<crate::viz::Update> {
+    /// Send the metrics to the viz TUI, ignoring the update if the TUI has been closed
+    fn publish(&mut self, episode: usize, metrics: &Metrics) {
+        let _ = self.send(crate::viz::Update {
+            episode: episode.try_into().unwrap_or(u16::MAX),
+            data: metrics.clone(),
+        });
+    }
+}
+
+/// A condition for ending training before the episode or step limit is reached
+#[derive(Debug, Clone, PartialEq)]
+pub struct EarlyStopping {
+    /// The name of the metric to monitor, such as `"eval_reward"`
+    pub metric: String,
+    /// The value the metric must reach
+    pub target: f64,
+    /// Whether the metric must fall to the target rather than rise to it
+    pub minimize: bool,
+    /// The number of consecutive episodes in which the metric must meet the target
+    ///
+    /// Episodes that do not produce the metric, such as those without an evaluation, are skipped.
+    pub patience: usize,
+}
+
+impl EarlyStopping {
+    /// Stop once the metric is at least `target`
+    pub fn above(metric: impl Into<String>, target: f64) -> Self {
+        Self {
+            metric: metric.into(),
+            target,
+            minimize: false,
+            patience: 1,
+        }
+    }
+
+    /// Stop once the metric is at most `target`
+    pub fn below(metric: impl Into<String>, target: f64) -> Self {
+        Self {
+            minimize: true,
+            ..Self::above(metric, target)
+        }
+    }
+
+    /// Require the target to be met for `patience` consecutive episodes
+    pub fn with_patience(mut self, patience: usize) -> Self {
+        self.patience = patience;
+        self
+    }
+
+    fn is_met(&self, value: f64) -> bool {
+        match self.minimize {
+            true => value <= self.target,
+            false => value >= self.target,
+        }
+    }
+}
+
+/// Configuration for the [Trainer]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainerConfig {
+    /// The maximum number of training episodes, or `None` for no limit
+    ///
+    /// **Default:** `Some(500)`
+    pub max_episodes: Option<usize>,
+    /// The maximum number of training steps, or `None` for no limit
+    ///
+    /// This is checked between episodes, so the last episode is always played to the end
+    ///
+    /// **Default:** `None`
+    pub max_steps: Option<usize>,
+    /// The number of training episodes between evaluations, or `None` to never evaluate
+    ///
+    /// Must be nonzero if set
+    ///
+    /// **Default:** `None`
+    pub eval_interval: Option<usize>,
+    /// The number of greedy episodes played in each evaluation
+    ///
+    /// Must be nonzero if `eval_interval` is set
+    ///
+    /// **Default:** `10`
+    pub eval_episodes: usize,
+    /// A condition for ending training early
+    ///
+    /// **Default:** `None`
+    pub early_stopping: Option<EarlyStopping>,
+}
+
+impl Default for TrainerConfig {
+    fn default() -> Self {
+        Self {
+            max_episodes: Some(500),
+            max_steps: None,
+            eval_interval: None,
+            eval_episodes: 10,
+            early_stopping: None,
+        }
+    }
+}
+
+/// The outcome of a call to [Trainer::train]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingSummary {
+    /// The number of training episodes played
+    pub episodes: usize,
+    /// The number of training steps taken
+    pub steps: usize,
+    /// Whether training ended because the [EarlyStopping] condition was met
+    pub stopped_early: bool,
+}
+
+/// Runs the training loop for any [Agent] in any [Environment]
+///
+/// At the end of each episode, the trainer takes the environment's and the agent's [Report] (see
+/// [Environment::report] and [Agent::report]) and adds its own metrics before publishing them to every [Sink]:
+/// - `episode_reward` and `episode_steps` - The return and length of the training episode
+/// - `eval_reward`, `eval_reward_std` and `eval_steps` - The mean return, its standard deviation, and the mean length
+///   over the greedy episodes of an [evaluation](Agent::evaluate), if one was run after this episode
+/// - `eval_success` - The success rate of the evaluation, if the environment reports success
+///
+/// Evaluation episodes are played in the training environment, and anything they record to its report is discarded.
+///
+/// ```ignore
+/// let (handle, tx) = viz::init(500);
+/// let mut trainer = Trainer::new(TrainerConfig::default()).with_sink(tx);
+/// trainer.train(&mut agent, &mut env);
+/// ```
+pub struct Trainer<'a> {
+    config: TrainerConfig,
+    sinks: Vec<Box<dyn Sink + 'a>>,
+}
+
+impl<'a> Trainer<'a> {
+    /// Create a new trainer without any sinks
+    ///
+    /// **Panics** if `eval_interval` is `Some(0)`, or if `eval_interval` is set and `eval_episodes` is 0
+    pub fn new(config: TrainerConfig) -> Self {
+        if config.eval_interval.is_some() {
+            assert!(
+                config.eval_interval != Some(0),
+                "eval_interval must be greater than 0"
+            );
+            assert!(
+                config.eval_episodes > 0,
+                "eval_episodes must be greater than 0 when evaluating"
+            );
+        }
+        Self {
+            config,
+            sinks: Vec::new(),
+        }
+    }
+
+    /// Publish metrics to the given sink
+    pub fn with_sink(mut self, sink: impl Sink + 'a) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    /// Train the agent in the environment until a limit is reached or the early stopping condition is met
+    ///
+    /// If neither limit is configured, this only returns once the early stopping condition is met.
+    pub fn train<E, A>(&mut self, agent: &mut A, env: &mut E) -> TrainingSummary
+    where
+        E: Environment,
+        A: Agent<E>,
+    {
+        let mut summary = TrainingSummary {
+            episodes: 0,
+            steps: 0,
+            stopped_early: false,
+        };
+        let mut streak = 0;
+
+        while self
+            .config
+            .max_episodes
+            .map_or(true, |n| summary.episodes < n)
+            && self.config.max_steps.map_or(true, |n| summary.steps < n)
+        {
+            let stats = agent.go(env);
+            summary.episodes += 1;
+            summary.steps += stats.steps;
+
+            let mut metrics = env.report().map(Report::take).unwrap_or_default();
+            metrics.extend(agent.report().map(Report::take).unwrap_or_default());
+            metrics.extend(stats_metrics("episode", Aggregation::Sum, [stats]));
+
+            if self
+                .config
+                .eval_interval
+                .is_some_and(|n| summary.episodes % n == 0)
+            {
+                let evaluation = agent.evaluate(env, self.config.eval_episodes);
+                if let Some(report) = env.report() {
+                    report.take();
+                }
+                metrics.extend(evaluation_metrics(&evaluation));
+            }
+
+            for sink in &mut self.sinks {
+                sink.publish(summary.episodes - 1, &metrics);
+            }
+
+            if let Some(early_stopping) = &self.config.early_stopping {
+                if let Some(metric) = metrics.get(&early_stopping.metric) {
+                    streak = match early_stopping.is_met(metric.value()) {
+                        true => streak + 1,
+                        false => 0,
+                    };
+                }
+                if streak >= early_stopping.patience {
+                    summary.stopped_early = true;
+                    break;
+                }
+            }
+        }
+
+        summary
+    }
+}
+
+/// Aggregate the returns and lengths of several episodes into `{prefix}_reward` and `{prefix}_steps` metrics
+fn stats_metrics(
+    prefix: &str,
+    aggregation: Aggregation,
+    stats: impl IntoIterator<Item = EpisodeStats>,
+) -> [(String, Metric); 2] {
+    let mut reward = Metric::new(aggregation);
+    let mut steps = Metric::new(aggregation);
+    for stats in stats {
+        reward.record(stats.reward as f64);
+        steps.record(stats.steps as f64);
+    }
+
+    [
+        (format!("{prefix}_reward"), reward),
+        (format!("{prefix}_steps"), steps),
+    ]
+}
+
+/// Convert the statistics of an evaluation into `eval_*` metrics
+fn evaluation_metrics(evaluation: &Evaluation) -> Vec<(String, Metric)> {
+    let metric = |value: f64| {
+        let mut metric = Metric::new(Aggregation::Mean);
+        metric.record(value);
+        metric
+    };
+
+    let mut metrics = vec![
+        ("eval_reward".to_string(), metric(evaluation.mean_reward)),
+        ("eval_reward_std".to_string(), metric(evaluation.std_reward)),
+        ("eval_steps".to_string(), metric(evaluation.mean_steps)),
+    ];
+    if let Some(success_rate) = evaluation.success_rate {
+        metrics.push(("eval_success".to_string(), metric(success_rate)));
+    }
+
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{memory::Exp, wrappers::tests::CounterEnv};
+
+    use super::*;
+
+    /// Plays the number of finished episodes as its action while training, and `2.0` when greedy
+    #[derive(Default)]
+    struct Learner {
+        episodes: usize,
+        observed: usize,
+        report: Report,
+    }
+
+    impl Agent<CounterEnv> for Learner {
+        fn act(&mut self, _env: &mut CounterEnv, _state: [f32; 1]) -> f32 {
+            self.episodes as f32
+        }
+
+        fn act_greedy(&mut self, _env: &mut CounterEnv, _state: [f32; 1]) -> f32 {
+            2.0
+        }
+
+        fn observe(&mut self, _env: &CounterEnv, _exp: Exp<CounterEnv>) {
+            self.observed += 1;
+            self.report.record("observed", 1.0);
+        }
+
+        fn end_episode(&mut self) {
+            self.episodes += 1;
+        }
+
+        fn report(&mut self) -> Option<&mut Report> {
+            Some(&mut self.report)
+        }
+    }
+
+    #[test]
+    fn trainer_loop() {
+        let mut env = CounterEnv::new(3);
+        let mut agent = Learner::default();
+        let mut published = Vec::new();
+        let summary = Trainer::new(TrainerConfig {
+            max_episodes: Some(4),
+            eval_interval: Some(2),
+            eval_episodes: 3,
+            ..Default::default()
+        })
+        .with_sink(|episode, metrics: &Metrics| {
+            published.push((
+                episode,
+                metrics["episode_reward"].value(),
+                metrics["observed"].value(),
+                metrics.get("eval_reward").map(Metric::value),
+            ))
+        })
+        .train(&mut agent, &mut env);
+
+        assert_eq!(
+            summary,
+            TrainingSummary {
+                episodes: 4,
+                steps: 12,
+                stopped_early: false
+            },
+            "Episode limit respected"
+        );
+        assert_eq!(agent.observed, 12, "Every training step is observed");
+        assert_eq!(
+            published,
+            [
+                (0, 0.0, 3.0, None),
+                (1, 3.0, 3.0, Some(6.0)),
+                (2, 6.0, 3.0, None),
+                (3, 9.0, 3.0, Some(6.0))
+            ],
+            "Metrics published every episode with periodic evaluations"
+        );
+    }
+
+    #[test]
+    fn trainer_early_stopping() {
+        let mut env = CounterEnv::new(3);
+        let mut agent = Learner::default();
+        let summary = Trainer::new(TrainerConfig {
+            max_episodes: Some(100),
+            early_stopping: Some(EarlyStopping::above("episode_reward", 6.0).with_patience(2)),
+            ..Default::default()
+        })
+        .train(&mut agent, &mut env);
+
+        assert!(summary.stopped_early, "Training stopped early");
+        assert_eq!(
+            summary.episodes, 4,
+            "Training stopped once the target was met for 2 consecutive episodes"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "eval_episodes must be greater than 0")]
+    fn trainer_rejects_empty_evaluations() {
+        Trainer::new(TrainerConfig {
+            eval_interval: Some(5),
+            eval_episodes: 0,
+            ..Default::default()
+        });
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{
+    env::{Environment, Report, StepResult},
+    memory::Exp,
+};
+
+/// The return and length of a single episode
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EpisodeStats {
+    /// The sum of rewards received over the episode
+    pub reward: f32,
+    /// The number of steps taken
+    pub steps: usize,
+    /// Whether the episode was a success, if the final step reported a `"success"` [info](crate::env::Info) value
+    ///
+    /// Any nonzero value counts as a success
+    pub success: Option<bool>,
+}
+
+impl EpisodeStats {
+    /// Account for a step of the episode
+    fn record<S>(&mut self, step: &StepResult<S>) {
+        self.reward += step.reward;
+        self.steps += 1;
+        self.success = step.info.get("success").map(|&success| success != 0.0);
+    }
+}
+
+/// Statistics of the greedy policy of an [Agent] over several episodes (see [evaluate](Agent::evaluate))
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    /// The number of episodes played
+    pub episodes: usize,
+    /// The mean return
+    pub mean_reward: f64,
+    /// The standard deviation of the return
+    pub std_reward: f64,
+    /// The mean episode length
+    pub mean_steps: f64,
+    /// The fraction of episodes that were successful, or `None` if the environment never reported success
+    ///
+    /// Episodes that did not report success are counted as failures.
+    pub success_rate: Option<f64>,
+}
+
+impl Evaluation {
+    /// Compute the statistics of a set of episodes
+    ///
+    /// **Panics** if `episodes` is empty
+    pub fn from_episodes(episodes: &[EpisodeStats]) -> Self {
+        assert!(!episodes.is_empty(), "Cannot evaluate zero episodes");
+        let n = episodes.len() as f64;
+        let mean_reward = episodes.iter().map(|e| e.reward as f64).sum::<f64>() / n;
+        let variance = episodes
+            .iter()
+            .map(|e| (e.reward as f64 - mean_reward).powi(2))
+            .sum::<f64>()
+            / n;
+        let success_rate = episodes
+            .iter()
+            .any(|e| e.success.is_some())
+            .then(|| episodes.iter().filter(|e| e.success == Some(true)).count() as f64 / n);
+
+        Self {
+            episodes: episodes.len(),
+            mean_reward,
+            std_reward: variance.sqrt(),
+            mean_steps: episodes.iter().map(|e| e.steps as f64).sum::<f64>() / n,
+            success_rate,
+        }
+    }
+}
+
+/// A learning agent that interacts with an [Environment] one transition at a time
+///
+/// Implementors only describe how to choose actions and how to learn from a transition, and the episode loop is
+/// provided by [go](Agent::go). This lets agents be swapped freely in training code such as the
+/// [Trainer](crate::trainer::Trainer).
+pub trait Agent<E: Environment> {
+    /// Choose an action from the given state during training, possibly exploring
+    fn act(&mut self, env: &mut E, state: E::State) -> E::Action;
+
+    /// Choose the best known action from the given state without exploring
+    fn act_greedy(&mut self, env: &mut E, state: E::State) -> E::Action;
+
+    /// Learn from a transition
+    ///
+    /// This is called right after the environment has stepped, so `env` reflects the transition's next state
+    fn observe(&mut self, env: &E, exp: Exp<E>);
+
+    /// Signal the end of a training episode
+    fn end_episode(&mut self) {}
+
+    /// Get the agent's [Report] of training diagnostics, such as losses, if it keeps one
+    ///
+    /// Like [Environment::report], the [Trainer](crate::trainer::Trainer) takes this at the end of every episode.
+    fn report(&mut self) -> Option<&mut Report> {
+        None
+    }
+
+    /// Deploy the agent into the environment for one training episode
+    fn go(&mut self, env: &mut E) -> EpisodeStats {
+        let mut stats = EpisodeStats::default();
+        let mut next_state = Some(env.reset());
+        while let Some(state) = next_state {
+            let action = self.act(env, state.clone());
+            let step = env.step(action.clone());
+            stats.record(&step);
+            let StepResult {
+                next_state: next,
+                reward,
+                truncated,
+                ..
+            } = step;
+            next_state = next.clone().filter(|_| !truncated);
+
+            // Truncated experiences keep their next state so the target is still bootstrapped from it
+            self.observe(
+                env,
+                Exp {
+                    state,
+                    action,
+                    reward,
+                    next_state: next,
+                    next_mask: None,
+                    steps: 1,
+                },
+            );
+        }
+
+        self.end_episode();
+        stats
+    }
+
+    /// Deploy the agent into the environment for one episode, acting greedily without learning
+    fn play(&mut self, env: &mut E) -> EpisodeStats {
+        let mut stats = EpisodeStats::default();
+        let mut next_state = Some(env.reset());
+        while let Some(state) = next_state {
+            let action = self.act_greedy(env, state);
+            let step = env.step(action);
+            stats.record(&step);
+            next_state = step.next_state.filter(|_| !step.truncated);
+        }
+
+        stats
+    }
+
+    /// Measure the agent's greedy policy by [playing](Agent::play) `episodes` episodes without exploring or learning
+    ///
+    /// **Panics** if `episodes` is zero
+    fn evaluate(&mut self, env: &mut E, episodes: usize) -> Evaluation {
+        let episodes = (0..episodes).map(|_| self.play(env)).collect::<Vec<_>>();
+        Evaluation::from_episodes(&episodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrappers::tests::CounterEnv;
+
+    use super::*;
+
+    /// Acts with the number of greedy actions it has taken so far
+    struct Counter(usize);
+
+    impl Agent<CounterEnv> for Counter {
+        fn act(&mut self, _env: &mut CounterEnv, _state: [f32; 1]) -> f32 {
+            0.0
+        }
+
+        fn act_greedy(&mut self, _env: &mut CounterEnv, _state: [f32; 1]) -> f32 {
+            self.0 += 1;
+            (self.0 - 1) as f32
+        }
+
+        fn observe(&mut self, _env: &CounterEnv, _exp: Exp<CounterEnv>) {
+            panic!("Evaluation does not learn");
+        }
+    }
+
+    #[test]
+    fn agent_evaluate() {
+        let mut env = CounterEnv::new(2);
+        let evaluation = Counter(0).evaluate(&mut env, 2);
+        assert_eq!(
+            evaluation,
+            Evaluation {
+                episodes: 2,
+                mean_reward: 3.0,
+                std_reward: 2.0,
+                mean_steps: 2.0,
+                success_rate: None,
+            },
+            "Statistics computed over greedy episodes"
+        );
+
+        let episodes = [true, false, true, true].map(|success| EpisodeStats {
+            success: Some(success),
+            ..Default::default()
+        });
+        assert_eq!(
+            Evaluation::from_episodes(&episodes).success_rate,
+            Some(0.75),
+            "Success rate computed from reported successes"
+        );
+    }
+}
@@ -1,3 +1,5 @@
+pub mod agent;
 pub mod to_tensor;
 
+pub use agent::{Agent, EpisodeStats, Evaluation};
 pub use to_tensor::ToTensor;
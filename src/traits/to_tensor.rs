@@ -69,6 +69,22 @@ where
     }
 }
 
+impl<B, E, K, const A: usize, const C: usize> ToTensor<B, 3, K> for Vec<[[E; A]; C]>
+where
+    B: Backend,
+    E: Element,
+    K: BasicOps<B, Elem = E>,
+{
+    fn to_tensor(self, device: &B::Device) -> Tensor<B, 3, K> {
+        let len = self.len();
+        let data = Data::new(
+            self.into_iter().flatten().flatten().collect::<Vec<_>>(),
+            [len * C * A].into(),
+        );
+        Tensor::from_data(data, device).reshape([-1, C as i32, A as i32])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use burn::backend::{ndarray::NdArrayDevice, NdArray as B};
@@ -100,4 +116,18 @@ mod tests {
             "valid tensor constructed from `Vec<[E; A]>`"
         );
     }
+
+    #[test]
+    fn vec_arr_arr_impl() {
+        let device = NdArrayDevice::Cpu;
+        let x = vec![[[1f32, 2.0], [3.0, 4.0]], [[5.0, 6.0], [7.0, 8.0]]];
+        let t1: Tensor<B, 3> = x.to_tensor(&device);
+
+        let t2: Tensor<B, 3> =
+            [[[1f32, 2.0], [3.0, 4.0]], [[5.0, 6.0], [7.0, 8.0]]].to_tensor(&device);
+        assert!(
+            t1.equal(t2).all().into_scalar(),
+            "valid tensor constructed from `Vec<[[E; A]; C]>`"
+        );
+    }
 }
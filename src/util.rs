@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use rand::{rngs::StdRng, SeedableRng};
 
 /// Asserts that a numerical value is in the provided interval `[a,b]` and panics
 /// with a helpful message if not
@@ -27,6 +27,10 @@ pub(crate) fn _format_float(float: f64, precision: usize) -> String {
     }
 }
 
-pub(crate) fn summary_from_keys(keys: &[&'static str]) -> BTreeMap<&'static str, f64> {
-    keys.iter().map(|k| (*k, 0.0)).collect()
+/// Construct a random number generator from an optional seed, falling back to system entropy
+pub(crate) fn rng_from_seed(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
 }
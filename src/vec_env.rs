@@ -0,0 +1,350 @@
+use std::{
+    mem,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+use crate::{
+    env::{Environment, MaskedActionSpace, StepResult},
+    nn::legal_mask,
+};
+
+type MaskFn<E> = fn(&E) -> Option<Vec<bool>>;
+
+/// The step result, the action mask of the next state before any reset, and the state to act from next
+type EnvStep<E> = (
+    StepResult<<E as Environment>::State>,
+    Option<Vec<bool>>,
+    <E as Environment>::State,
+);
+
+/// A chunk of environments to step, with their actions
+type Job<E> = (Vec<E>, Vec<<E as Environment>::Action>, MaskFn<E>);
+
+/// A persistent thread that steps each chunk of environments it receives and sends the chunk back
+///
+/// The thread exits once its job channel is closed, when the worker is dropped.
+struct Worker<E: Environment> {
+    jobs: Sender<Job<E>>,
+    results: Receiver<(Vec<E>, Vec<EnvStep<E>>)>,
+}
+
+impl<E> Worker<E>
+where
+    E: Environment + Send + 'static,
+    E::State: Send,
+    E::Action: Send,
+{
+    fn spawn() -> Self {
+        let (jobs, job_rx) = mpsc::channel::<Job<E>>();
+        let (result_tx, results) = mpsc::channel();
+        thread::spawn(move || {
+            for (mut envs, actions, mask) in job_rx {
+                let steps = step_sequential(&mut envs, actions, mask);
+                if result_tx.send((envs, steps)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self { jobs, results }
+    }
+}
+
+/// The result of taking a step in a [VecEnv]
+#[derive(Debug, Clone, PartialEq)]
+pub struct VecStepResult<S> {
+    /// The step result of each environment, as returned by [Environment::step] before any automatic reset
+    ///
+    /// Use these to build experiences, since they preserve the final state of truncated episodes
+    pub steps: Vec<StepResult<S>>,
+    /// The current state of each environment, which is the initial state of a new episode for environments that
+    /// finished on this step
+    ///
+    /// Use these to choose the next actions
+    pub states: Vec<S>,
+}
+
+impl<S> VecStepResult<S> {
+    /// The reward received by each environment
+    pub fn rewards(&self) -> Vec<f32> {
+        self.steps.iter().map(|s| s.reward).collect()
+    }
+
+    /// Whether each environment reached a terminal state
+    pub fn terminated(&self) -> Vec<bool> {
+        self.steps.iter().map(StepResult::is_terminated).collect()
+    }
+
+    /// Whether each environment was truncated
+    pub fn truncated(&self) -> Vec<bool> {
+        self.steps.iter().map(|s| s.truncated).collect()
+    }
+}
+
+/// A vectorized environment that steps several copies of an [Environment] in lockstep
+///
+/// Environments that finish an episode, by termination or truncation, are reset automatically, so the
+/// [states](VecEnv::states) are always ready for choosing the next batch of actions. Since states are returned as a
+/// `Vec`, they convert directly into a batched tensor through [ToTensor](crate::traits::ToTensor).
+///
+/// By default, environments are stepped sequentially on the calling thread. Use [with_threads](VecEnv::with_threads)
+/// to step them in parallel on a pool of worker threads.
+pub struct VecEnv<E: Environment> {
+    envs: Vec<E>,
+    states: Vec<E::State>,
+    workers: Vec<Worker<E>>,
+}
+
+impl<E: Environment> VecEnv<E> {
+    /// Create a vectorized environment from several copies of an environment
+    ///
+    /// The environments must be [reset](VecEnv::reset) before stepping
+    ///
+    /// **Panics** if `envs` is empty
+    pub fn new(envs: Vec<E>) -> Self {
+        assert!(!envs.is_empty(), "VecEnv requires at least one environment");
+        Self {
+            envs,
+            states: Vec::new(),
+            workers: Vec::new(),
+        }
+    }
+
+    /// Create a vectorized environment of `n` environments created by `f`
+    ///
+    /// **Panics** if `n` is 0
+    pub fn from_fn(n: usize, f: impl FnMut(usize) -> E) -> Self {
+        Self::new((0..n).map(f).collect())
+    }
+
+    /// The number of environments
+    pub fn len(&self) -> usize {
+        self.envs.len()
+    }
+
+    /// Always `false`, since a `VecEnv` cannot be empty
+    pub fn is_empty(&self) -> bool {
+        self.envs.is_empty()
+    }
+
+    /// Get a reference to the wrapped environments
+    pub fn envs(&self) -> &[E] {
+        &self.envs
+    }
+
+    /// The current state of each environment, or an empty slice if the environments have not been reset yet
+    pub fn states(&self) -> &[E::State] {
+        &self.states
+    }
+
+    /// Seed each environment, where environment `i` is seeded with `seed + i`
+    pub fn seed(&mut self, seed: u64) {
+        for (i, env) in self.envs.iter_mut().enumerate() {
+            env.seed(seed.wrapping_add(i as u64));
+        }
+    }
+
+    /// Reset all environments
+    ///
+    /// **Returns** the initial state of each environment
+    pub fn reset(&mut self) -> Vec<E::State> {
+        self.states = self.envs.iter_mut().map(E::reset).collect();
+        self.states.clone()
+    }
+
+    /// Step every environment with its corresponding action, resetting environments whose episodes end
+    ///
+    /// **Panics** if the number of actions does not match the number of environments, or if the environments
+    /// have not been reset
+    pub fn step(&mut self, actions: Vec<E::Action>) -> VecStepResult<E::State> {
+        self.step_with(actions, |_| None).0
+    }
+
+    fn step_with(
+        &mut self,
+        actions: Vec<E::Action>,
+        mask: MaskFn<E>,
+    ) -> (VecStepResult<E::State>, Vec<Option<Vec<bool>>>) {
+        assert_eq!(
+            actions.len(),
+            self.envs.len(),
+            "Exactly one action must be given per environment"
+        );
+        assert!(
+            !self.states.is_empty(),
+            "VecEnv must be reset before stepping"
+        );
+
+        let results = if self.workers.is_empty() {
+            step_sequential(&mut self.envs, actions, mask)
+        } else {
+            self.step_parallel(actions, mask)
+        };
+
+        let mut steps = Vec::with_capacity(self.envs.len());
+        let mut final_masks = Vec::with_capacity(self.envs.len());
+        let mut states = Vec::with_capacity(self.envs.len());
+        for (step, final_mask, state) in results {
+            steps.push(step);
+            final_masks.push(final_mask);
+            states.push(state);
+        }
+        self.states.clone_from(&states);
+
+        (VecStepResult { steps, states }, final_masks)
+    }
+
+    /// Send a contiguous chunk of the environments to each worker and collect them back in order
+    fn step_parallel(&mut self, actions: Vec<E::Action>, mask: MaskFn<E>) -> Vec<EnvStep<E>> {
+        let chunk_size = self.envs.len().div_ceil(self.workers.len());
+        let mut envs = mem::take(&mut self.envs).into_iter();
+        let mut actions = actions.into_iter();
+        for worker in &self.workers {
+            let chunk = envs.by_ref().take(chunk_size).collect::<Vec<_>>();
+            let actions = actions.by_ref().take(chunk.len()).collect();
+            worker
+                .jobs
+                .send((chunk, actions, mask))
+                .expect("Environment thread panicked");
+        }
+
+        let mut results = Vec::new();
+        for worker in &self.workers {
+            let (envs, steps) = worker.results.recv().expect("Environment thread panicked");
+            self.envs.extend(envs);
+            results.extend(steps);
+        }
+        results
+    }
+
+    /// Select a random action in environment `index`
+    pub fn random_action(&mut self, index: usize) -> E::Action {
+        self.envs[index].random_action()
+    }
+
+    /// Select a random action in each environment
+    pub fn random_actions(&mut self) -> Vec<E::Action> {
+        self.envs.iter_mut().map(E::random_action).collect()
+    }
+
+    /// Unwrap the vectorized environment, returning the environments
+    pub fn into_inner(self) -> Vec<E> {
+        self.envs
+    }
+}
+
+impl<E> VecEnv<E>
+where
+    E: MaskedActionSpace,
+    E::Action: Into<usize>,
+{
+    /// Step every environment like [step](VecEnv::step), also capturing the action mask of each environment's next
+    /// state before it is reset
+    ///
+    /// Since finished environments are reset automatically, this is the only way to know the legal actions in the
+    /// final state of a truncated episode.
+    ///
+    /// **Returns** the step result and, for each environment, the legal actions in its next state as a mask over
+    /// action indices, or `None` if every action is legal or the episode terminated
+    ///
+    /// **Panics** if the number of actions does not match the number of environments, or if the environments
+    /// have not been reset
+    pub fn step_masked(
+        &mut self,
+        actions: Vec<E::Action>,
+    ) -> (VecStepResult<E::State>, Vec<Option<Vec<bool>>>) {
+        self.step_with(actions, legal_mask)
+    }
+}
+
+impl<E> VecEnv<E>
+where
+    E: Environment + Send + 'static,
+    E::State: Send,
+    E::Action: Send,
+{
+    /// Step the environments in parallel on a pool of `num_threads` worker threads, capped at the number of
+    /// environments
+    ///
+    /// The workers are spawned once and live as long as the `VecEnv`. On each step, the environments are split
+    /// into contiguous chunks that are moved to the workers and back, so parallelism pays off when stepping an
+    /// environment is expensive relative to a round trip over a channel. A `num_threads` of 1 steps the
+    /// environments sequentially on the calling thread.
+    ///
+    /// **Panics** if `num_threads` is 0
+    pub fn with_threads(mut self, num_threads: usize) -> Self {
+        assert!(num_threads > 0, "num_threads must be greater than 0");
+        let num_threads = num_threads.min(self.envs.len());
+        self.workers = if num_threads > 1 {
+            (0..num_threads).map(|_| Worker::spawn()).collect()
+        } else {
+            Vec::new()
+        };
+        self
+    }
+}
+
+/// Step an environment, resetting it if the episode ends
+fn step_auto_reset<E: Environment>(env: &mut E, action: E::Action, mask: MaskFn<E>) -> EnvStep<E> {
+    let step = env.step(action);
+    let final_mask = step.next_state.as_ref().and_then(|_| mask(env));
+    let state = match &step.next_state {
+        Some(state) if !step.truncated => state.clone(),
+        _ => env.reset(),
+    };
+    (step, final_mask, state)
+}
+
+fn step_sequential<E: Environment>(
+    envs: &mut [E],
+    actions: Vec<E::Action>,
+    mask: MaskFn<E>,
+) -> Vec<EnvStep<E>> {
+    envs.iter_mut()
+        .zip(actions)
+        .map(|(env, action)| step_auto_reset(env, action, mask))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrappers::tests::CounterEnv;
+
+    use super::*;
+
+    #[test]
+    fn vec_env_auto_reset() {
+        let mut envs = VecEnv::from_fn(3, |i| CounterEnv::new(i as u32 + 1));
+        assert_eq!(envs.reset(), [[0.0]; 3], "All environments reset");
+
+        let result = envs.step(vec![1.0; 3]);
+        assert_eq!(
+            result.terminated(),
+            [true, false, false],
+            "First environment terminated"
+        );
+        assert_eq!(
+            result.states,
+            [[0.0], [1.0], [1.0]],
+            "Terminated environment was reset"
+        );
+        assert_eq!(envs.states(), result.states, "States are tracked");
+    }
+
+    #[test]
+    fn vec_env_parallel() {
+        let mut sequential = VecEnv::from_fn(5, |i| CounterEnv::new(i as u32 + 1));
+        let mut parallel = VecEnv::from_fn(5, |i| CounterEnv::new(i as u32 + 1)).with_threads(2);
+        sequential.reset();
+        parallel.reset();
+
+        for _ in 0..4 {
+            assert_eq!(
+                sequential.step(vec![1.0; 5]),
+                parallel.step(vec![1.0; 5]),
+                "Parallel stepping matches sequential stepping"
+            );
+        }
+    }
+}
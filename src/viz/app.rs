@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     io,
     sync::mpsc::{Receiver, TryRecvError},
     time::Duration,
@@ -16,6 +17,7 @@ use crossterm::event::{
 use ratatui::{prelude::*, widgets::*};
 
 use super::tui;
+use crate::env::Metric;
 
 const TABS: [&str; 2] = ["Plots", "Logs"];
 
@@ -28,9 +30,12 @@ pub enum AppMode {
 }
 
 /// Format for updating plot data
+///
+/// Each metric is plotted under its name, so updates may add new metrics or omit existing ones.
+/// Typically built from [Report::take](crate::env::Report::take).
 pub struct Update {
     pub episode: u16,
-    pub data: Vec<f64>,
+    pub data: BTreeMap<String, Metric>,
 }
 
 /// The root TUI component which holds the main app state and runs the render loop
@@ -45,14 +50,14 @@ pub struct App {
 }
 
 impl App {
-    pub fn new(plots: &[&'static str], episodes: u16) -> Self {
+    pub fn new(episodes: u16) -> Self {
         Self {
             state: Default::default(),
             episode: 0,
             total_episodes: episodes,
             selected_tab: 0,
             show_help: false,
-            plots: Plots::new(plots.to_vec(), episodes),
+            plots: Plots::new(episodes),
             logs: Logs::new(),
         }
     }
@@ -126,21 +126,19 @@ impl WidgetRef for Plot {
 }
 
 pub struct Plots {
-    plot_names: Vec<&'static str>,
+    plot_names: Vec<String>,
     plots: Vec<Plot>,
     selected: usize,
+    episodes: u16,
 }
 
 impl Plots {
-    pub fn new(names: Vec<&'static str>, episodes: u16) -> Self {
-        let plots = names
-            .iter()
-            .map(|k| Plot::new(k).with_x_bounds([0.0, episodes.into()]))
-            .collect();
+    pub fn new(episodes: u16) -> Self {
         Self {
-            plot_names: names,
-            plots,
+            plot_names: Vec::new(),
+            plots: Vec::new(),
             selected: 0,
+            episodes,
         }
     }
 
@@ -149,25 +147,39 @@ impl Plots {
     }
 
     pub fn next_plot(&mut self) {
-        self.selected = (self.selected + 1) % self.len()
+        if !self.plots.is_empty() {
+            self.selected = (self.selected + 1) % self.len()
+        }
     }
 
     pub fn prev_plot(&mut self) {
         let len = self.len();
-        self.selected = (self.selected + len - 1) % len;
+        if len > 0 {
+            self.selected = (self.selected + len - 1) % len;
+        }
     }
 
     pub fn update(&mut self, update: Update) {
         let Update { episode, data } = update;
-        for (i, metric) in data.iter().enumerate() {
-            self.plots[i].update((episode as f64, *metric));
+        for (name, metric) in data {
+            let i = match self.plot_names.iter().position(|n| *n == name) {
+                Some(i) => i,
+                None => {
+                    let y_label = format!("{name} ({})", metric.aggregation());
+                    self.plots
+                        .push(Plot::new(&y_label).with_x_bounds([0.0, self.episodes.into()]));
+                    self.plot_names.push(name);
+                    self.plots.len() - 1
+                }
+            };
+            self.plots[i].update((episode as f64, metric.value()));
         }
     }
 }
 
 impl WidgetRef for Plots {
     fn render_ref(&self, area: Rect, buf: &mut Buffer) {
-        Tabs::new(self.plot_names.iter().copied())
+        Tabs::new(self.plot_names.iter().map(String::as_str))
             .block(Block::default().padding(Padding::uniform(2)))
             .white()
             .highlight_style(Style::default().light_green())
@@ -19,23 +19,23 @@ pub use app::Update;
 
 /// Initialize the viz training dashboard TUI in a separate thread
 ///
-/// Sets up a global [logger](log) that sends log data to the TUI through the log macros
+/// Sets up a global [logger](log) that sends log data to the TUI through the log macros. A plot is added for each
+/// metric the first time it appears in an [Update].
 ///
 /// ### Arguments
-/// - `plots` - The names of the plots to render in the TUI
 /// - `episodes` - The number of episodes to show on the x-axis
 ///
 /// ### Returns
 /// A tuple `(handle, tx)`
 /// - `handle` - The [JoinHandle] of the TUI thread
 /// - `tx` - A [mpsc::Sender] for transmitting plot data updates to the TUI
-pub fn init(plots: &[&'static str], episodes: u16) -> (JoinHandle<io::Result<()>>, Sender<Update>) {
+pub fn init(episodes: u16) -> (JoinHandle<io::Result<()>>, Sender<Update>) {
     tui_logger::init_logger(log::LevelFilter::Trace).unwrap();
     tui_logger::set_default_level(log::LevelFilter::Warn);
     tui_logger::set_level_for_target("tui", log::LevelFilter::Trace);
     tui_logger::move_events();
 
-    let mut app = App::new(plots, episodes);
+    let mut app = App::new(episodes);
     let (tx, rx) = mpsc::channel();
     let handle = thread::spawn(move || app.run(rx));
 
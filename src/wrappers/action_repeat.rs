@@ -0,0 +1,140 @@
+use crate::{
+    env::{
+        DiscreteActionSpace, DiscreteStateSpace, Environment, MaskedActionSpace, Report, Spaces,
+        StepResult,
+    },
+    space::Space,
+};
+
+use super::Wrapper;
+
+/// Repeats each action for a fixed number of steps, summing the rewards
+///
+/// Repetition stops early if the episode ends. The info of the final inner step is returned.
+#[derive(Debug, Clone)]
+pub struct ActionRepeat<E: Environment> {
+    env: E,
+    repeats: usize,
+}
+
+impl<E: Environment> ActionRepeat<E> {
+    /// Wrap an environment, repeating each action `repeats` times
+    ///
+    /// **Panics** if `repeats` is 0
+    pub fn new(env: E, repeats: usize) -> Self {
+        assert!(repeats > 0, "repeats must be greater than 0");
+        Self { env, repeats }
+    }
+}
+
+impl<E: Environment> Environment for ActionRepeat<E> {
+    type State = E::State;
+    type Action = E::Action;
+
+    fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
+        let mut step = self.env.step(action.clone());
+        let mut reward = step.reward;
+        for _ in 1..self.repeats {
+            if step.is_done() {
+                break;
+            }
+            step = self.env.step(action.clone());
+            reward += step.reward;
+        }
+        step.reward = reward;
+        step
+    }
+
+    fn reset(&mut self) -> Self::State {
+        self.env.reset()
+    }
+
+    fn random_action(&mut self) -> Self::Action {
+        self.env.random_action()
+    }
+
+    fn seed(&mut self, seed: u64) {
+        self.env.seed(seed)
+    }
+
+    fn is_active(&self) -> bool {
+        self.env.is_active()
+    }
+
+    fn report(&mut self) -> Option<&mut Report> {
+        self.env.report()
+    }
+}
+
+impl<E: Environment> Wrapper for ActionRepeat<E> {
+    type Inner = E;
+
+    fn inner(&self) -> &E {
+        &self.env
+    }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.env
+    }
+
+    fn into_inner(self) -> E {
+        self.env
+    }
+}
+
+impl<E: DiscreteActionSpace> DiscreteActionSpace for ActionRepeat<E> {
+    fn actions(&self) -> Vec<Self::Action> {
+        self.env.actions()
+    }
+}
+
+impl<E: MaskedActionSpace> MaskedActionSpace for ActionRepeat<E> {
+    fn num_actions(&self) -> usize {
+        self.env.num_actions()
+    }
+
+    fn action_mask(&self) -> Vec<bool>
+    where
+        Self::Action: Into<usize>,
+    {
+        self.env.action_mask()
+    }
+}
+
+impl<E: DiscreteStateSpace> DiscreteStateSpace for ActionRepeat<E> {
+    fn states(&self) -> Vec<Self::State> {
+        self.env.states()
+    }
+}
+
+impl<E: Spaces> Spaces for ActionRepeat<E> {
+    fn observation_space(&self) -> Space {
+        self.env.observation_space()
+    }
+
+    fn action_space(&self) -> Space {
+        self.env.action_space()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrappers::tests::CounterEnv;
+
+    use super::*;
+
+    #[test]
+    fn action_repeat_sums_rewards() {
+        let mut env = ActionRepeat::new(CounterEnv::new(5), 2);
+        env.reset();
+
+        let step = env.step(1.0);
+        assert_eq!(step.next_state, Some([2.0]), "Action was repeated");
+        assert_eq!(step.reward, 2.0, "Rewards were summed");
+
+        env.step(1.0);
+        let step = env.step(1.0);
+        assert!(step.is_terminated(), "Episode terminated");
+        assert_eq!(step.reward, 1.0, "Repetition stops when the episode ends");
+    }
+}
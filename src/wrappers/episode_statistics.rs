@@ -0,0 +1,138 @@
+use crate::{
+    env::{
+        DiscreteActionSpace, DiscreteStateSpace, Environment, MaskedActionSpace, Report, Spaces,
+        StepResult,
+    },
+    space::Space,
+};
+
+use super::Wrapper;
+
+/// Records the return and length of each episode in a [Report]
+///
+/// The report has the keys `"reward"` and `"steps"`, accumulated over every step since it was last
+/// [taken](Report::take). Take the report at the end of each episode to get per-episode statistics.
+#[derive(Debug, Clone)]
+pub struct EpisodeStatistics<E: Environment> {
+    env: E,
+    report: Report,
+}
+
+impl<E: Environment> EpisodeStatistics<E> {
+    /// Wrap an environment, recording its episode statistics
+    pub fn new(env: E) -> Self {
+        Self {
+            env,
+            report: Report::new(vec!["reward", "steps"]),
+        }
+    }
+}
+
+impl<E: Environment> Environment for EpisodeStatistics<E> {
+    type State = E::State;
+    type Action = E::Action;
+
+    fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
+        let step = self.env.step(action);
+        self.report.record("reward", step.reward as f64);
+        self.report.record("steps", 1.0);
+        step
+    }
+
+    fn reset(&mut self) -> Self::State {
+        self.env.reset()
+    }
+
+    fn random_action(&mut self) -> Self::Action {
+        self.env.random_action()
+    }
+
+    fn seed(&mut self, seed: u64) {
+        self.env.seed(seed)
+    }
+
+    fn is_active(&self) -> bool {
+        self.env.is_active()
+    }
+
+    fn report(&mut self) -> Option<&mut Report> {
+        Some(&mut self.report)
+    }
+}
+
+impl<E: Environment> Wrapper for EpisodeStatistics<E> {
+    type Inner = E;
+
+    fn inner(&self) -> &E {
+        &self.env
+    }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.env
+    }
+
+    fn into_inner(self) -> E {
+        self.env
+    }
+}
+
+impl<E: DiscreteActionSpace> DiscreteActionSpace for EpisodeStatistics<E> {
+    fn actions(&self) -> Vec<Self::Action> {
+        self.env.actions()
+    }
+}
+
+impl<E: MaskedActionSpace> MaskedActionSpace for EpisodeStatistics<E> {
+    fn num_actions(&self) -> usize {
+        self.env.num_actions()
+    }
+
+    fn action_mask(&self) -> Vec<bool>
+    where
+        Self::Action: Into<usize>,
+    {
+        self.env.action_mask()
+    }
+}
+
+impl<E: DiscreteStateSpace> DiscreteStateSpace for EpisodeStatistics<E> {
+    fn states(&self) -> Vec<Self::State> {
+        self.env.states()
+    }
+}
+
+impl<E: Spaces> Spaces for EpisodeStatistics<E> {
+    fn observation_space(&self) -> Space {
+        self.env.observation_space()
+    }
+
+    fn action_space(&self) -> Space {
+        self.env.action_space()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrappers::{tests::CounterEnv, TimeLimit};
+
+    use super::*;
+
+    #[test]
+    fn episode_statistics_report() {
+        let mut env = EpisodeStatistics::new(TimeLimit::new(CounterEnv::new(10), 4));
+        let mut state = Some(env.reset());
+        while state.is_some() {
+            let step = env.step(0.5);
+            state = step.next_state.filter(|_| !step.truncated);
+        }
+
+        let report = env.report.take();
+        assert_eq!(report["reward"].value(), 2.0, "Episode return recorded");
+        assert_eq!(report["steps"].value(), 4.0, "Episode length recorded");
+        assert_eq!(
+            env.report["steps"].value(),
+            0.0,
+            "Taking the report resets it"
+        );
+    }
+}
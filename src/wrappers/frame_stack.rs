@@ -0,0 +1,158 @@
+use std::collections::VecDeque;
+
+use crate::{
+    env::{DiscreteActionSpace, Environment, MaskedActionSpace, Report, Spaces, StepResult},
+    space::Space,
+};
+
+use super::Wrapper;
+
+/// Stacks the `K` most recent observations into a single state
+///
+/// The state is an array of observations ordered from oldest to newest. On reset, the initial observation
+/// is repeated `K` times.
+///
+/// ### Generics
+/// - `E` - The wrapped [Environment]
+/// - `K` - The number of observations to stack
+#[derive(Debug, Clone)]
+pub struct FrameStack<E: Environment, const K: usize> {
+    env: E,
+    frames: VecDeque<E::State>,
+}
+
+impl<E: Environment, const K: usize> FrameStack<E, K> {
+    /// Wrap an environment, stacking its `K` most recent observations
+    ///
+    /// **Panics** if `K` is 0
+    pub fn new(env: E) -> Self {
+        assert!(K > 0, "K must be greater than 0");
+        Self {
+            env,
+            frames: VecDeque::with_capacity(K),
+        }
+    }
+
+    fn stacked(&self) -> [E::State; K] {
+        std::array::from_fn(|i| self.frames[i].clone())
+    }
+}
+
+impl<E: Environment, const K: usize> Environment for FrameStack<E, K> {
+    type State = [E::State; K];
+    type Action = E::Action;
+
+    fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
+        let step = self.env.step(action);
+        if let Some(state) = &step.next_state {
+            self.frames.pop_front();
+            self.frames.push_back(state.clone());
+        }
+        step.map(|_| self.stacked())
+    }
+
+    fn reset(&mut self) -> Self::State {
+        let state = self.env.reset();
+        self.frames.clear();
+        self.frames.resize(K, state);
+        self.stacked()
+    }
+
+    fn random_action(&mut self) -> Self::Action {
+        self.env.random_action()
+    }
+
+    fn seed(&mut self, seed: u64) {
+        self.env.seed(seed)
+    }
+
+    fn is_active(&self) -> bool {
+        self.env.is_active()
+    }
+
+    fn report(&mut self) -> Option<&mut Report> {
+        self.env.report()
+    }
+}
+
+impl<E: Environment, const K: usize> Wrapper for FrameStack<E, K> {
+    type Inner = E;
+
+    fn inner(&self) -> &E {
+        &self.env
+    }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.env
+    }
+
+    fn into_inner(self) -> E {
+        self.env
+    }
+}
+
+impl<E: DiscreteActionSpace, const K: usize> DiscreteActionSpace for FrameStack<E, K> {
+    fn actions(&self) -> Vec<Self::Action> {
+        self.env.actions()
+    }
+}
+
+impl<E: MaskedActionSpace, const K: usize> MaskedActionSpace for FrameStack<E, K> {
+    fn num_actions(&self) -> usize {
+        self.env.num_actions()
+    }
+
+    fn action_mask(&self) -> Vec<bool>
+    where
+        Self::Action: Into<usize>,
+    {
+        self.env.action_mask()
+    }
+}
+
+impl<E: Spaces, const K: usize> Spaces for FrameStack<E, K> {
+    /// A box with a leading dimension of size `K` if the wrapped observation space is a box,
+    /// otherwise a tuple of `K` copies of the wrapped observation space
+    fn observation_space(&self) -> Space {
+        match self.env.observation_space() {
+            Space::Box { shape, low, high } => Space::bounded_elementwise(
+                &[&[K], shape.as_slice()].concat(),
+                low.repeat(K),
+                high.repeat(K),
+            ),
+            space => Space::tuple(vec![space; K]),
+        }
+    }
+
+    fn action_space(&self) -> Space {
+        self.env.action_space()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrappers::tests::CounterEnv;
+
+    use super::*;
+
+    #[test]
+    fn frame_stack_functional() {
+        let mut env = FrameStack::<_, 3>::new(CounterEnv::new(3));
+        assert_eq!(
+            env.reset(),
+            [[0.0], [0.0], [0.0]],
+            "Initial observation is repeated"
+        );
+        assert_eq!(
+            env.step(1.0).next_state,
+            Some([[0.0], [0.0], [1.0]]),
+            "Newest observation is last"
+        );
+        assert_eq!(
+            env.step(1.0).next_state,
+            Some([[0.0], [1.0], [2.0]]),
+            "Oldest observation is dropped"
+        );
+        assert!(env.step(1.0).is_terminated(), "Termination is forwarded");
+    }
+}
@@ -0,0 +1,82 @@
+//! Wrappers are [Environment]s that wrap another environment to modify its behavior without re-implementing it.
+//!
+//! Wrappers can be composed by nesting them, for example
+//! `EpisodeStatistics::new(TimeLimit::new(ClipReward::new(env, -1.0, 1.0), 500))`.
+//! Where possible, wrappers forward [DiscreteActionSpace](crate::env::DiscreteActionSpace),
+//! [DiscreteStateSpace](crate::env::DiscreteStateSpace), and [Spaces](crate::env::Spaces) to the wrapped environment.
+
+mod action_repeat;
+mod episode_statistics;
+mod frame_stack;
+mod normalize;
+mod reward;
+mod time_limit;
+
+pub use action_repeat::ActionRepeat;
+pub use episode_statistics::EpisodeStatistics;
+pub use frame_stack::FrameStack;
+pub use normalize::NormalizeObservation;
+pub use reward::{ClipReward, ScaleReward};
+pub use time_limit::TimeLimit;
+
+use crate::env::Environment;
+
+/// An [Environment] that wraps another environment
+pub trait Wrapper: Environment {
+    /// The type of the wrapped environment
+    type Inner: Environment;
+
+    /// Get a reference to the wrapped environment
+    fn inner(&self) -> &Self::Inner;
+
+    /// Get a mutable reference to the wrapped environment
+    fn inner_mut(&mut self) -> &mut Self::Inner;
+
+    /// Unwrap the wrapper, returning the wrapped environment
+    fn into_inner(self) -> Self::Inner;
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::env::{DiscreteActionSpace, StepResult};
+
+    use super::*;
+
+    /// Counts steps, terminating after `limit` steps, and rewards the agent with the action it takes
+    pub(crate) struct CounterEnv {
+        pub count: u32,
+        pub limit: u32,
+    }
+
+    impl CounterEnv {
+        pub fn new(limit: u32) -> Self {
+            Self { count: 0, limit }
+        }
+    }
+
+    impl Environment for CounterEnv {
+        type State = [f32; 1];
+        type Action = f32;
+
+        fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
+            self.count += 1;
+            let next_state = (self.count < self.limit).then_some([self.count as f32]);
+            StepResult::new(next_state, action)
+        }
+
+        fn reset(&mut self) -> Self::State {
+            self.count = 0;
+            [0.0]
+        }
+
+        fn random_action(&mut self) -> Self::Action {
+            1.0
+        }
+    }
+
+    impl DiscreteActionSpace for CounterEnv {
+        fn actions(&self) -> Vec<Self::Action> {
+            vec![1.0]
+        }
+    }
+}
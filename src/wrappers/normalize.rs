@@ -0,0 +1,231 @@
+use crate::{
+    env::{DiscreteActionSpace, Environment, MaskedActionSpace, Report, Spaces, StepResult},
+    space::Space,
+};
+
+use super::Wrapper;
+
+/// Normalizes observations to zero mean and unit variance using running statistics
+///
+/// Each element of the observation is normalized independently with a running mean and variance
+/// computed with Welford's algorithm, then clipped to `[-clip, clip]`. Statistics are updated on every observation
+/// until the wrapper is [frozen](NormalizeObservation::freeze), e.g. for evaluation.
+///
+/// ### Generics
+/// - `E` - The wrapped [Environment], whose state must be viewable as a mutable slice of floats, such as `[f32; N]`
+#[derive(Debug, Clone)]
+pub struct NormalizeObservation<E: Environment> {
+    env: E,
+    count: f64,
+    mean: Vec<f64>,
+    m2: Vec<f64>,
+    epsilon: f64,
+    clip: f32,
+    frozen: bool,
+}
+
+impl<E> NormalizeObservation<E>
+where
+    E: Environment,
+    E::State: AsRef<[f32]> + AsMut<[f32]>,
+{
+    /// Wrap an environment, normalizing its observations and clipping them to `[-10, 10]`
+    pub fn new(env: E) -> Self {
+        Self::with_clip(env, 10.0)
+    }
+
+    /// Wrap an environment, normalizing its observations and clipping them to `[-clip, clip]`
+    ///
+    /// **Panics** if `clip` is not positive
+    pub fn with_clip(env: E, clip: f32) -> Self {
+        assert!(clip > 0.0, "clip must be positive");
+        Self {
+            env,
+            count: 0.0,
+            mean: Vec::new(),
+            m2: Vec::new(),
+            epsilon: 1e-8,
+            clip,
+            frozen: false,
+        }
+    }
+
+    /// Stop or resume updating the running statistics
+    pub fn freeze(&mut self, frozen: bool) {
+        self.frozen = frozen;
+    }
+
+    /// The running mean of each observation element
+    pub fn mean(&self) -> &[f64] {
+        &self.mean
+    }
+
+    /// The running variance of each observation element
+    pub fn variance(&self) -> Vec<f64> {
+        self.m2
+            .iter()
+            .map(|m2| {
+                if self.count > 0.0 {
+                    m2 / self.count
+                } else {
+                    1.0
+                }
+            })
+            .collect()
+    }
+
+    /// Update the running statistics with a raw observation and normalize it in place
+    fn normalize(&mut self, mut state: E::State) -> E::State {
+        let x = state.as_mut();
+        if self.mean.is_empty() {
+            self.mean = vec![0.0; x.len()];
+            self.m2 = vec![0.0; x.len()];
+        }
+
+        if !self.frozen {
+            self.count += 1.0;
+            for ((&x, mean), m2) in x.iter().zip(&mut self.mean).zip(&mut self.m2) {
+                let delta = x as f64 - *mean;
+                *mean += delta / self.count;
+                *m2 += delta * (x as f64 - *mean);
+            }
+        }
+
+        let variance = self.variance();
+        for ((x, mean), var) in x.iter_mut().zip(&self.mean).zip(variance) {
+            let z = (*x as f64 - mean) / (var + self.epsilon).sqrt();
+            *x = (z as f32).clamp(-self.clip, self.clip);
+        }
+
+        state
+    }
+}
+
+impl<E> Environment for NormalizeObservation<E>
+where
+    E: Environment,
+    E::State: AsRef<[f32]> + AsMut<[f32]>,
+{
+    type State = E::State;
+    type Action = E::Action;
+
+    fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
+        let mut step = self.env.step(action);
+        step.next_state = step.next_state.map(|s| self.normalize(s));
+        step
+    }
+
+    fn reset(&mut self) -> Self::State {
+        let state = self.env.reset();
+        self.normalize(state)
+    }
+
+    fn random_action(&mut self) -> Self::Action {
+        self.env.random_action()
+    }
+
+    fn seed(&mut self, seed: u64) {
+        self.env.seed(seed)
+    }
+
+    fn is_active(&self) -> bool {
+        self.env.is_active()
+    }
+
+    fn report(&mut self) -> Option<&mut Report> {
+        self.env.report()
+    }
+}
+
+impl<E> Wrapper for NormalizeObservation<E>
+where
+    E: Environment,
+    E::State: AsRef<[f32]> + AsMut<[f32]>,
+{
+    type Inner = E;
+
+    fn inner(&self) -> &E {
+        &self.env
+    }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.env
+    }
+
+    fn into_inner(self) -> E {
+        self.env
+    }
+}
+
+impl<E> DiscreteActionSpace for NormalizeObservation<E>
+where
+    E: DiscreteActionSpace,
+    E::State: AsRef<[f32]> + AsMut<[f32]>,
+{
+    fn actions(&self) -> Vec<Self::Action> {
+        self.env.actions()
+    }
+}
+
+impl<E> MaskedActionSpace for NormalizeObservation<E>
+where
+    E: MaskedActionSpace,
+    E::State: AsRef<[f32]> + AsMut<[f32]>,
+{
+    fn num_actions(&self) -> usize {
+        self.env.num_actions()
+    }
+
+    fn action_mask(&self) -> Vec<bool>
+    where
+        Self::Action: Into<usize>,
+    {
+        self.env.action_mask()
+    }
+}
+
+impl<E> Spaces for NormalizeObservation<E>
+where
+    E: Spaces,
+    E::State: AsRef<[f32]> + AsMut<[f32]>,
+{
+    /// A box with the same shape as the wrapped observation space, bounded by the clip value
+    fn observation_space(&self) -> Space {
+        match self.env.observation_space() {
+            Space::Box { shape, .. } => Space::bounded(&shape, -self.clip, self.clip),
+            space => Space::bounded(&[space.flat_dim()], -self.clip, self.clip),
+        }
+    }
+
+    fn action_space(&self) -> Space {
+        self.env.action_space()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrappers::tests::CounterEnv;
+
+    use super::*;
+
+    #[test]
+    fn normalize_observation_running_stats() {
+        let mut env = NormalizeObservation::new(CounterEnv::new(10));
+        env.reset();
+        for _ in 0..4 {
+            env.step(1.0);
+        }
+
+        // Observed 0, 1, 2, 3, 4
+        assert_eq!(env.mean(), [2.0], "Running mean is correct");
+        assert_eq!(env.variance(), [2.0], "Running variance is correct");
+
+        env.freeze(true);
+        let state = env.step(1.0).next_state.unwrap();
+        assert_eq!(env.mean(), [2.0], "Frozen statistics are not updated");
+        assert!(
+            (state[0] - 3.0 / 2f32.sqrt()).abs() < 1e-5,
+            "Observation is normalized"
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{
+    env::{
+        DiscreteActionSpace, DiscreteStateSpace, Environment, MaskedActionSpace, Report, Spaces,
+        StepResult,
+    },
+    space::Space,
+};
+
+use super::Wrapper;
+
+/// Clips rewards to the interval `[min, max]`
+#[derive(Debug, Clone)]
+pub struct ClipReward<E: Environment> {
+    env: E,
+    min: f32,
+    max: f32,
+}
+
+impl<E: Environment> ClipReward<E> {
+    /// Wrap an environment, clipping its rewards to the interval `[min, max]`
+    ///
+    /// **Panics** if `min > max`
+    pub fn new(env: E, min: f32, max: f32) -> Self {
+        assert!(min <= max, "min must not exceed max");
+        Self { env, min, max }
+    }
+}
+
+/// Multiplies rewards by a constant factor
+#[derive(Debug, Clone)]
+pub struct ScaleReward<E: Environment> {
+    env: E,
+    scale: f32,
+}
+
+impl<E: Environment> ScaleReward<E> {
+    /// Wrap an environment, multiplying its rewards by `scale`
+    pub fn new(env: E, scale: f32) -> Self {
+        Self { env, scale }
+    }
+}
+
+impl<E: Environment> Environment for ClipReward<E> {
+    type State = E::State;
+    type Action = E::Action;
+
+    fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
+        let mut step = self.env.step(action);
+        step.reward = step.reward.clamp(self.min, self.max);
+        step
+    }
+
+    fn reset(&mut self) -> Self::State {
+        self.env.reset()
+    }
+
+    fn random_action(&mut self) -> Self::Action {
+        self.env.random_action()
+    }
+
+    fn seed(&mut self, seed: u64) {
+        self.env.seed(seed)
+    }
+
+    fn is_active(&self) -> bool {
+        self.env.is_active()
+    }
+
+    fn report(&mut self) -> Option<&mut Report> {
+        self.env.report()
+    }
+}
+
+impl<E: Environment> Environment for ScaleReward<E> {
+    type State = E::State;
+    type Action = E::Action;
+
+    fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
+        let mut step = self.env.step(action);
+        step.reward *= self.scale;
+        step
+    }
+
+    fn reset(&mut self) -> Self::State {
+        self.env.reset()
+    }
+
+    fn random_action(&mut self) -> Self::Action {
+        self.env.random_action()
+    }
+
+    fn seed(&mut self, seed: u64) {
+        self.env.seed(seed)
+    }
+
+    fn is_active(&self) -> bool {
+        self.env.is_active()
+    }
+
+    fn report(&mut self) -> Option<&mut Report> {
+        self.env.report()
+    }
+}
+
+impl<E: Environment> Wrapper for ClipReward<E> {
+    type Inner = E;
+
+    fn inner(&self) -> &E {
+        &self.env
+    }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.env
+    }
+
+    fn into_inner(self) -> E {
+        self.env
+    }
+}
+
+impl<E: Environment> Wrapper for ScaleReward<E> {
+    type Inner = E;
+
+    fn inner(&self) -> &E {
+        &self.env
+    }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.env
+    }
+
+    fn into_inner(self) -> E {
+        self.env
+    }
+}
+
+impl<E: DiscreteActionSpace> DiscreteActionSpace for ClipReward<E> {
+    fn actions(&self) -> Vec<Self::Action> {
+        self.env.actions()
+    }
+}
+
+impl<E: MaskedActionSpace> MaskedActionSpace for ClipReward<E> {
+    fn num_actions(&self) -> usize {
+        self.env.num_actions()
+    }
+
+    fn action_mask(&self) -> Vec<bool>
+    where
+        Self::Action: Into<usize>,
+    {
+        self.env.action_mask()
+    }
+}
+
+impl<E: DiscreteActionSpace> DiscreteActionSpace for ScaleReward<E> {
+    fn actions(&self) -> Vec<Self::Action> {
+        self.env.actions()
+    }
+}
+
+impl<E: MaskedActionSpace> MaskedActionSpace for ScaleReward<E> {
+    fn num_actions(&self) -> usize {
+        self.env.num_actions()
+    }
+
+    fn action_mask(&self) -> Vec<bool>
+    where
+        Self::Action: Into<usize>,
+    {
+        self.env.action_mask()
+    }
+}
+
+impl<E: DiscreteStateSpace> DiscreteStateSpace for ClipReward<E> {
+    fn states(&self) -> Vec<Self::State> {
+        self.env.states()
+    }
+}
+
+impl<E: DiscreteStateSpace> DiscreteStateSpace for ScaleReward<E> {
+    fn states(&self) -> Vec<Self::State> {
+        self.env.states()
+    }
+}
+
+impl<E: Spaces> Spaces for ClipReward<E> {
+    fn observation_space(&self) -> Space {
+        self.env.observation_space()
+    }
+
+    fn action_space(&self) -> Space {
+        self.env.action_space()
+    }
+}
+
+impl<E: Spaces> Spaces for ScaleReward<E> {
+    fn observation_space(&self) -> Space {
+        self.env.observation_space()
+    }
+
+    fn action_space(&self) -> Space {
+        self.env.action_space()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrappers::tests::CounterEnv;
+
+    use super::*;
+
+    #[test]
+    fn reward_wrappers() {
+        let mut env = ScaleReward::new(ClipReward::new(CounterEnv::new(10), -1.0, 1.0), 0.5);
+        env.reset();
+        assert_eq!(env.step(5.0).reward, 0.5, "Reward clipped then scaled");
+        assert_eq!(
+            env.step(-0.5).reward,
+            -0.25,
+            "Reward within bounds is only scaled"
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{
+    env::{
+        DiscreteActionSpace, DiscreteStateSpace, Environment, MaskedActionSpace, Report, Spaces,
+        StepResult,
+    },
+    space::Space,
+};
+
+use super::Wrapper;
+
+/// Truncates episodes after a maximum number of steps
+///
+/// When the limit is reached without reaching a terminal state, the step is marked as
+/// [truncated](StepResult::truncated) so that agents can still bootstrap from the final state.
+#[derive(Debug, Clone)]
+pub struct TimeLimit<E: Environment> {
+    env: E,
+    max_steps: usize,
+    steps: usize,
+}
+
+impl<E: Environment> TimeLimit<E> {
+    /// Wrap an environment, truncating episodes after `max_steps` steps
+    ///
+    /// **Panics** if `max_steps` is 0
+    pub fn new(env: E, max_steps: usize) -> Self {
+        assert!(max_steps > 0, "max_steps must be greater than 0");
+        Self {
+            env,
+            max_steps,
+            steps: 0,
+        }
+    }
+
+    /// The number of steps taken in the current episode
+    pub fn elapsed_steps(&self) -> usize {
+        self.steps
+    }
+}
+
+impl<E: Environment> Environment for TimeLimit<E> {
+    type State = E::State;
+    type Action = E::Action;
+
+    fn step(&mut self, action: Self::Action) -> StepResult<Self::State> {
+        self.steps += 1;
+        let mut step = self.env.step(action);
+        if self.steps >= self.max_steps && !step.is_terminated() {
+            step.truncated = true;
+        }
+        step
+    }
+
+    fn reset(&mut self) -> Self::State {
+        self.steps = 0;
+        self.env.reset()
+    }
+
+    fn random_action(&mut self) -> Self::Action {
+        self.env.random_action()
+    }
+
+    fn seed(&mut self, seed: u64) {
+        self.env.seed(seed)
+    }
+
+    fn is_active(&self) -> bool {
+        self.env.is_active() && self.steps < self.max_steps
+    }
+
+    fn report(&mut self) -> Option<&mut Report> {
+        self.env.report()
+    }
+}
+
+impl<E: Environment> Wrapper for TimeLimit<E> {
+    type Inner = E;
+
+    fn inner(&self) -> &E {
+        &self.env
+    }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.env
+    }
+
+    fn into_inner(self) -> E {
+        self.env
+    }
+}
+
+impl<E: DiscreteActionSpace> DiscreteActionSpace for TimeLimit<E> {
+    fn actions(&self) -> Vec<Self::Action> {
+        self.env.actions()
+    }
+}
+
+impl<E: MaskedActionSpace> MaskedActionSpace for TimeLimit<E> {
+    fn num_actions(&self) -> usize {
+        self.env.num_actions()
+    }
+
+    fn action_mask(&self) -> Vec<bool>
+    where
+        Self::Action: Into<usize>,
+    {
+        self.env.action_mask()
+    }
+}
+
+impl<E: DiscreteStateSpace> DiscreteStateSpace for TimeLimit<E> {
+    fn states(&self) -> Vec<Self::State> {
+        self.env.states()
+    }
+}
+
+impl<E: Spaces> Spaces for TimeLimit<E> {
+    fn observation_space(&self) -> Space {
+        self.env.observation_space()
+    }
+
+    fn action_space(&self) -> Space {
+        self.env.action_space()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrappers::tests::CounterEnv;
+
+    use super::*;
+
+    #[test]
+    fn time_limit_truncates() {
+        let mut env = TimeLimit::new(CounterEnv::new(10), 3);
+        env.reset();
+        assert!(!env.step(1.0).is_done(), "Step 1 continues");
+        assert!(!env.step(1.0).is_done(), "Step 2 continues");
+        let step = env.step(1.0);
+        assert!(
+            step.truncated && !step.is_terminated(),
+            "Step 3 is truncated"
+        );
+
+        env.reset();
+        assert_eq!(env.elapsed_steps(), 0, "Reset clears the step counter");
+
+        let mut env = TimeLimit::new(CounterEnv::new(2), 2);
+        env.reset();
+        env.step(1.0);
+        let step = env.step(1.0);
+        assert!(
+            step.is_terminated() && !step.truncated,
+            "Termination takes precedence over truncation"
+        );
+    }
+}